@@ -1,4 +1,5 @@
 use alloy_eips::eip1559::BaseFeeParams;
+use core::time::Duration;
 use reth_primitives_traits::constants::{
     BASE_SEPOLIA_EIP1559_DEFAULT_ELASTICITY_MULTIPLIER,
     OP_MAINNET_EIP1559_BASE_FEE_MAX_CHANGE_DENOMINATOR_CANYON,
@@ -9,6 +10,9 @@ use reth_primitives_traits::constants::{
     OP_SEPOLIA_EIP1559_DEFAULT_ELASTICITY_MULTIPLIER,
 };
 
+/// The time between two blocks on OP Stack chains.
+pub(crate) const OP_BLOCK_TIME: Duration = Duration::from_secs(2);
+
 /// Get the base fee parameters for Base Sepolia.
 pub(crate) const BASE_SEPOLIA_BASE_FEE_PARAMS: BaseFeeParams = BaseFeeParams {
     max_change_denominator: OP_SEPOLIA_EIP1559_DEFAULT_BASE_FEE_MAX_CHANGE_DENOMINATOR,
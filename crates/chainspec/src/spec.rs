@@ -11,6 +11,7 @@ use alloy_chains::{Chain, ChainKind, NamedChain};
 use alloy_genesis::Genesis;
 use alloy_primitives::{address, b256, Address, BlockNumber, B256, U256};
 use alloy_trie::EMPTY_ROOT_HASH;
+use core::time::Duration;
 use derive_more::From;
 use once_cell::sync::Lazy;
 use reth_ethereum_forks::{
@@ -21,7 +22,7 @@ use reth_network_peers::NodeRecord;
 use reth_primitives_traits::{
     constants::{
         EIP1559_INITIAL_BASE_FEE, EMPTY_OMMER_ROOT_HASH, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS,
-        EMPTY_WITHDRAWALS,
+        EMPTY_WITHDRAWALS, SLOT_DURATION,
     },
     Header, SealedHeader,
 };
@@ -32,7 +33,8 @@ use std::sync::Arc;
 #[cfg(feature = "optimism")]
 use crate::constants::optimism::{
     BASE_SEPOLIA_BASE_FEE_PARAMS, BASE_SEPOLIA_CANYON_BASE_FEE_PARAMS, OP_BASE_FEE_PARAMS,
-    OP_CANYON_BASE_FEE_PARAMS, OP_SEPOLIA_BASE_FEE_PARAMS, OP_SEPOLIA_CANYON_BASE_FEE_PARAMS,
+    OP_BLOCK_TIME, OP_CANYON_BASE_FEE_PARAMS, OP_SEPOLIA_BASE_FEE_PARAMS,
+    OP_SEPOLIA_CANYON_BASE_FEE_PARAMS,
 };
 pub use alloy_eips::eip1559::BaseFeeParams;
 #[cfg(feature = "optimism")]
@@ -64,6 +66,7 @@ pub static MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        block_time: SLOT_DURATION,
         prune_delete_limit: 3500,
     }
     .into()
@@ -88,6 +91,7 @@ pub static GOERLI: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        block_time: SLOT_DURATION,
         prune_delete_limit: 1700,
     }
     .into()
@@ -112,6 +116,7 @@ pub static SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        block_time: SLOT_DURATION,
         prune_delete_limit: 1700,
     }
     .into()
@@ -134,6 +139,7 @@ pub static HOLESKY: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             b256!("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"),
         )),
         base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        block_time: SLOT_DURATION,
         prune_delete_limit: 1700,
     }
     .into()
@@ -181,6 +187,7 @@ pub static OP_MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             ]
             .into(),
         ),
+        block_time: OP_BLOCK_TIME,
         prune_delete_limit: 1700,
         ..Default::default()
     }
@@ -206,6 +213,7 @@ pub static OP_SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             ]
             .into(),
         ),
+        block_time: OP_BLOCK_TIME,
         prune_delete_limit: 1700,
         ..Default::default()
     }
@@ -231,6 +239,7 @@ pub static BASE_SEPOLIA: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             ]
             .into(),
         ),
+        block_time: OP_BLOCK_TIME,
         prune_delete_limit: 1700,
         ..Default::default()
     }
@@ -256,6 +265,7 @@ pub static BASE_MAINNET: Lazy<Arc<ChainSpec>> = Lazy::new(|| {
             ]
             .into(),
         ),
+        block_time: OP_BLOCK_TIME,
         prune_delete_limit: 1700,
         ..Default::default()
     }
@@ -338,6 +348,9 @@ pub struct ChainSpec {
     /// The parameters that configure how a block's base fee is computed
     pub base_fee_params: BaseFeeParamsKind,
 
+    /// The expected time between two consecutive blocks.
+    pub block_time: Duration,
+
     /// The delete limit for pruner, per block. In the actual pruner run it will be multiplied by
     /// the amount of blocks between pruner runs to account for the difference in amount of new
     /// data coming in.
@@ -354,6 +367,7 @@ impl Default for ChainSpec {
             hardforks: Default::default(),
             deposit_contract: Default::default(),
             base_fee_params: BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+            block_time: SLOT_DURATION,
             prune_delete_limit: MAINNET.prune_delete_limit,
         }
     }
@@ -838,6 +852,8 @@ impl From<Genesis> for ChainSpec {
             deposit_contract,
             #[cfg(feature = "optimism")]
             base_fee_params: optimism_genesis_info.base_fee_params,
+            #[cfg(feature = "optimism")]
+            block_time: OP_BLOCK_TIME,
             ..Default::default()
         }
     }
@@ -22,17 +22,28 @@ pub struct GasPriceOracleArgs {
     #[arg(long = "gpo.maxprice", default_value_t = DEFAULT_MAX_GAS_PRICE.to())]
     pub max_price: u64,
 
+    /// Minimum transaction priority fee(or gasprice before London Fork) to be recommended by gpo
+    ///
+    /// Defaults to 1 gwei, or 0.001 gwei on OP chains.
+    #[arg(long = "gpo.minpriorityfee")]
+    pub min_suggested_priority_fee: Option<u64>,
+
     /// The percentile of gas prices to use for the estimate
-    #[arg(long = "gpo.percentile", default_value_t = DEFAULT_GAS_PRICE_PERCENTILE)]
+    #[arg(
+        long = "gpo.percentile",
+        default_value_t = DEFAULT_GAS_PRICE_PERCENTILE,
+        value_parser = clap::value_parser!(u32).range(..=100)
+    )]
     pub percentile: u32,
 }
 
 impl GasPriceOracleArgs {
     /// Returns a [`GasPriceOracleConfig`] from the arguments.
     pub fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
-        let Self { blocks, ignore_price, max_price, percentile } = self;
+        let Self { blocks, ignore_price, max_price, min_suggested_priority_fee, percentile } = self;
         GasPriceOracleConfig {
             max_price: Some(U256::from(*max_price)),
+            min_suggested_priority_fee: min_suggested_priority_fee.map(U256::from),
             ignore_price: Some(U256::from(*ignore_price)),
             percentile: *percentile,
             blocks: *blocks,
@@ -47,6 +58,7 @@ impl Default for GasPriceOracleArgs {
             blocks: DEFAULT_GAS_PRICE_BLOCKS,
             ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
             max_price: DEFAULT_MAX_GAS_PRICE.to(),
+            min_suggested_priority_fee: None,
             percentile: DEFAULT_GAS_PRICE_PERCENTILE,
         }
     }
@@ -72,11 +84,44 @@ mod tests {
                 blocks: DEFAULT_GAS_PRICE_BLOCKS,
                 ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
                 max_price: DEFAULT_MAX_GAS_PRICE.to(),
+                min_suggested_priority_fee: None,
                 percentile: DEFAULT_GAS_PRICE_PERCENTILE,
             }
         );
     }
 
+    #[test]
+    fn test_parse_gpo_percentile() {
+        let args =
+            CommandParser::<GasPriceOracleArgs>::parse_from(["reth", "--gpo.percentile", "90"])
+                .args;
+        assert_eq!(args.gas_price_oracle_config().percentile, 90);
+
+        let res = CommandParser::<GasPriceOracleArgs>::try_parse_from([
+            "reth",
+            "--gpo.percentile",
+            "101",
+        ]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_parse_gpo_min_priority_fee() {
+        let args = CommandParser::<GasPriceOracleArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.gas_price_oracle_config().min_suggested_priority_fee, None);
+
+        let args = CommandParser::<GasPriceOracleArgs>::parse_from([
+            "reth",
+            "--gpo.minpriorityfee",
+            "100",
+        ])
+        .args;
+        assert_eq!(
+            args.gas_price_oracle_config().min_suggested_priority_fee,
+            Some(U256::from(100))
+        );
+    }
+
     #[test]
     fn gpo_args_default_sanity_test() {
         let default_args = GasPriceOracleArgs::default();
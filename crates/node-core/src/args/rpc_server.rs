@@ -4,6 +4,7 @@ use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr},
     path::PathBuf,
+    time::Duration,
 };
 
 use alloy_rpc_types_engine::JwtSecret;
@@ -11,6 +12,7 @@ use clap::{
     builder::{PossibleValue, RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
+use humantime::parse_duration;
 use rand::Rng;
 use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
 
@@ -138,6 +140,25 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-tracing-requests", alias = "rpc-max-tracing-requests", value_name = "COUNT", default_value_t = constants::default_max_tracing_requests())]
     pub rpc_max_tracing_requests: usize,
 
+    /// Maximum number of concurrent blocking tasks of the `eth` API, like `eth_call` or tracing.
+    #[arg(long = "rpc.max-blocking-tasks", value_name = "COUNT", default_value_t = constants::DEFAULT_MAX_BLOCKING_TASKS)]
+    pub rpc_max_blocking_tasks: usize,
+
+    /// Maximum time a request waits for the result of a blocking task of the `eth` API, like
+    /// `eth_call` or tracing, before it fails.
+    ///
+    /// The timed out task itself keeps running. Unbounded by default.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --rpc.blocking-task-timeout 30s
+    #[arg(
+        long = "rpc.blocking-task-timeout",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub rpc_blocking_task_timeout: Option<Duration>,
+
     /// Maximum number of blocks that could be scanned per filter request. (0 = entire chain)
     #[arg(long = "rpc.max-blocks-per-filter", alias = "rpc-max-blocks-per-filter", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_BLOCKS_PER_FILTER))]
     pub rpc_max_blocks_per_filter: ZeroAsNoneU64,
@@ -146,6 +167,20 @@ pub struct RpcServerArgs {
     #[arg(long = "rpc.max-logs-per-response", alias = "rpc-max-logs-per-response", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64))]
     pub rpc_max_logs_per_response: ZeroAsNoneU64,
 
+    /// Maximum time an `eth_getLogs` request for a range of blocks may take before it fails.
+    ///
+    /// The error includes the range of blocks that were queried in time. Unbounded by default.
+    ///
+    /// Parses strings using [`humantime::parse_duration`]
+    /// --rpc.max-logs-query-duration 10s
+    #[arg(
+        long = "rpc.max-logs-query-duration",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub rpc_max_logs_query_duration: Option<Duration>,
+
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     #[arg(
         long = "rpc.gascap",
@@ -156,6 +191,86 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Maximum gas limit for `eth_call`, `eth_callMany` and `eth_createAccessList`.
+    ///
+    /// Defaults to `--rpc.gascap`.
+    #[arg(
+        long = "rpc.gascap.call",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_call_gas_cap: Option<u64>,
+
+    /// Maximum gas limit for `eth_estimateGas`.
+    ///
+    /// Defaults to `--rpc.gascap`.
+    #[arg(
+        long = "rpc.gascap.estimate",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_estimate_gas_cap: Option<u64>,
+
+    /// Maximum gas limit for `debug_trace*` and `trace_*` calls.
+    ///
+    /// Defaults to `--rpc.gascap`.
+    #[arg(
+        long = "rpc.gascap.trace",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub rpc_trace_gas_cap: Option<u64>,
+
+    /// Maximum number of blocks that can be simulated in a single `eth_simulateV1` request.
+    #[arg(long = "rpc.max-simulate-blocks", value_name = "COUNT", default_value_t = constants::DEFAULT_MAX_SIMULATE_BLOCKS)]
+    pub rpc_max_simulate_blocks: u64,
+
+    /// Maximum number of `eth_call` outputs on top of historical blocks to cache.
+    ///
+    /// The cache is disabled by default.
+    #[arg(long = "rpc.call-cache-size", value_name = "COUNT", default_value_t = 0)]
+    pub rpc_call_cache_size: u32,
+
+    /// Maximum number of locally built pending blocks to cache, keyed by their parent block.
+    #[arg(
+        long = "rpc.pending-block-cache-size",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u32>::new().range(1..),
+        default_value_t = constants::DEFAULT_PENDING_BLOCK_CACHE_SIZE
+    )]
+    pub rpc_pending_block_cache_size: u32,
+
+    /// Include transactions submitted as private in the locally built pending block.
+    ///
+    /// By default private transactions are excluded, so the pending block doesn't leak them.
+    #[arg(long = "rpc.pending-block-include-private", default_value_t = false)]
+    pub rpc_pending_block_include_private: bool,
+
+    /// Maximum number of blocks that can be requested in a single `eth_feeHistory` request.
+    ///
+    /// Larger requests are rejected. If unset, they are truncated to the maximum fee history.
+    #[arg(long = "rpc.max-fee-history-blocks", value_name = "COUNT")]
+    pub rpc_max_fee_history_blocks: Option<u64>,
+
+    /// Path of the snapshot of the fee history cache, which is loaded on startup and periodically
+    /// rewritten, so the cache doesn't need to be rebuilt after a restart.
+    ///
+    /// Defaults to `fee-history-snapshot.json` in the datadir.
+    #[arg(long = "rpc.fee-history-snapshot", value_name = "PATH")]
+    pub rpc_fee_history_snapshot: Option<PathBuf>,
+
+    /// Maximum number of distinct storage slots that can be proven in a single `eth_getProof`
+    /// request.
+    ///
+    /// Repeated slots are only counted once, larger requests are rejected.
+    #[arg(
+        long = "rpc.max-proof-storage-slots",
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        default_value_t = constants::DEFAULT_MAX_PROOF_STORAGE_SLOTS
+    )]
+    pub rpc_max_proof_storage_slots: usize,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -283,9 +398,22 @@ impl Default for RpcServerArgs {
             rpc_max_subscriptions_per_connection: RPC_DEFAULT_MAX_SUBS_PER_CONN.into(),
             rpc_max_connections: RPC_DEFAULT_MAX_CONNECTIONS.into(),
             rpc_max_tracing_requests: constants::default_max_tracing_requests(),
+            rpc_max_blocking_tasks: constants::DEFAULT_MAX_BLOCKING_TASKS,
+            rpc_blocking_task_timeout: None,
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
+            rpc_max_logs_query_duration: None,
             rpc_gas_cap: constants::gas_oracle::RPC_DEFAULT_GAS_CAP,
+            rpc_call_gas_cap: None,
+            rpc_estimate_gas_cap: None,
+            rpc_trace_gas_cap: None,
+            rpc_max_simulate_blocks: constants::DEFAULT_MAX_SIMULATE_BLOCKS,
+            rpc_call_cache_size: 0,
+            rpc_pending_block_cache_size: constants::DEFAULT_PENDING_BLOCK_CACHE_SIZE,
+            rpc_pending_block_include_private: false,
+            rpc_max_fee_history_blocks: None,
+            rpc_fee_history_snapshot: None,
+            rpc_max_proof_storage_slots: constants::DEFAULT_MAX_PROOF_STORAGE_SLOTS,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
         }
@@ -1,7 +1,7 @@
 use clap::Args;
 use reth_rpc_server_types::constants::cache::{
     DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN,
-    DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_MISSING_ENTRIES_MAX_LEN, DEFAULT_MISSING_ENTRY_TTL_MS, DEFAULT_RECEIPT_CACHE_MAX_LEN,
 };
 
 /// Parameters to configure RPC state cache.
@@ -35,6 +35,25 @@ pub struct RpcStateCacheArgs {
         default_value_t = DEFAULT_CONCURRENT_DB_REQUESTS,
     )]
     pub max_concurrent_db_requests: usize,
+
+    /// Don't warm the cache with the blocks and receipts of new canonical blocks.
+    #[arg(long = "rpc-cache.no-cache-new-blocks")]
+    pub no_cache_new_blocks: bool,
+
+    /// Max number of recently missing blocks and receipts that are remembered.
+    #[arg(
+        long = "rpc-cache.max-missing-entries",
+        default_value_t = DEFAULT_MISSING_ENTRIES_MAX_LEN,
+    )]
+    pub max_missing_entries: u32,
+
+    /// Time in milliseconds a missing block or receipts are remembered without asking the
+    /// database again, 0 disables it.
+    #[arg(
+        long = "rpc-cache.missing-entry-ttl-ms",
+        default_value_t = DEFAULT_MISSING_ENTRY_TTL_MS,
+    )]
+    pub missing_entry_ttl_ms: u64,
 }
 
 impl Default for RpcStateCacheArgs {
@@ -44,6 +63,9 @@ impl Default for RpcStateCacheArgs {
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            no_cache_new_blocks: false,
+            max_missing_entries: DEFAULT_MISSING_ENTRIES_MAX_LEN,
+            missing_entry_ttl_ms: DEFAULT_MISSING_ENTRY_TTL_MS,
         }
     }
 }
@@ -330,6 +330,13 @@ impl<D> ChainPath<D> {
         self.data_dir().join("txpool-transactions-backup.rlp")
     }
 
+    /// Returns the path to the snapshot of the RPC fee history cache for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/fee-history-snapshot.json`
+    pub fn fee_history_snapshot(&self) -> PathBuf {
+        self.data_dir().join("fee-history-snapshot.json")
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
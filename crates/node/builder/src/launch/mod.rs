@@ -27,6 +27,7 @@ use reth_primitives::format_ether;
 use reth_provider::providers::BlockchainProvider;
 use reth_rpc_engine_api::EngineApi;
 use reth_rpc_types::engine::ClientVersionV1;
+use reth_stages::{PipelineEvent, StageId};
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use reth_transaction_pool::TransactionPool;
@@ -230,6 +231,7 @@ where
         };
 
         let pipeline_events = pipeline.events();
+        let mut sync_events = pipeline.events();
 
         let initial_target = ctx.node_config().debug.tip;
 
@@ -322,6 +324,47 @@ where
             rpc_registry.eth_api().with_dev_accounts();
         }
 
+        // report the pipeline progress to `eth_syncing`
+        let eth_api = rpc_registry.eth_api();
+        ctx.task_executor().spawn(Box::pin(async move {
+            let mut current_stage = 0;
+            while let Some(event) = sync_events.next().await {
+                match event {
+                    PipelineEvent::Prepare {
+                        pipeline_stages_progress, checkpoint, target, ..
+                    } => {
+                        // the first stage can be executed multiple times per run
+                        if pipeline_stages_progress.current == 1 && current_stage != 1 {
+                            eth_api.set_starting_block(checkpoint.unwrap_or_default().block_number);
+                        }
+                        if let Some(target) = target {
+                            eth_api.set_highest_block(target);
+                        }
+                        current_stage = pipeline_stages_progress.current;
+                    }
+                    // the pipeline changes the canonical chain without notifications
+                    PipelineEvent::Ran { stage_id: StageId::Execution, result, .. } => {
+                        eth_api.set_current_block(result.checkpoint.block_number);
+                        eth_api.chain_info_cache().clear();
+                    }
+                    PipelineEvent::Unwound { stage_id: StageId::Execution, result } => {
+                        eth_api.set_current_block(result.checkpoint.block_number);
+                        eth_api.chain_info_cache().clear();
+                    }
+                    // the run is complete once the last stage is done, so its target is no longer
+                    // ahead of the chain
+                    PipelineEvent::Ran { pipeline_stages_progress, result, .. }
+                        if result.done &&
+                            pipeline_stages_progress.current ==
+                                pipeline_stages_progress.total =>
+                    {
+                        eth_api.set_highest_block(result.checkpoint.block_number);
+                    }
+                    _ => {}
+                }
+            }
+        }));
+
         // Run consensus engine to completion
         let (tx, rx) = oneshot::channel();
         info!(target: "reth::cli", "Starting consensus engine");
@@ -262,7 +262,15 @@ where
     let RpcHooks { on_rpc_started, extend_rpc_modules } = hooks;
 
     let auth_config = config.rpc.auth_server_config(jwt_secret)?;
-    let module_config = config.rpc.transport_rpc_module_config();
+    let mut module_config = config.rpc.transport_rpc_module_config();
+    if let Some(module_config) = module_config.config_mut() {
+        // the fee history snapshot is stored in the datadir, unless configured otherwise
+        module_config
+            .eth_mut()
+            .fee_history_cache
+            .snapshot_path
+            .get_or_insert_with(|| config.datadir().fee_history_snapshot());
+    }
     debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
     let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
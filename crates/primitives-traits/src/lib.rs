@@ -32,6 +32,8 @@ mod error;
 pub use error::{GotExpected, GotExpectedBoxed};
 
 mod log;
+#[cfg(feature = "abi")]
+pub use log::LogDecodeExt;
 pub use log::{logs_bloom, Log, LogData};
 
 mod storage;
@@ -13,6 +13,22 @@ pub fn logs_bloom<'a>(logs: impl IntoIterator<Item = &'a Log>) -> Bloom {
     bloom
 }
 
+/// Extension trait for decoding the topics and data of a [`Log`] into a typed event.
+#[cfg(feature = "abi")]
+pub trait LogDecodeExt {
+    /// Decodes the indexed topics and the data of the log into the event `E`.
+    ///
+    /// The first topic must be the signature hash of the event, unless the event is anonymous.
+    fn data_as<E: alloy_sol_types::SolEvent>(&self) -> alloy_sol_types::Result<E>;
+}
+
+#[cfg(feature = "abi")]
+impl LogDecodeExt for Log {
+    fn data_as<E: alloy_sol_types::SolEvent>(&self) -> alloy_sol_types::Result<E> {
+        E::decode_log_data(&self.data, true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::{Address, Bytes, Log as AlloyLog, B256};
@@ -50,6 +66,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn roundtrip_conversion_preserves_topics_and_data() {
+        // five topics is more than any `LOG` opcode emits, but must survive the conversion too
+        for num_topics in [0, 1, 4, 5] {
+            let log = Log {
+                address: Address::with_last_byte(1),
+                topics: (0..num_topics).map(|i| B256::with_last_byte(i as u8 + 1)).collect(),
+                data: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            };
+
+            let alloy_log = AlloyLog::from(log.clone());
+            assert_eq!(alloy_log.topics(), log.topics.as_slice());
+            assert_eq!(alloy_log.data.data, log.data);
+
+            assert_eq!(Log::from(alloy_log), log);
+        }
+    }
+
+    #[test]
+    fn checked_constructor_limits_topics() {
+        // `LOG0` to `LOG4` emit at most four topics
+        for num_topics in 0..=4 {
+            let topics = vec![B256::ZERO; num_topics];
+            let log = AlloyLog::new(Address::ZERO, topics, Bytes::new()).unwrap();
+            assert!(log.is_valid());
+        }
+        assert!(AlloyLog::new(Address::ZERO, vec![B256::ZERO; 5], Bytes::new()).is_none());
+    }
+
+    #[cfg(feature = "abi")]
+    #[test]
+    fn decode_erc20_transfer() {
+        use super::LogDecodeExt;
+        use alloy_primitives::U256;
+        use alloy_sol_types::{sol, SolEvent};
+
+        sol! {
+            event Transfer(address indexed from, address indexed to, uint256 value);
+            event AnonymousTransfer(address indexed from, address indexed to, uint256 value) anonymous;
+        }
+
+        let from = Address::with_last_byte(1);
+        let to = Address::with_last_byte(2);
+        let log = AlloyLog::new_unchecked(
+            Address::with_last_byte(0xaa),
+            vec![Transfer::SIGNATURE_HASH, from.into_word(), to.into_word()],
+            U256::from(1000).to_be_bytes_vec().into(),
+        );
+
+        let transfer = log.data_as::<Transfer>().unwrap();
+        assert_eq!(transfer.from, from);
+        assert_eq!(transfer.to, to);
+        assert_eq!(transfer.value, U256::from(1000));
+
+        // anonymous events don't have a signature topic
+        let anonymous = AlloyLog::new_unchecked(
+            log.address,
+            vec![from.into_word(), to.into_word()],
+            log.data.data.clone(),
+        );
+        let transfer = anonymous.data_as::<AnonymousTransfer>().unwrap();
+        assert_eq!((transfer.from, transfer.to), (from, to));
+        assert!(anonymous.data_as::<Transfer>().is_err());
+    }
+
     proptest! {
         #[test]
         fn test_roundtrip_conversion_between_log_and_alloy_log(log in arb::<Log>()) {
@@ -53,6 +53,8 @@ pub use header::{Header, HeadersDirection, SealedHeader};
 pub use receipt::{
     gas_spent_by_transactions, Receipt, ReceiptWithBloom, ReceiptWithBloomRef, Receipts,
 };
+#[cfg(feature = "abi")]
+pub use reth_primitives_traits::LogDecodeExt;
 pub use reth_primitives_traits::{
     logs_bloom, Account, Bytecode, GotExpected, GotExpectedBoxed, Log, LogData, Request, Requests,
     StorageEntry, Withdrawal, Withdrawals,
@@ -38,6 +38,11 @@ pub trait DebugApi {
     #[method(name = "getBadBlocks")]
     async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>>;
 
+    /// Returns the reason the most recent attempt to build the local pending block failed, if it
+    /// did.
+    #[method(name = "getPendingBlockBuildError")]
+    async fn pending_block_build_error(&self) -> RpcResult<Option<String>>;
+
     /// Returns the structured logs created during the execution of EVM between two blocks
     /// (excluding start) as a JSON object.
     #[method(name = "traceChain")]
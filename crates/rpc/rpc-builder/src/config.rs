@@ -87,13 +87,26 @@ impl RethRpcServerConfig for RpcServerArgs {
     }
 
     fn eth_config(&self) -> EthConfig {
-        EthConfig::default()
+        let mut config = EthConfig::default()
             .max_tracing_requests(self.rpc_max_tracing_requests)
+            .max_blocking_tasks(self.rpc_max_blocking_tasks)
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .max_simulate_blocks(self.rpc_max_simulate_blocks)
+            .call_cache_size(self.rpc_call_cache_size)
+            .pending_block_cache_size(self.rpc_pending_block_cache_size)
+            .include_private_in_pending(self.rpc_pending_block_include_private)
+            .max_proof_storage_slots(self.rpc_max_proof_storage_slots)
             .state_cache(self.state_cache_config())
-            .gpo_config(self.gas_price_oracle_config())
+            .gpo_config(self.gas_price_oracle_config());
+        config.blocking_task_timeout = self.rpc_blocking_task_timeout;
+        config.max_logs_query_duration = self.rpc_max_logs_query_duration;
+        config.call_gas_cap = self.rpc_call_gas_cap;
+        config.estimate_gas_cap = self.rpc_estimate_gas_cap;
+        config.trace_gas_cap = self.rpc_trace_gas_cap;
+        config.fee_history_cache.snapshot_path.clone_from(&self.rpc_fee_history_snapshot);
+        config
     }
 
     fn state_cache_config(&self) -> EthStateCacheConfig {
@@ -102,6 +115,9 @@ impl RethRpcServerConfig for RpcServerArgs {
             max_receipts: self.rpc_state_cache.max_receipts,
             max_envs: self.rpc_state_cache.max_envs,
             max_concurrent_db_requests: self.rpc_state_cache.max_concurrent_db_requests,
+            cache_new_blocks: !self.rpc_state_cache.no_cache_new_blocks,
+            max_missing_entries: self.rpc_state_cache.max_missing_entries,
+            missing_entry_ttl_ms: self.rpc_state_cache.missing_entry_ttl_ms,
         }
     }
 
@@ -114,7 +130,10 @@ impl RethRpcServerConfig for RpcServerArgs {
     }
 
     fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
-        self.gas_price_oracle.gas_price_oracle_config()
+        GasPriceOracleConfig {
+            max_fee_history_blocks: self.rpc_max_fee_history_blocks,
+            ..self.gas_price_oracle.gas_price_oracle_config()
+        }
     }
 
     fn transport_rpc_module_config(&self) -> TransportRpcModuleConfig {
@@ -214,10 +233,15 @@ impl RethRpcServerConfig for RpcServerArgs {
 
 #[cfg(test)]
 mod tests {
-    use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+    use std::{
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        path::PathBuf,
+        time::Duration,
+    };
 
     use clap::{Args, Parser};
     use reth_node_core::args::RpcServerArgs;
+    use reth_rpc_eth_types::GasCaps;
     use reth_rpc_server_types::{
         constants, constants::gas_oracle::RPC_DEFAULT_GAS_CAP, RethRpcModule, RpcModuleSelection,
     };
@@ -246,6 +270,76 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_gas_caps() {
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.gascap", "1000"]).args;
+        let caps = args.eth_config().gas_caps();
+        assert_eq!(caps, GasCaps::from(1000));
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.gascap",
+            "1000",
+            "--rpc.gascap.call",
+            "100",
+            "--rpc.gascap.trace",
+            "10000",
+        ])
+        .args;
+        let caps = args.eth_config().gas_caps();
+        assert_eq!(caps, GasCaps { call: 100, estimate: 1000, trace: 10000 });
+
+        let args =
+            CommandParser::<RpcServerArgs>::try_parse_from(["reth", "--rpc.gascap.estimate", "0"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_blocking_task_timeout() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.eth_config().blocking_task_timeout, None);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.blocking-task-timeout",
+            "30s",
+        ])
+        .args;
+        assert_eq!(args.eth_config().blocking_task_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_rpc_max_logs_query_duration() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.eth_config().max_logs_query_duration, None);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-logs-query-duration",
+            "10s",
+        ])
+        .args;
+        assert_eq!(args.eth_config().max_logs_query_duration, Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_rpc_fee_history_snapshot() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.eth_config().fee_history_cache.snapshot_path, None);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.fee-history-snapshot",
+            "/tmp/fee-history.json",
+        ])
+        .args;
+        assert_eq!(
+            args.eth_config().fee_history_cache.snapshot_path,
+            Some(PathBuf::from("/tmp/fee-history.json"))
+        );
+    }
+
     #[test]
     fn test_transport_rpc_module_config() {
         let args = CommandParser::<RpcServerArgs>::parse_from([
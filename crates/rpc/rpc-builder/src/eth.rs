@@ -8,17 +8,20 @@ use reth_provider::{
 };
 use reth_rpc::eth::{EthApi, EthFilter, EthFilterConfig, EthPubSub, RawTransactionForwarder};
 use reth_rpc_eth_types::{
-    cache::cache_new_blocks_task, fee_history::fee_history_cache_new_blocks_task, EthStateCache,
-    EthStateCacheConfig, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
-    GasPriceOracleConfig,
+    cache::cache_new_blocks_task, chain_info::chain_info_cache_new_blocks_task,
+    fee_history::fee_history_cache_new_blocks_task, gas_oracle::gas_oracle_new_blocks_task,
+    pending_block::pending_block_cache_new_blocks_task, EthStateCache, EthStateCacheConfig,
+    FeeHistoryCache, FeeHistoryCacheConfig, GasCaps, GasPriceOracle, GasPriceOracleConfig,
 };
 use reth_rpc_server_types::constants::{
-    default_max_tracing_requests, gas_oracle::RPC_DEFAULT_GAS_CAP, DEFAULT_MAX_BLOCKS_PER_FILTER,
-    DEFAULT_MAX_LOGS_PER_RESPONSE,
+    default_max_tracing_requests, gas_oracle::RPC_DEFAULT_GAS_CAP, DEFAULT_MAX_BLOCKING_TASKS,
+    DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE, DEFAULT_MAX_PROOF_STORAGE_SLOTS,
+    DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PENDING_BLOCK_CACHE_SIZE,
 };
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner};
 use reth_transaction_pool::TransactionPool;
 use serde::{Deserialize, Serialize};
+use tracing::error;
 
 use crate::RpcModuleConfig;
 
@@ -111,6 +114,15 @@ where
         // Initialize the Eth API
         let api = self.init_api(&cache, gas_oracle, &fee_history_cache, &blocking_task_pool);
 
+        // Spawn background task for the gas price oracle
+        self.spawn_gas_oracle_task(&api);
+
+        // Spawn background task for the pending block cache
+        self.spawn_pending_block_task(&api);
+
+        // Spawn background task for the cached chain info
+        self.spawn_chain_info_task(&api);
+
         // Initialize the filter
         let filter = self.init_filter(&cache);
 
@@ -179,6 +191,51 @@ where
         )
     }
 
+    /// Spawns a task that feeds new canonical blocks into the gas price oracle of the given API.
+    fn spawn_gas_oracle_task(&self, api: &EthApi<Provider, Pool, Network, EvmConfig>) {
+        let new_canonical_blocks = self.eth_handlers_config.events.canonical_state_stream();
+        let api = api.clone();
+
+        self.eth_handlers_config.executor.spawn_critical(
+            "gas price oracle canonical blocks task",
+            Box::pin(async move {
+                gas_oracle_new_blocks_task(api.gas_oracle(), new_canonical_blocks).await;
+            }),
+        );
+    }
+
+    /// Spawns a task that evicts the cached pending blocks of the given API that are realized by
+    /// new canonical blocks.
+    fn spawn_pending_block_task(&self, api: &EthApi<Provider, Pool, Network, EvmConfig>) {
+        let new_canonical_blocks = self.eth_handlers_config.events.canonical_state_stream();
+        let api = api.clone();
+
+        self.eth_handlers_config.executor.spawn_critical(
+            "pending block cache canonical blocks task",
+            Box::pin(async move {
+                pending_block_cache_new_blocks_task(
+                    api.pending_block_cache(),
+                    new_canonical_blocks,
+                )
+                .await;
+            }),
+        );
+    }
+
+    /// Spawns a task that caches the tip of the canonical chain for the given API.
+    fn spawn_chain_info_task(&self, api: &EthApi<Provider, Pool, Network, EvmConfig>) {
+        let new_canonical_blocks = self.eth_handlers_config.events.canonical_state_stream();
+        let api = api.clone();
+
+        self.eth_handlers_config.executor.spawn_critical(
+            "chain info canonical blocks task",
+            Box::pin(async move {
+                chain_info_cache_new_blocks_task(api.chain_info_cache(), new_canonical_blocks)
+                    .await;
+            }),
+        );
+    }
+
     /// Initializes the `BlockingTaskPool`.
     fn init_blocking_task_pool(&self) -> BlockingTaskPool {
         BlockingTaskPool::build().expect("failed to build tracing pool")
@@ -192,19 +249,30 @@ where
         fee_history_cache: &FeeHistoryCache,
         blocking_task_pool: &BlockingTaskPool,
     ) -> EthApi<Provider, Pool, Network, EvmConfig> {
-        EthApi::with_spawner(
+        let api = EthApi::with_spawner(
             self.eth_handlers_config.provider.clone(),
             self.eth_handlers_config.pool.clone(),
             self.eth_handlers_config.network.clone(),
             cache.clone(),
             gas_oracle,
-            self.rpc_config.eth.rpc_gas_cap,
+            self.rpc_config.eth.gas_caps(),
+            self.rpc_config.eth.max_simulate_blocks,
+            self.rpc_config.eth.pending_block_cache_size,
             Box::new(self.eth_handlers_config.executor.clone()),
             blocking_task_pool.clone(),
             fee_history_cache.clone(),
             self.eth_handlers_config.evm_config.clone(),
             self.eth_handlers_config.eth_raw_transaction_forwarder.clone(),
-        )
+        );
+        api.set_include_private_in_pending(self.rpc_config.eth.include_private_in_pending);
+        api.set_max_proof_storage_slots(self.rpc_config.eth.max_proof_storage_slots);
+        api.set_max_blocking_tasks(self.rpc_config.eth.max_blocking_tasks);
+        api.set_blocking_task_timeout(self.rpc_config.eth.blocking_task_timeout);
+        api.set_call_cache_size(self.rpc_config.eth.call_cache_size);
+        if let Err(err) = api.check_genesis() {
+            error!(target: "rpc::eth", %err, "Database doesn't belong to the configured chain");
+        }
+        api
     }
 
     /// Initializes the `EthFilter`.
@@ -239,14 +307,46 @@ pub struct EthConfig {
     pub gas_oracle: GasPriceOracleConfig,
     /// The maximum number of tracing calls that can be executed in concurrently.
     pub max_tracing_requests: usize,
+    /// The maximum number of blocking tasks, like `eth_call` or tracing, of the `eth` API that
+    /// are executed concurrently.
+    pub max_blocking_tasks: usize,
+    /// How long a request waits for the result of a blocking task of the `eth` API.
+    ///
+    /// Defaults to no limit.
+    pub blocking_task_timeout: Option<std::time::Duration>,
     /// Maximum number of blocks that could be scanned per filter request in `eth_getLogs` calls.
     pub max_blocks_per_filter: u64,
     /// Maximum number of logs that can be returned in a single response in `eth_getLogs` calls.
     pub max_logs_per_response: usize,
+    /// Maximum time an `eth_getLogs` call for a range of blocks may take.
+    ///
+    /// Defaults to no limit.
+    pub max_logs_query_duration: Option<std::time::Duration>,
     /// Gas limit for `eth_call` and call tracing RPC methods.
     ///
     /// Defaults to [`RPC_DEFAULT_GAS_CAP`]
     pub rpc_gas_cap: u64,
+    /// Gas limit for `eth_call`, `eth_callMany` and `eth_createAccessList`, if it differs from
+    /// [`Self::rpc_gas_cap`].
+    pub call_gas_cap: Option<u64>,
+    /// Gas limit for `eth_estimateGas`, if it differs from [`Self::rpc_gas_cap`].
+    pub estimate_gas_cap: Option<u64>,
+    /// Gas limit for `debug_trace*` and `trace_*` calls, if it differs from
+    /// [`Self::rpc_gas_cap`].
+    pub trace_gas_cap: Option<u64>,
+    /// Maximum number of blocks that can be simulated in a single `eth_simulateV1` request.
+    pub max_simulate_blocks: u64,
+    /// Maximum number of `eth_call` outputs on top of historical blocks that are cached.
+    ///
+    /// Defaults to zero, which disables the cache.
+    pub call_cache_size: u32,
+    /// Maximum number of locally built pending blocks that are cached.
+    pub pending_block_cache_size: u32,
+    /// Whether private transactions are included in the locally built pending block.
+    pub include_private_in_pending: bool,
+    /// Maximum number of distinct storage slots that can be proven in a single `eth_getProof`
+    /// request.
+    pub max_proof_storage_slots: usize,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
@@ -255,12 +355,25 @@ pub struct EthConfig {
 }
 
 impl EthConfig {
+    /// Returns the gas caps of the method groups, which default to [`Self::rpc_gas_cap`].
+    pub fn gas_caps(&self) -> GasCaps {
+        GasCaps {
+            call: self.call_gas_cap.unwrap_or(self.rpc_gas_cap),
+            estimate: self.estimate_gas_cap.unwrap_or(self.rpc_gas_cap),
+            trace: self.trace_gas_cap.unwrap_or(self.rpc_gas_cap),
+        }
+    }
+
     /// Returns the filter config for the `eth_filter` handler.
     pub fn filter_config(&self) -> EthFilterConfig {
-        EthFilterConfig::default()
+        let config = EthFilterConfig::default()
             .max_blocks_per_filter(self.max_blocks_per_filter)
             .max_logs_per_response(self.max_logs_per_response)
-            .stale_filter_ttl(self.stale_filter_ttl)
+            .stale_filter_ttl(self.stale_filter_ttl);
+        match self.max_logs_query_duration {
+            Some(duration) => config.max_logs_query_duration(duration),
+            None => config,
+        }
     }
 }
 
@@ -273,9 +386,20 @@ impl Default for EthConfig {
             cache: EthStateCacheConfig::default(),
             gas_oracle: GasPriceOracleConfig::default(),
             max_tracing_requests: default_max_tracing_requests(),
+            max_blocking_tasks: DEFAULT_MAX_BLOCKING_TASKS,
+            blocking_task_timeout: None,
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
+            max_logs_query_duration: None,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP,
+            call_gas_cap: None,
+            estimate_gas_cap: None,
+            trace_gas_cap: None,
+            max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
+            call_cache_size: 0,
+            pending_block_cache_size: DEFAULT_PENDING_BLOCK_CACHE_SIZE,
+            include_private_in_pending: false,
+            max_proof_storage_slots: DEFAULT_MAX_PROOF_STORAGE_SLOTS,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
         }
@@ -301,6 +425,18 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum number of concurrent blocking tasks of the `eth` API
+    pub const fn max_blocking_tasks(mut self, max_tasks: usize) -> Self {
+        self.max_blocking_tasks = max_tasks;
+        self
+    }
+
+    /// Configures how long a request waits for the result of a blocking task of the `eth` API
+    pub const fn blocking_task_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.blocking_task_timeout = Some(timeout);
+        self
+    }
+
     /// Configures the maximum block length to scan per `eth_getLogs` request
     pub const fn max_blocks_per_filter(mut self, max_blocks: u64) -> Self {
         self.max_blocks_per_filter = max_blocks;
@@ -313,9 +449,64 @@ impl EthConfig {
         self
     }
 
+    /// Configures the maximum time an `eth_getLogs` call for a range of blocks may take
+    pub const fn max_logs_query_duration(mut self, duration: std::time::Duration) -> Self {
+        self.max_logs_query_duration = Some(duration);
+        self
+    }
+
     /// Configures the maximum gas limit for `eth_call` and call tracing RPC methods
     pub const fn rpc_gas_cap(mut self, rpc_gas_cap: u64) -> Self {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the maximum gas limit for `eth_call`, `eth_callMany` and `eth_createAccessList`
+    pub const fn call_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.call_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Configures the maximum gas limit for `eth_estimateGas`
+    pub const fn estimate_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.estimate_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Configures the maximum gas limit for `debug_trace*` and `trace_*` calls
+    pub const fn trace_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.trace_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Configures the maximum number of blocks that can be simulated per `eth_simulateV1` request
+    pub const fn max_simulate_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_simulate_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the maximum number of `eth_call` outputs that are cached
+    pub const fn call_cache_size(mut self, max_len: u32) -> Self {
+        self.call_cache_size = max_len;
+        self
+    }
+
+    /// Configures the maximum number of locally built pending blocks that are cached
+    pub const fn pending_block_cache_size(mut self, max_blocks: u32) -> Self {
+        self.pending_block_cache_size = max_blocks;
+        self
+    }
+
+    /// Configures whether private transactions are included in the locally built pending block
+    pub const fn include_private_in_pending(mut self, include: bool) -> Self {
+        self.include_private_in_pending = include;
+        self
+    }
+
+    /// Configures the maximum number of storage slots that can be proven per `eth_getProof`
+    /// request
+    pub const fn max_proof_storage_slots(mut self, max_slots: usize) -> Self {
+        self.max_proof_storage_slots = max_slots;
+        self
+    }
 }
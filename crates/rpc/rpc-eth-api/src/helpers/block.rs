@@ -7,7 +7,7 @@ use reth_primitives::{BlockId, Receipt, SealedBlock, SealedBlockWithSenders, Tra
 use reth_provider::{BlockIdReader, BlockReader, BlockReaderIdExt, HeaderProvider};
 use reth_rpc_eth_types::{EthApiError, EthResult, EthStateCache, ReceiptBuilder};
 use reth_rpc_types::{AnyTransactionReceipt, Header, Index, RichBlock};
-use reth_rpc_types_compat::block::{from_block, uncle_block_from_header};
+use reth_rpc_types_compat::block::{from_block, from_pending_block, uncle_block_from_header};
 
 use super::{LoadPendingBlock, LoadReceipt, SpawnBlocking};
 
@@ -48,6 +48,21 @@ pub trait EthBlocks: LoadBlock {
                 None => return Ok(None),
             };
             let block_hash = block.hash();
+            if block_id.is_pending() {
+                // the pending block isn't stored yet, so its total difficulty is derived from its
+                // parent
+                let total_difficulty = EthBlocks::provider(self)
+                    .header_td(&block.parent_hash)?
+                    .ok_or(EthApiError::UnknownBlockNumber)? +
+                    block.difficulty;
+                let block = from_pending_block(
+                    block.unseal(),
+                    total_difficulty,
+                    full.into(),
+                    Some(block_hash),
+                )?;
+                return Ok(Some(block.into()))
+            }
             let total_difficulty = EthBlocks::provider(self)
                 .header_td_by_number(block.number)?
                 .ok_or(EthApiError::UnknownBlockNumber)?;
@@ -59,6 +74,9 @@ pub trait EthBlocks: LoadBlock {
 
     /// Returns the number transactions in the given block.
     ///
+    /// For the `pending` tag this is the number of transactions in the pending block received from
+    /// the CL, or else in the locally built pending block.
+    ///
     /// Returns `None` if the block does not exist
     fn block_transaction_count(
         &self,
@@ -66,8 +84,7 @@ pub trait EthBlocks: LoadBlock {
     ) -> impl Future<Output = EthResult<Option<usize>>> + Send {
         async move {
             if block_id.is_pending() {
-                // Pending block can be fetched directly without need for caching
-                return Ok(LoadBlock::provider(self).pending_block()?.map(|block| block.body.len()))
+                return Ok(self.block_with_senders(block_id).await?.map(|block| block.body.len()))
             }
 
             let block_hash = match LoadBlock::provider(self).block_hash_for_id(block_id)? {
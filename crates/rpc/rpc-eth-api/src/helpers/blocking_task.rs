@@ -1,10 +1,20 @@
 //! Spawns a blocking task. CPU heavy tasks are executed with the `rayon` library. IO heavy tasks
 //! are executed on the `tokio` runtime.
 
+use std::{
+    any::Any,
+    panic::{catch_unwind, AssertUnwindSafe},
+    time::Duration,
+};
+
 use futures::Future;
 use reth_rpc_eth_types::{EthApiError, EthResult};
-use reth_tasks::{pool::BlockingTaskPool, TaskSpawner};
-use tokio::sync::oneshot;
+use reth_tasks::{
+    pool::{BlockingTaskGuard, BlockingTaskPool},
+    TaskSpawner,
+};
+use tokio::sync::{oneshot, OwnedSemaphorePermit};
+use tracing::error;
 
 /// Executes code on a blocking thread.
 pub trait SpawnBlocking: Clone + Send + Sync + 'static {
@@ -18,8 +28,34 @@ pub trait SpawnBlocking: Clone + Send + Sync + 'static {
     /// Thread pool access in default trait method implementations.
     fn tracing_task_pool(&self) -> &BlockingTaskPool;
 
+    /// Returns the guard that limits the number of blocking tasks running concurrently, if any.
+    ///
+    /// Blocking tasks wait for a permit of the guard before they are spawned and hold it until
+    /// they complete. By default the number of tasks isn't limited.
+    fn blocking_task_guard(&self) -> Option<BlockingTaskGuard> {
+        None
+    }
+
+    /// Returns how long a request waits for the result of a blocking task, if bounded.
+    ///
+    /// Once the timeout elapses the request fails with [`EthApiError::ExecutionTimedOut`]. The
+    /// timeout includes the time the task is queued for a permit of the
+    /// [`blocking_task_guard`](Self::blocking_task_guard).
+    ///
+    /// Note: The blocking task itself can't be interrupted, since EVM execution isn't
+    /// cancellable. A timed out task keeps running, and holding its permit, until it completes,
+    /// so slow calls should also be bounded by the gas cap. By default there's no timeout.
+    fn blocking_task_timeout(&self) -> Option<Duration> {
+        None
+    }
+
     /// Executes the future on a new blocking task.
     ///
+    /// If the task panics, the panic message is returned as
+    /// [`EthApiError::BlockingTaskPanicked`]. The task is queued until a permit of the
+    /// [`blocking_task_guard`](Self::blocking_task_guard) is available, and its result is
+    /// awaited for at most the [`blocking_task_timeout`](Self::blocking_task_timeout).
+    ///
     /// Note: This is expected for futures that are dominated by blocking IO operations, for tracing
     /// or CPU bound operations in general use [`spawn_tracing`](Self::spawn_tracing).
     fn spawn_blocking_io<F, R>(&self, f: F) -> impl Future<Output = EthResult<R>> + Send
@@ -27,18 +63,32 @@ pub trait SpawnBlocking: Clone + Send + Sync + 'static {
         F: FnOnce(Self) -> EthResult<R> + Send + 'static,
         R: Send + 'static,
     {
-        let (tx, rx) = oneshot::channel();
+        let guard = self.blocking_task_guard();
+        let timeout = self.blocking_task_timeout();
         let this = self.clone();
-        self.io_task_spawner().spawn_blocking(Box::pin(async move {
-            let res = async move { f(this) }.await;
-            let _ = tx.send(res);
-        }));
-
-        async move { rx.await.map_err(|_| EthApiError::InternalEthError)? }
+        with_timeout(timeout, async move {
+            let permit = acquire_blocking_permit(guard).await?;
+            let (tx, rx) = oneshot::channel();
+            this.io_task_spawner().spawn_blocking(Box::pin({
+                let this = this.clone();
+                async move {
+                    let res = catch_unwind(AssertUnwindSafe(move || f(this)))
+                        .unwrap_or_else(|panic| Err(blocking_task_panicked(panic)));
+                    drop(permit);
+                    let _ = tx.send(res);
+                }
+            }));
+            rx.await.map_err(|_| EthApiError::InternalEthError)?
+        })
     }
 
     /// Executes a blocking task on the tracing pool.
     ///
+    /// If the task panics, the panic message is returned as
+    /// [`EthApiError::BlockingTaskPanicked`]. The task is queued until a permit of the
+    /// [`blocking_task_guard`](Self::blocking_task_guard) is available, and its result is
+    /// awaited for at most the [`blocking_task_timeout`](Self::blocking_task_timeout).
+    ///
     /// Note: This is expected for futures that are predominantly CPU bound, as it uses `rayon`
     /// under the hood, for blocking IO futures use [`spawn_blocking`](Self::spawn_blocking_io). See
     /// <https://ryhl.io/blog/async-what-is-blocking/>.
@@ -47,8 +97,54 @@ pub trait SpawnBlocking: Clone + Send + Sync + 'static {
         F: FnOnce(Self) -> EthResult<R> + Send + 'static,
         R: Send + 'static,
     {
+        let guard = self.blocking_task_guard();
+        let timeout = self.blocking_task_timeout();
         let this = self.clone();
-        let fut = self.tracing_task_pool().spawn(move || f(this));
-        async move { fut.await.map_err(|_| EthApiError::InternalBlockingTaskError)? }
+        with_timeout(timeout, async move {
+            let permit = acquire_blocking_permit(guard).await?;
+            let fut = this.tracing_task_pool().spawn({
+                let this = this.clone();
+                move || {
+                    let res = f(this);
+                    drop(permit);
+                    res
+                }
+            });
+            fut.await.map_err(blocking_task_panicked)?
+        })
     }
 }
+
+/// Awaits the result of a blocking task, failing with [`EthApiError::ExecutionTimedOut`] if the
+/// timeout elapses first.
+async fn with_timeout<R>(
+    timeout: Option<Duration>,
+    fut: impl Future<Output = EthResult<R>>,
+) -> EthResult<R> {
+    let Some(timeout) = timeout else { return fut.await };
+    tokio::time::timeout(timeout, fut).await.map_err(|_| EthApiError::ExecutionTimedOut(timeout))?
+}
+
+/// Waits for a permit of the given guard, if any.
+async fn acquire_blocking_permit(
+    guard: Option<BlockingTaskGuard>,
+) -> EthResult<Option<OwnedSemaphorePermit>> {
+    let Some(guard) = guard else { return Ok(None) };
+    guard.acquire_owned().await.map(Some).map_err(|_| EthApiError::InternalEthError)
+}
+
+/// Converts the payload of a panicked blocking task into an error carrying the panic message.
+///
+/// Payloads that aren't a panic message, like a task that was dropped before it completed, are
+/// reported as [`EthApiError::InternalBlockingTaskError`].
+fn blocking_task_panicked(panic: Box<dyn Any + Send>) -> EthApiError {
+    let msg = match panic.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(panic) => match panic.downcast::<&str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => return EthApiError::InternalBlockingTaskError,
+        },
+    };
+    error!(target: "rpc::eth", %msg, "blocking task panicked");
+    EthApiError::BlockingTaskPanicked(msg)
+}
@@ -1,34 +1,41 @@
 //! Loads a pending block from database. Helper trait for `eth_` transaction, call and trace RPC
 //! methods.
 
-use futures::Future;
+use std::collections::HashSet;
+
+use futures::{Future, FutureExt};
+use jsonrpsee::types::ErrorObject;
 use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_primitives::{
     revm::env::tx_env_with_recovered,
     revm_primitives::{
         BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason,
-        ResultAndState, TransactTo,
+        ResultAndState, SpecId, TransactTo,
     },
-    Bytes, TransactionSignedEcRecovered, TxKind, B256, U256,
+    Bloom, Bytes, TransactionSignedEcRecovered, TxKind, B256, U256,
 };
-use reth_provider::StateProvider;
+use reth_provider::{BlockIdReader, ChainSpecProvider, HeaderProvider, StateProvider};
 use reth_revm::{database::StateProviderDatabase, db::CacheDB, DatabaseRef};
 use reth_rpc_eth_types::{
     cache::db::{StateCacheDbRefMutWrapper, StateProviderTraitObjWrapper},
     error::ensure_success,
+    fee_history::next_block_base_fee,
     revm_utils::{
-        apply_state_overrides, build_call_evm_env, caller_gas_allowance,
-        cap_tx_gas_limit_with_caller_allowance, get_precompiles, prepare_call_env,
+        apply_block_overrides, apply_spec_id_override, apply_state_overrides, build_call_evm_env,
+        caller_gas_allowance, cap_tx_gas_limit_with_caller_allowance, get_precompiles,
+        prepare_call_env,
     },
-    EthApiError, EthResult, RevertError, RpcInvalidTransactionError, StateCacheDb,
+    EthApiError, EthCallCache, EthCallCacheKey, EthResult, GasEstimate, GasEstimateBound,
+    RevertError, RpcInvalidTransactionError, StateCacheDb,
 };
 use reth_rpc_server_types::constants::gas_oracle::{ESTIMATE_GAS_ERROR_RATIO, MIN_TRANSACTION_GAS};
 use reth_rpc_types::{
     state::{EvmOverrides, StateOverride},
-    AccessListWithGasUsed, BlockId, Bundle, EthCallResponse, StateContext, TransactionInfo,
-    TransactionRequest,
+    AccessListWithGasUsed, BlockId, Bundle, EthCallResponse, Log, Receipt, ReceiptWithBloom,
+    SimBlock, SimCallResult, SimulateError, SimulatePayload, SimulatedBlock, StateContext,
+    TransactionInfo, TransactionRequest,
 };
-use revm::{Database, DatabaseCommit};
+use revm::{interpreter::gas::validate_initial_tx_gas, Database, DatabaseCommit};
 use revm_inspectors::access_list::AccessListInspector;
 use tracing::trace;
 
@@ -37,6 +44,19 @@ use super::{LoadBlock, LoadPendingBlock, LoadState, LoadTransaction, SpawnBlocki
 /// Execution related functions for the [`EthApiServer`](crate::EthApiServer) trait in
 /// the `eth_` namespace.
 pub trait EthCall: Call + LoadPendingBlock {
+    /// Returns the maximum number of blocks that can be simulated in a single `eth_simulateV1`
+    /// request.
+    ///
+    /// Data access in default trait method implementations.
+    fn max_simulate_blocks(&self) -> u64;
+
+    /// Returns the cache for the outputs of `eth_call` requests, if any.
+    ///
+    /// Data access in default trait method implementations.
+    fn call_cache(&self) -> Option<&parking_lot::Mutex<EthCallCache>> {
+        None
+    }
+
     /// Estimate gas needed for execution of the `request` at the [`BlockId`].
     fn estimate_gas_at(
         &self,
@@ -47,7 +67,21 @@ pub trait EthCall: Call + LoadPendingBlock {
         Call::estimate_gas_at(self, request, at, state_override)
     }
 
+    /// Estimate gas needed for execution of the `request` at the [`BlockId`] and returns the
+    /// bounds of the search alongside the estimate.
+    fn estimate_gas_detailed_at(
+        &self,
+        request: TransactionRequest,
+        at: BlockId,
+        state_override: Option<StateOverride>,
+    ) -> impl Future<Output = EthResult<GasEstimate>> + Send {
+        Call::estimate_gas_detailed_at(self, request, at, state_override)
+    }
+
     /// Executes the call request (`eth_call`) and returns the output
+    ///
+    /// If the [`call_cache`](Self::call_cache) is enabled, the output of calls on top of a block
+    /// that is neither `latest` nor `pending` is cached by the hash of the block.
     fn call(
         &self,
         request: TransactionRequest,
@@ -55,8 +89,51 @@ pub trait EthCall: Call + LoadPendingBlock {
         overrides: EvmOverrides,
     ) -> impl Future<Output = EthResult<Bytes>> + Send {
         async move {
-            let (res, _env) =
-                self.transact_call_at(request, block_number.unwrap_or_default(), overrides).await?;
+            let at = block_number.unwrap_or_default();
+            let cache = self.call_cache().filter(|cache| cache.lock().is_enabled());
+            let key = match cache {
+                Some(_) if !at.is_latest() && !at.is_pending() => LoadState::provider(self)
+                    .block_hash_for_id(at)?
+                    .map(|block_hash| EthCallCacheKey::new(block_hash, &request, &overrides)),
+                _ => None,
+            };
+            if let Some((cache, key)) = cache.zip(key.as_ref()) {
+                if let Some(output) = cache.lock().get(key) {
+                    return Ok(output)
+                }
+            }
+
+            let (res, _env) = self.transact_call_at(request, at, overrides).await?;
+            let output = ensure_success(res.result)?;
+
+            if let Some((cache, key)) = cache.zip(key) {
+                cache.lock().insert(key, output.clone());
+            }
+            Ok(output)
+        }
+    }
+
+    /// Executes the call request like [`call`](Self::call), but under the rules of the given
+    /// hardfork instead of the ones of the block, while the state of the block is kept.
+    ///
+    /// This allows simulating a call on a block under the rules of another hardfork, e.g. a
+    /// mainnet call under Cancun rules.
+    fn call_with_spec_id(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        overrides: EvmOverrides,
+        spec_id: SpecId,
+    ) -> impl Future<Output = EthResult<Bytes>> + Send {
+        async move {
+            let (res, _env) = self
+                .transact_call_at_with_spec_id(
+                    request,
+                    block_number.unwrap_or_default(),
+                    overrides,
+                    spec_id,
+                )
+                .await?;
 
             ensure_success(res.result)
         }
@@ -170,6 +247,208 @@ pub trait EthCall: Call + LoadPendingBlock {
         }
     }
 
+    /// Simulates a chain of blocks (`eth_simulateV1`) on top of the given [`BlockId`], or latest
+    /// block.
+    ///
+    /// All calls are executed in order on the same state, so later calls, also of later blocks,
+    /// see the state changes of earlier ones. Unless overridden, every simulated block advances
+    /// the block number by one and the timestamp by the block time of the chain, and its base fee
+    /// follows from the gas used by its parent. Overridden numbers and timestamps must be
+    /// increasing.
+    ///
+    /// The calls of a block can't use more gas than the block gas limit, and all calls of the
+    /// request share the gas budget of [`Call::call_gas_limit`].
+    fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> impl Future<Output = EthResult<Vec<SimulatedBlock>>> + Send {
+        async move {
+            let SimulatePayload { block_state_calls, return_full_receipts } = payload;
+            if block_state_calls.is_empty() {
+                return Err(EthApiError::InvalidParams(String::from("no blocks to simulate.")))
+            }
+            let max_blocks = self.max_simulate_blocks();
+            if block_state_calls.len() as u64 > max_blocks {
+                return Err(EthApiError::InvalidParams(format!(
+                    "too many blocks to simulate, max is {max_blocks}."
+                )))
+            }
+
+            let block_id = block_number.unwrap_or_default();
+            let (cfg, block_env, at) = self.evm_env_at(block_id).await?;
+            // the base fee of the first simulated block follows from the gas used by the block it's
+            // built on
+            let parent_gas_used = if block_id.is_pending() {
+                self.pending_block_env_and_cfg()?
+                    .origin
+                    .into_actual_pending()
+                    .map(|block| block.gas_used)
+                    .unwrap_or_default()
+            } else {
+                let provider = LoadPendingBlock::provider(self);
+                let block_hash =
+                    provider.block_hash_for_id(at)?.ok_or(EthApiError::UnknownBlockNumber)?;
+                provider.header(&block_hash)?.ok_or(EthApiError::UnknownBlockNumber)?.gas_used
+            };
+            let chain_spec = LoadPendingBlock::provider(self).chain_spec();
+            let mut gas_budget = self.call_gas_limit();
+
+            let this = self.clone();
+            self.spawn_with_state_at_block(at, move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                let mut blocks = Vec::with_capacity(block_state_calls.len());
+                let mut parent = block_env;
+                let mut parent_gas_used = parent_gas_used;
+
+                for SimBlock { block_overrides, state_overrides, calls } in block_state_calls {
+                    let mut block_env = parent.clone();
+                    block_env.number = parent.number.saturating_add(U256::from(1));
+                    block_env.timestamp = parent
+                        .timestamp
+                        .saturating_add(U256::from(chain_spec.block_time.as_secs()));
+                    let mut base_fee_overridden = false;
+                    if let Some(mut block_overrides) = block_overrides {
+                        base_fee_overridden = block_overrides.base_fee.is_some();
+                        if let Some(block_hashes) = block_overrides.block_hash.take() {
+                            db.block_hashes.extend(
+                                block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)),
+                            )
+                        }
+                        apply_block_overrides(block_overrides, &mut block_env);
+                    }
+                    if block_env.number <= parent.number {
+                        return Err(EthApiError::InvalidParams(format!(
+                            "block number {} is not greater than {}.",
+                            block_env.number, parent.number
+                        )))
+                    }
+                    if block_env.timestamp <= parent.timestamp {
+                        return Err(EthApiError::InvalidParams(format!(
+                            "block timestamp {} is not greater than {}.",
+                            block_env.timestamp, parent.timestamp
+                        )))
+                    }
+
+                    // a zero base fee means that EIP-1559 isn't active, since an active base fee
+                    // never drops to zero
+                    if !base_fee_overridden && parent.basefee > U256::ZERO {
+                        block_env.basefee = U256::from(next_block_base_fee(
+                            parent_gas_used,
+                            parent.gas_limit.saturating_to(),
+                            parent.basefee.saturating_to(),
+                            chain_spec
+                                .base_fee_params_at_timestamp(block_env.timestamp.saturating_to()),
+                        ));
+                    }
+
+                    if let Some(state_overrides) = state_overrides {
+                        apply_state_overrides(state_overrides, &mut db)?;
+                    }
+
+                    let number = block_env.number.saturating_to();
+                    let timestamp = block_env.timestamp.saturating_to();
+                    let block_gas_limit: u64 = block_env.gas_limit.saturating_to();
+                    let mut results = Vec::with_capacity(calls.len());
+                    let mut cumulative_gas_used = 0;
+                    let mut log_index = 0;
+
+                    for (tx_index, call) in calls.into_iter().enumerate() {
+                        let block_gas_left = block_gas_limit.saturating_sub(cumulative_gas_used);
+                        if call.gas.map_or(block_gas_left == 0, |gas| gas > block_gas_left as u128)
+                        {
+                            return Err(EthApiError::InvalidParams(format!(
+                                "block gas limit {block_gas_limit} of block {number} reached by \
+                                 call {tx_index}."
+                            )))
+                        }
+                        let call_gas_limit = gas_budget.min(block_gas_left);
+                        let mut env = prepare_call_env(
+                            cfg.clone(),
+                            block_env.clone(),
+                            call,
+                            call_gas_limit,
+                            &mut db,
+                            EvmOverrides::default(),
+                        )?;
+                        // the caller allowance doesn't respect the remaining gas
+                        env.tx.gas_limit = env.tx.gas_limit.min(call_gas_limit);
+                        let (ResultAndState { result, state }, _) = this.transact(&mut db, env)?;
+                        // later calls build on the state changes of this call
+                        db.commit(state);
+
+                        let gas_used = result.gas_used();
+                        cumulative_gas_used += gas_used;
+                        gas_budget = gas_budget.saturating_sub(gas_used);
+                        let status = result.is_success();
+                        let logs = result
+                            .logs()
+                            .iter()
+                            .map(|log| {
+                                let log = Log {
+                                    inner: log.clone(),
+                                    block_hash: None,
+                                    block_number: Some(number),
+                                    block_timestamp: Some(timestamp),
+                                    transaction_hash: None,
+                                    transaction_index: Some(tx_index as u64),
+                                    log_index: Some(log_index),
+                                    removed: false,
+                                };
+                                log_index += 1;
+                                log
+                            })
+                            .collect::<Vec<_>>();
+                        let receipt = return_full_receipts.then(|| {
+                            let mut logs_bloom = Bloom::ZERO;
+                            logs.iter().for_each(|log| logs_bloom.accrue_log(&log.inner));
+                            ReceiptWithBloom {
+                                receipt: Receipt {
+                                    status: status.into(),
+                                    cumulative_gas_used: cumulative_gas_used as u128,
+                                    logs: logs.clone(),
+                                },
+                                logs_bloom,
+                            }
+                        });
+                        let return_data = match &result {
+                            ExecutionResult::Success { output, .. } => output.data().clone(),
+                            ExecutionResult::Revert { output, .. } => output.clone(),
+                            ExecutionResult::Halt { .. } => Bytes::new(),
+                        };
+                        let error = ensure_success(result).err().map(|err| {
+                            let err = ErrorObject::from(err);
+                            SimulateError { code: err.code(), message: err.message().to_string() }
+                        });
+
+                        results.push(SimCallResult {
+                            return_data,
+                            logs,
+                            gas_used,
+                            status,
+                            error,
+                            receipt,
+                        });
+                    }
+
+                    blocks.push(SimulatedBlock {
+                        number,
+                        timestamp,
+                        gas_limit: block_env.gas_limit.saturating_to(),
+                        gas_used: cumulative_gas_used,
+                        base_fee_per_gas: block_env.basefee.saturating_to(),
+                        calls: results,
+                    });
+                    parent = block_env;
+                    parent_gas_used = cumulative_gas_used;
+                }
+
+                Ok(blocks)
+            })
+            .await
+        }
+    }
+
     /// Creates [`AccessListWithGasUsed`] for the [`TransactionRequest`] at the given
     /// [`BlockId`], or latest block.
     fn create_access_list_at(
@@ -193,6 +472,13 @@ pub trait EthCall: Call + LoadPendingBlock {
 
     /// Creates [`AccessListWithGasUsed`] for the [`TransactionRequest`] at the given
     /// [`BlockId`].
+    ///
+    /// The transaction is executed repeatedly with the access list of the previous run until the
+    /// touched accounts and storage slots no longer change, since the access list itself affects
+    /// the gas available to the transaction. Precompiles are never included in the access list.
+    ///
+    /// If the transaction reverts, the accounts and slots touched until the revert are still
+    /// returned together with the gas used by the reverted execution.
     fn create_access_list_with(
         &self,
         cfg: CfgEnvWithHandlerCfg,
@@ -218,9 +504,13 @@ pub trait EthCall: Call + LoadPendingBlock {
 
         let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
-        if request.gas.is_none() && env.tx.gas_price > U256::ZERO {
+        if request.gas.is_none() {
             // no gas limit was provided in the request, so we need to cap the request's gas limit
-            cap_tx_gas_limit_with_caller_allowance(&mut db, &mut env.tx)?;
+            if env.tx.gas_price > U256::ZERO {
+                cap_tx_gas_limit_with_caller_allowance(&mut db, &mut env.tx)?;
+            }
+            // the configured gas cap applies in either case
+            env.tx.gas_limit = env.tx.gas_limit.min(self.call_gas_limit());
         }
 
         let from = request.from.unwrap_or_default();
@@ -231,35 +521,62 @@ pub trait EthCall: Call + LoadPendingBlock {
             from.create(nonce)
         };
 
-        // can consume the list since we're not using the request anymore
-        let initial = request.access_list.take().unwrap_or_default();
+        let precompiles =
+            get_precompiles(env.handler_cfg.spec_id).into_iter().collect::<HashSet<_>>();
 
-        let precompiles = get_precompiles(env.handler_cfg.spec_id);
-        let mut inspector = AccessListInspector::new(initial, from, to, precompiles);
-        let (result, env) = self.inspect(&mut db, env, &mut inspector)?;
+        // can consume the list since we're not using the request anymore, precompiles are always
+        // warm and are excluded from the list
+        let mut access_list = request.access_list.take().unwrap_or_default();
+        access_list.0.retain(|item| !precompiles.contains(&item.address));
 
-        match result.result {
-            ExecutionResult::Halt { reason, .. } => Err(match reason {
-                HaltReason::NonceOverflow => RpcInvalidTransactionError::NonceMaxValue,
-                halt => RpcInvalidTransactionError::EvmHalt(halt),
-            }),
-            ExecutionResult::Revert { output, .. } => {
-                Err(RpcInvalidTransactionError::Revert(RevertError::new(output)))
-            }
-            ExecutionResult::Success { .. } => Ok(()),
-        }?;
+        // execute until the access list is stable, each run is seeded with the previous list
+        let (result, env) = loop {
+            env.tx.access_list = access_list.clone().into_flattened();
 
-        let access_list = inspector.into_access_list();
+            let mut inspector = AccessListInspector::new(
+                access_list.clone(),
+                from,
+                to,
+                precompiles.iter().copied(),
+            );
+            let (result, env) = self.inspect(&mut db, env.clone(), &mut inspector)?;
 
-        let cfg_with_spec_id =
-            CfgEnvWithHandlerCfg { cfg_env: env.cfg.clone(), handler_cfg: env.handler_cfg };
+            let mut touched = inspector.into_access_list();
+            // the inspector doesn't preserve the order of the accounts
+            touched.0.sort_unstable_by_key(|item| item.address);
 
-        // calculate the gas used using the access list
-        request.access_list = Some(access_list.clone());
-        let gas_used =
-            self.estimate_gas_with(cfg_with_spec_id, env.block.clone(), request, &*db.db, None)?;
+            if touched == access_list {
+                break (result, env)
+            }
+            trace!(target: "rpc::eth::call", ?touched, "Access list changed, executing again");
+            access_list = touched;
+        };
+
+        match result.result {
+            ExecutionResult::Halt { reason, .. } => {
+                Err(RpcInvalidTransactionError::halt(reason, env.tx.gas_limit).into())
+            }
+            ExecutionResult::Revert { gas_used, .. } => {
+                // the state touched before the revert is still reported
+                Ok(AccessListWithGasUsed { access_list, gas_used: U256::from(gas_used) })
+            }
+            ExecutionResult::Success { .. } => {
+                let cfg_with_spec_id =
+                    CfgEnvWithHandlerCfg { cfg_env: env.cfg.clone(), handler_cfg: env.handler_cfg };
+
+                // calculate the gas used using the access list
+                request.access_list = Some(access_list.clone());
+                let gas_used = self.estimate_gas_with(
+                    cfg_with_spec_id,
+                    env.block.clone(),
+                    request,
+                    &*db.db,
+                    None,
+                )?;
 
-        Ok(AccessListWithGasUsed { access_list, gas_used })
+                Ok(AccessListWithGasUsed { access_list, gas_used })
+            }
+        }
     }
 }
 
@@ -270,6 +587,20 @@ pub trait Call: LoadState + SpawnBlocking {
     /// Data access in default trait method implementations.
     fn call_gas_limit(&self) -> u64;
 
+    /// Returns the gas limit cap for `eth_estimateGas`.
+    ///
+    /// Defaults to [`Call::call_gas_limit`].
+    fn estimate_gas_limit(&self) -> u64 {
+        self.call_gas_limit()
+    }
+
+    /// Returns the gas limit cap for `debug_trace*` and `trace_*` calls.
+    ///
+    /// Defaults to [`Call::call_gas_limit`].
+    fn trace_gas_limit(&self) -> u64 {
+        self.call_gas_limit()
+    }
+
     /// Returns a handle for reading evm config.
     ///
     /// Data access in default (L1) trait method implementations.
@@ -343,6 +674,26 @@ pub trait Call: LoadState + SpawnBlocking {
         overrides: EvmOverrides,
         f: F,
     ) -> impl Future<Output = EthResult<R>> + Send
+    where
+        Self: LoadPendingBlock,
+        F: FnOnce(StateCacheDbRefMutWrapper<'_, '_>, EnvWithHandlerCfg) -> EthResult<R>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        self.spawn_with_call_at_capped(request, at, overrides, self.call_gas_limit(), f)
+    }
+
+    /// Same as [`Call::spawn_with_call_at`], but caps the call with the given `gas_limit` instead
+    /// of [`Call::call_gas_limit`].
+    fn spawn_with_call_at_capped<F, R>(
+        &self,
+        request: TransactionRequest,
+        at: BlockId,
+        overrides: EvmOverrides,
+        gas_limit: u64,
+        f: F,
+    ) -> impl Future<Output = EthResult<R>> + Send
     where
         Self: LoadPendingBlock,
         F: FnOnce(StateCacheDbRefMutWrapper<'_, '_>, EnvWithHandlerCfg) -> EthResult<R>
@@ -351,26 +702,84 @@ pub trait Call: LoadState + SpawnBlocking {
         R: Send + 'static,
     {
         async move {
-            let (cfg, block_env, at) = self.evm_env_at(at).await?;
-            let this = self.clone();
-            self.spawn_tracing(move |_| {
-                let state = this.state_at_block_id(at)?;
+            let (cfg, block_env, state_at) = self.evm_env_at(at).await?;
+            let at = if at.is_pending() { at } else { state_at };
+            self.spawn_with_call_in_env(request, cfg, block_env, at, overrides, gas_limit, f).await
+        }
+    }
+
+    /// Same as [`Call::spawn_with_call_at_capped`], but executes the call in the given EVM env on
+    /// top of the state at the given [`BlockId`].
+    ///
+    /// The `pending` state is the [`LoadPendingBlock::pending_state_provider`].
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_with_call_in_env<F, R>(
+        &self,
+        request: TransactionRequest,
+        cfg: CfgEnvWithHandlerCfg,
+        block_env: BlockEnv,
+        at: BlockId,
+        overrides: EvmOverrides,
+        gas_limit: u64,
+        f: F,
+    ) -> impl Future<Output = EthResult<R>> + Send
+    where
+        Self: LoadPendingBlock,
+        F: FnOnce(StateCacheDbRefMutWrapper<'_, '_>, EnvWithHandlerCfg) -> EthResult<R>
+            + Send
+            + 'static,
+        R: Send + 'static,
+    {
+        async move {
+            let pending_state =
+                if at.is_pending() { Some(self.pending_state_provider().await?) } else { None };
+            self.spawn_tracing(move |this| {
+                let state = match pending_state {
+                    Some(state) => state,
+                    None => this.state_at_block_id(at)?,
+                };
                 let mut db =
                     CacheDB::new(StateProviderDatabase::new(StateProviderTraitObjWrapper(&state)));
 
-                let env = prepare_call_env(
-                    cfg,
-                    block_env,
-                    request,
-                    this.call_gas_limit(),
-                    &mut db,
-                    overrides,
-                )?;
+                let env = prepare_call_env(cfg, block_env, request, gas_limit, &mut db, overrides)?;
 
                 f(StateCacheDbRefMutWrapper(&mut db), env)
             })
+            // boxed, since rustc fails to prove that the future is `Send` if the state provider is
+            // moved into the closure
+            .boxed()
+            .await
+        }
+    }
+
+    /// Executes the call request at the given [`BlockId`] under the rules of the given hardfork
+    /// instead of the ones of the block, see [`apply_spec_id_override`].
+    fn transact_call_at_with_spec_id(
+        &self,
+        request: TransactionRequest,
+        at: BlockId,
+        overrides: EvmOverrides,
+        spec_id: SpecId,
+    ) -> impl Future<Output = EthResult<(ResultAndState, EnvWithHandlerCfg)>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        async move {
+            let (mut cfg, mut block_env, state_at) = self.evm_env_at(at).await?;
+            let at = if at.is_pending() { at } else { state_at };
+            apply_spec_id_override(spec_id, &mut cfg, &mut block_env, &request)?;
+
+            let this = self.clone();
+            self.spawn_with_call_in_env(
+                request,
+                cfg,
+                block_env,
+                at,
+                overrides,
+                self.call_gas_limit(),
+                move |db, env| this.transact(db, env),
+            )
             .await
-            .map_err(|_| EthApiError::InternalBlockingTaskError)
         }
     }
 
@@ -481,12 +890,36 @@ pub trait Call: LoadState + SpawnBlocking {
         Self: LoadPendingBlock,
     {
         async move {
-            let (cfg, block_env, at) = self.evm_env_at(at).await?;
+            let estimate = self.estimate_gas_detailed_at(request, at, state_override).await?;
+            Ok(U256::from(estimate.gas))
+        }
+    }
+
+    /// Estimate gas needed for execution of the `request` at the [`BlockId`] and returns the
+    /// bounds of the search alongside the estimate.
+    fn estimate_gas_detailed_at(
+        &self,
+        request: TransactionRequest,
+        at: BlockId,
+        state_override: Option<StateOverride>,
+    ) -> impl Future<Output = EthResult<GasEstimate>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        async move {
+            let (cfg, block_env, state_at) = self.evm_env_at(at).await?;
+            let pending_state =
+                if at.is_pending() { Some(self.pending_state_provider().await?) } else { None };
 
             self.spawn_blocking_io(move |this| {
-                let state = this.state_at_block_id(at)?;
-                this.estimate_gas_with(cfg, block_env, request, state, state_override)
+                let state = match pending_state {
+                    Some(state) => state,
+                    None => this.state_at_block_id(state_at)?,
+                };
+                this.estimate_gas_detailed_with(cfg, block_env, request, state, state_override)
             })
+            // see `Call::spawn_with_call_in_env`
+            .boxed()
             .await
         }
     }
@@ -496,12 +929,33 @@ pub trait Call: LoadState + SpawnBlocking {
     /// This will execute the [`TransactionRequest`] and find the best gas limit via binary search
     fn estimate_gas_with<S>(
         &self,
-        mut cfg: CfgEnvWithHandlerCfg,
+        cfg: CfgEnvWithHandlerCfg,
         block: BlockEnv,
         request: TransactionRequest,
         state: S,
         state_override: Option<StateOverride>,
     ) -> EthResult<U256>
+    where
+        S: StateProvider,
+    {
+        self.estimate_gas_detailed_with(cfg, block, request, state, state_override)
+            .map(|estimate| U256::from(estimate.gas))
+    }
+
+    /// Same as [`Call::estimate_gas_with`], but returns the intrinsic gas of the request and what
+    /// bounded the binary search alongside the estimate.
+    ///
+    /// If the call runs out of gas at the configured gas cap while the block gas limit allows for
+    /// more gas, this returns [`RpcInvalidTransactionError::GasCapReached`]. Requests with a gas
+    /// limit above the gas cap are rejected with [`EthApiError::GasCapExceeded`].
+    fn estimate_gas_detailed_with<S>(
+        &self,
+        mut cfg: CfgEnvWithHandlerCfg,
+        block: BlockEnv,
+        request: TransactionRequest,
+        state: S,
+        state_override: Option<StateOverride>,
+    ) -> EthResult<GasEstimate>
     where
         S: StateProvider,
     {
@@ -520,13 +974,36 @@ pub trait Call: LoadState + SpawnBlocking {
         let block_env_gas_limit = block.gas_limit;
 
         // Determine the highest possible gas limit, considering both the request's specified limit
-        // and the block's limit.
-        let mut highest_gas_limit = tx_request_gas_limit
+        // and the block's limit, capped by the configured estimate gas cap.
+        let block_gas_limit = tx_request_gas_limit
             .map(|tx_gas_limit| U256::from(tx_gas_limit).max(block_env_gas_limit))
             .unwrap_or(block_env_gas_limit);
+        let estimate_gas_limit = self.estimate_gas_limit();
+        if let Some(requested) =
+            tx_request_gas_limit.filter(|gas| *gas > estimate_gas_limit as u128)
+        {
+            return Err(EthApiError::GasCapExceeded {
+                cap: estimate_gas_limit,
+                requested: requested.try_into().unwrap_or(u64::MAX),
+            })
+        }
+        let gas_cap = U256::from(estimate_gas_limit);
+        let (mut highest_gas_limit, mut bound) = if gas_cap < block_gas_limit {
+            (gas_cap, GasEstimateBound::GasCap)
+        } else {
+            (block_gas_limit, GasEstimateBound::BlockGasLimit)
+        };
 
         // Configure the evm env
         let mut env = build_call_evm_env(cfg, block, request)?;
+
+        // The intrinsic gas is the lowest gas limit the transaction can succeed with
+        let floor = validate_initial_tx_gas(
+            env.handler_cfg.spec_id,
+            &env.tx.data,
+            env.tx.transact_to.is_create(),
+            &env.tx.access_list,
+        );
         let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
         // Apply any state overrides if specified.
@@ -549,7 +1026,12 @@ pub trait Call: LoadState + SpawnBlocking {
                         env.tx.gas_limit = MIN_TRANSACTION_GAS;
                         if let Ok((res, _)) = self.transact(&mut db, env) {
                             if res.result.is_success() {
-                                return Ok(U256::from(MIN_TRANSACTION_GAS))
+                                return Ok(GasEstimate {
+                                    gas: MIN_TRANSACTION_GAS,
+                                    floor,
+                                    highest: MIN_TRANSACTION_GAS,
+                                    bound,
+                                })
                             }
                         }
                     }
@@ -562,11 +1044,16 @@ pub trait Call: LoadState + SpawnBlocking {
         // The caller allowance is check by doing `(account.balance - tx.value) / tx.gas_price`
         if env.tx.gas_price > U256::ZERO {
             // cap the highest gas limit by max gas caller can afford with given gas price
-            highest_gas_limit = highest_gas_limit.min(caller_gas_allowance(&mut db, &env.tx)?);
+            let allowance = caller_gas_allowance(&mut db, &env.tx)?;
+            if allowance < highest_gas_limit {
+                highest_gas_limit = allowance;
+                bound = GasEstimateBound::CallerAllowance;
+            }
         }
 
         // We can now normalize the highest gas limit to a u64
         let mut highest_gas_limit: u64 = highest_gas_limit.try_into().unwrap_or(u64::MAX);
+        let search_start = highest_gas_limit;
 
         // If the provided gas limit is less than computed cap, use that
         env.tx.gas_limit = env.tx.gas_limit.min(highest_gas_limit);
@@ -591,6 +1078,14 @@ pub trait Call: LoadState + SpawnBlocking {
         let gas_refund = match res.result {
             ExecutionResult::Success { gas_refunded, .. } => gas_refunded,
             ExecutionResult::Halt { reason, gas_used } => {
+                // the call needs more gas than the gas cap allows, even though the block would
+                // have room for it
+                if bound == GasEstimateBound::GasCap &&
+                    env.tx.gas_limit == highest_gas_limit &&
+                    matches!(reason, HaltReason::OutOfGas(_))
+                {
+                    return Err(RpcInvalidTransactionError::GasCapReached(highest_gas_limit).into())
+                }
                 // here we don't check for invalid opcode because already executed with highest gas
                 // limit
                 return Err(RpcInvalidTransactionError::halt(reason, gas_used).into())
@@ -687,7 +1182,7 @@ pub trait Call: LoadState + SpawnBlocking {
             mid_gas_limit = ((highest_gas_limit as u128 + lowest_gas_limit as u128) / 2) as u64;
         }
 
-        Ok(U256::from(highest_gas_limit))
+        Ok(GasEstimate { gas: highest_gas_limit, floor, highest: search_start, bound })
     }
 
     /// Updates the highest and lowest gas limits for binary search based on the execution result.
@@ -730,7 +1225,7 @@ pub trait Call: LoadState + SpawnBlocking {
                         // These cases should be unreachable because we know the transaction
                         // succeeds, but if they occur, treat them as an
                         // error.
-                        return Err(RpcInvalidTransactionError::EvmHalt(err).into())
+                        return Err(RpcInvalidTransactionError::halt(err, tx_gas_limit).into())
                     }
                 }
             }
@@ -768,7 +1263,7 @@ pub trait Call: LoadState + SpawnBlocking {
                 RpcInvalidTransactionError::Revert(RevertError::new(output)).into()
             }
             ExecutionResult::Halt { reason, .. } => {
-                RpcInvalidTransactionError::EvmHalt(reason).into()
+                RpcInvalidTransactionError::halt(reason, req_gas_limit).into()
             }
         }
     }
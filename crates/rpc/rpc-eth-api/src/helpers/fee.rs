@@ -1,14 +1,19 @@
 //! Loads fee history from database. Helper trait for `eth_` fee and transaction RPC methods.
 
 use futures::Future;
+use reth_chainspec::EthereumHardfork;
 use reth_primitives::U256;
 use reth_provider::{BlockIdReader, BlockReaderIdExt, ChainSpecProvider, HeaderProvider};
 use reth_rpc_eth_types::{
-    fee_history::calculate_reward_percentiles_for_block, EthApiError, EthResult, EthStateCache,
-    FeeHistoryCache, FeeHistoryEntry, GasPriceOracle, RpcInvalidTransactionError,
+    fee_history::{
+        calculate_reward_percentiles_for_block, fee_history_range,
+        gas_used_ratio as block_gas_used_ratio, next_block_base_fee, reward_percentiles_from_tips,
+    },
+    EthApiError, EthResult, EthStateCache, FeeHistoryCache, FeeHistoryEntry, GasPriceOracle,
+    RpcInvalidTransactionError,
 };
 use reth_rpc_types::{BlockNumberOrTag, FeeHistory};
-use tracing::debug;
+use tracing::{debug, trace};
 
 use super::LoadBlock;
 
@@ -44,10 +49,11 @@ pub trait EthFees: LoadFee {
     /// Reports the fee history, for the given amount of blocks, up until the given newest block.
     ///
     /// If `reward_percentiles` are provided the [`FeeHistory`] will include the _approximated_
-    /// rewards for the requested range.
+    /// rewards for the requested range. Like geth, an empty list of percentiles is treated as if
+    /// none were requested, so the `reward` field is omitted and no rewards are computed.
     fn fee_history(
         &self,
-        mut block_count: u64,
+        block_count: u64,
         newest_block: BlockNumberOrTag,
         reward_percentiles: Option<Vec<f64>>,
     ) -> impl Future<Output = EthResult<FeeHistory>> + Send {
@@ -56,6 +62,16 @@ pub trait EthFees: LoadFee {
                 return Ok(FeeHistory::default())
             }
 
+            let reward_percentiles = reward_percentiles.filter(|p| !p.is_empty());
+
+            if let Some(max_blocks) = self.gas_oracle().config().max_fee_history_blocks {
+                if block_count > max_blocks {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block count {block_count} exceeds the maximum of {max_blocks} blocks"
+                    )))
+                }
+            }
+
             // See https://github.com/ethereum/go-ethereum/blob/2754b197c935ee63101cbbca2752338246384fec/eth/gasprice/feehistory.go#L218C8-L225
             let max_fee_history = if reward_percentiles.is_none() {
                 self.gas_oracle().config().max_header_history
@@ -69,21 +85,50 @@ pub trait EthFees: LoadFee {
                     truncated = max_fee_history,
                     "Sanitizing fee history block count"
                 );
-                block_count = max_fee_history
             }
 
-            let Some(end_block) =
-                LoadFee::provider(self).block_number_for_id(newest_block.into())?
-            else {
-                return Err(EthApiError::UnknownBlockNumber)
+            // The locally built pending block is the newest entry if `pending` is requested. It
+            // isn't part of the chain, so it is never persisted to the fee history cache.
+            let pending_block = if newest_block.is_pending() {
+                self.local_pending_block_and_receipts().await?
+            } else {
+                None
             };
 
-            // need to add 1 to the end block to get the correct (inclusive) range
-            let end_block_plus = end_block + 1;
-            // Ensure that we would not be querying outside of genesis
-            if end_block_plus < block_count {
-                block_count = end_block_plus;
-            }
+            // If the pending block can't be built, `pending` resolves to its parent and the base
+            // fee of the next block is taken from the pending block header. `finalized` and
+            // `safe` error if the consensus layer has not reported these blocks yet.
+            let (end_block, pending_base_fee) = if let Some((block, _)) = &pending_block {
+                (block.number, None)
+            } else if newest_block.is_pending() {
+                let pending = self.pending_block_env_and_cfg()?;
+                let header = pending.origin.header();
+                (
+                    header.number.saturating_sub(1),
+                    header.base_fee_per_gas.map(|base_fee| base_fee as u128),
+                )
+            } else {
+                let Some(end_block) =
+                    LoadFee::provider(self).block_number_for_id(newest_block.into())?
+                else {
+                    return Err(EthApiError::UnknownBlockNumber)
+                };
+                (end_block, None)
+            };
+
+            // Clamp the range to the maximum block count and to genesis, so `oldestBlock` is the
+            // first block that is actually returned
+            let Some(range) = fee_history_range(block_count, end_block, max_fee_history) else {
+                return Ok(FeeHistory::default())
+            };
+            let start_block = *range.start();
+
+            // The blocks of the range that are part of the chain
+            let chain_end_block = if pending_block.is_some() {
+                end_block.checked_sub(1).filter(|end| *end >= start_block)
+            } else {
+                Some(end_block)
+            };
 
             // If reward percentiles were specified, we
             // need to validate that they are monotonically
@@ -95,13 +140,6 @@ pub trait EthFees: LoadFee {
                 }
             }
 
-            // Fetch the headers and ensure we got all of them
-            //
-            // Treat a request for 1 block as a request for `newest_block..=newest_block`,
-            // otherwise `newest_block - 2
-            // NOTE: We ensured that block count is capped
-            let start_block = end_block_plus - block_count;
-
             // Collect base fees, gas usage ratios and (optionally) reward percentile data
             let mut base_fee_per_gas: Vec<u128> = Vec::new();
             let mut gas_used_ratio: Vec<f64> = Vec::new();
@@ -111,90 +149,155 @@ pub trait EthFees: LoadFee {
 
             let mut rewards: Vec<Vec<u128>> = Vec::new();
 
-            // Check if the requested range is within the cache bounds
-            let fee_entries = self.fee_history_cache().get_history(start_block, end_block).await;
+            if let Some(end_block) = chain_end_block {
+                let block_count = end_block - start_block + 1;
 
-            if let Some(fee_entries) = fee_entries {
-                if fee_entries.len() != block_count as usize {
-                    return Err(EthApiError::InvalidBlockRange)
+                if let Some(percentiles) = &reward_percentiles {
+                    self.fee_history_cache().record_requested_percentiles(percentiles);
                 }
 
-                for entry in &fee_entries {
-                    base_fee_per_gas.push(entry.base_fee_per_gas as u128);
-                    gas_used_ratio.push(entry.gas_used_ratio);
-                    base_fee_per_blob_gas.push(entry.base_fee_per_blob_gas.unwrap_or_default());
-                    blob_gas_used_ratio.push(entry.blob_gas_used_ratio);
+                // Check if the requested range is within the cache bounds and wasn't reorged, and
+                // the cached rewards cover the requested percentiles
+                let fee_entries = self
+                    .fee_history_cache()
+                    .get_canonical_history(
+                        start_block,
+                        end_block,
+                        reward_percentiles.is_some(),
+                        &LoadFee::provider(self),
+                    )
+                    .await?
+                    .filter(|entries| {
+                        reward_percentiles.as_ref().map_or(true, |percentiles| {
+                            entries.iter().all(|entry| entry.has_rewards_for(percentiles))
+                        })
+                    });
+
+                if let Some(fee_entries) = fee_entries {
+                    if fee_entries.len() != block_count as usize {
+                        return Err(EthApiError::InvalidBlockRange)
+                    }
 
-                    if let Some(percentiles) = &reward_percentiles {
-                        let mut block_rewards = Vec::with_capacity(percentiles.len());
-                        for &percentile in percentiles {
-                            block_rewards.push(self.approximate_percentile(entry, percentile));
+                    for entry in &fee_entries {
+                        base_fee_per_gas.push(entry.base_fee_per_gas as u128);
+                        gas_used_ratio.push(entry.gas_used_ratio);
+                        base_fee_per_blob_gas.push(entry.base_fee_per_blob_gas.unwrap_or_default());
+                        blob_gas_used_ratio.push(entry.blob_gas_used_ratio);
+
+                        if let Some(percentiles) = &reward_percentiles {
+                            let mut block_rewards = Vec::with_capacity(percentiles.len());
+                            for &percentile in percentiles {
+                                block_rewards.push(self.approximate_percentile(entry, percentile));
+                            }
+                            rewards.push(block_rewards);
                         }
-                        rewards.push(block_rewards);
                     }
-                }
-                let last_entry = fee_entries.last().expect("is not empty");
+                    let last_entry = fee_entries.last().expect("is not empty");
 
-                // Also need to include the `base_fee_per_gas` and `base_fee_per_blob_gas` for the
-                // next block
-                base_fee_per_gas
-                    .push(last_entry.next_block_base_fee(&LoadFee::provider(self).chain_spec())
-                        as u128);
+                    // Also need to include the `base_fee_per_gas` and `base_fee_per_blob_gas` for
+                    // the next block
+                    base_fee_per_gas.push(
+                        last_entry.next_block_base_fee(&LoadFee::provider(self).chain_spec())
+                            as u128,
+                    );
 
-                base_fee_per_blob_gas.push(last_entry.next_block_blob_fee().unwrap_or_default());
-            } else {
-            // read the requested header range
-            let headers = LoadFee::provider(self).sealed_headers_range(start_block..=end_block)?;
-            if headers.len() != block_count as usize {
-                return Err(EthApiError::InvalidBlockRange)
+                    base_fee_per_blob_gas
+                        .push(last_entry.next_block_blob_fee().unwrap_or_default());
+                } else {
+                    trace!(target: "rpc::fee", start_block, end_block, "Fee history cache miss, reading headers");
+                    // read the requested header range
+                    let headers =
+                        LoadFee::provider(self).sealed_headers_range(start_block..=end_block)?;
+                    if headers.len() != block_count as usize {
+                        return Err(EthApiError::InvalidBlockRange)
+                    }
+
+                    for header in &headers {
+                        base_fee_per_gas.push(header.base_fee_per_gas.unwrap_or_default() as u128);
+                        gas_used_ratio
+                            .push(block_gas_used_ratio(header.gas_used, header.gas_limit));
+                        base_fee_per_blob_gas.push(header.blob_fee().unwrap_or_default());
+                        blob_gas_used_ratio.push(
+                            header.blob_gas_used.unwrap_or_default() as f64 /
+                                reth_primitives::constants::eip4844::MAX_DATA_GAS_PER_BLOCK
+                                    as f64,
+                        );
+
+                        // Percentiles were specified, so we need to collect reward percentile ino
+                        if let Some(percentiles) = &reward_percentiles {
+                            let (transactions, receipts) = LoadFee::cache(self)
+                                .get_transactions_and_receipts(header.hash())
+                                .await?
+                                .ok_or(EthApiError::InvalidBlockRange)?;
+                            // the tips are memoized, so requests for other percentiles of the
+                            // same block reuse them
+                            let tips = self.fee_history_cache().block_tips(
+                                header.hash(),
+                                header.base_fee_per_gas.unwrap_or_default(),
+                                &transactions,
+                                &receipts,
+                            )?;
+                            rewards.push(reward_percentiles_from_tips(
+                                percentiles,
+                                header.gas_used,
+                                &tips,
+                            ));
+                        }
+                    }
+
+                    // The spec states that `base_fee_per_gas` "[..] includes the next block after
+                    // the newest of the returned range, because this value can be derived from
+                    // the newest block"
+                    //
+                    // The unwrap is safe since we checked earlier that we got at least 1 header.
+                    let last_header = headers.last().expect("is present");
+                    let base_fee_params = LoadFee::provider(self)
+                        .chain_spec()
+                        .base_fee_params_at_timestamp(last_header.timestamp);
+                    base_fee_per_gas.push(next_block_base_fee(
+                        last_header.gas_used,
+                        last_header.gas_limit,
+                        last_header.base_fee_per_gas.unwrap_or_default(),
+                        base_fee_params,
+                    ) as u128);
+
+                    // Same goes for the `base_fee_per_blob_gas`, which "[..] includes the next
+                    // block after the newest of the returned range, because this value can be
+                    // derived from the newest block".
+                    base_fee_per_blob_gas
+                        .push(last_header.next_block_blob_fee().unwrap_or_default());
+                }
             }
 
-            for header in &headers {
-                base_fee_per_gas.push(header.base_fee_per_gas.unwrap_or_default() as u128);
-                gas_used_ratio.push(header.gas_used as f64 / header.gas_limit as f64);
-                base_fee_per_blob_gas.push(header.blob_fee().unwrap_or_default());
-                blob_gas_used_ratio.push(
-                    header.blob_gas_used.unwrap_or_default() as f64 /
-                        reth_primitives::constants::eip4844::MAX_DATA_GAS_PER_BLOCK as f64,
-                );
+            if let Some((block, receipts)) = pending_block {
+                // The pending block replaces the projection for the block after the chain range
+                base_fee_per_gas.pop();
+                base_fee_per_blob_gas.pop();
+
+                let entry = FeeHistoryEntry::new(&block.block);
+                base_fee_per_gas.push(entry.base_fee_per_gas as u128);
+                gas_used_ratio.push(entry.gas_used_ratio);
+                base_fee_per_blob_gas.push(entry.base_fee_per_blob_gas.unwrap_or_default());
+                blob_gas_used_ratio.push(entry.blob_gas_used_ratio);
 
-                // Percentiles were specified, so we need to collect reward percentile ino
                 if let Some(percentiles) = &reward_percentiles {
-                    let (transactions, receipts) = LoadFee::cache(self)
-                        .get_transactions_and_receipts(header.hash())
-                        .await?
-                        .ok_or(EthApiError::InvalidBlockRange)?;
-                    rewards.push(
-                        calculate_reward_percentiles_for_block(
-                            percentiles,
-                            header.gas_used,
-                            header.base_fee_per_gas.unwrap_or_default(),
-                            &transactions,
-                            &receipts,
-                        )
-                        .unwrap_or_default(),
-                    );
+                    rewards.push(calculate_reward_percentiles_for_block(
+                        percentiles,
+                        entry.gas_used,
+                        entry.base_fee_per_gas,
+                        &block.body,
+                        &receipts,
+                    )?);
                 }
+
+                base_fee_per_gas
+                    .push(entry.next_block_base_fee(&LoadFee::provider(self).chain_spec()) as u128);
+                base_fee_per_blob_gas.push(entry.next_block_blob_fee().unwrap_or_default());
             }
 
-            // The spec states that `base_fee_per_gas` "[..] includes the next block after the
-            // newest of the returned range, because this value can be derived from the
-            // newest block"
-            //
-            // The unwrap is safe since we checked earlier that we got at least 1 header.
-            let last_header = headers.last().expect("is present");
-            base_fee_per_gas.push(
-                LoadFee::provider(self).chain_spec().base_fee_params_at_timestamp(last_header.timestamp).next_block_base_fee(
-                    last_header.gas_used as u128,
-                    last_header.gas_limit as u128,
-                    last_header.base_fee_per_gas.unwrap_or_default() as u128,
-                ));
-
-            // Same goes for the `base_fee_per_blob_gas`:
-            // > "[..] includes the next block after the newest of the returned range, because this value can be derived from the newest block.
-            base_fee_per_blob_gas
-                .push(last_header.next_block_blob_fee().unwrap_or_default());
-        };
+            if let Some(pending_base_fee) = pending_base_fee {
+                *base_fee_per_gas.last_mut().expect("is not empty") = pending_base_fee;
+            }
 
             Ok(FeeHistory {
                 base_fee_per_gas,
@@ -208,17 +311,11 @@ pub trait EthFees: LoadFee {
     }
 
     /// Approximates reward at a given percentile for a specific block
-    /// Based on the configured resolution
+    /// Based on the configured resolution, see [`FeeHistoryEntry::reward_for_percentile`]
     fn approximate_percentile(&self, entry: &FeeHistoryEntry, requested_percentile: f64) -> u128 {
-        let resolution = self.fee_history_cache().resolution();
-        let rounded_percentile =
-            (requested_percentile * resolution as f64).round() / resolution as f64;
-        let clamped_percentile = rounded_percentile.clamp(0.0, 100.0);
-
-        // Calculate the index in the precomputed rewards array
-        let index = (clamped_percentile / (1.0 / resolution as f64)).round() as usize;
-        // Fetch the reward from the FeeHistoryEntry
-        entry.rewards.get(index).cloned().unwrap_or_default()
+        entry
+            .reward_for_percentile(requested_percentile, self.fee_history_cache().resolution())
+            .unwrap_or_default()
     }
 }
 
@@ -315,12 +412,23 @@ pub trait LoadFee: LoadBlock {
     /// Returns a suggestion for a gas price for legacy transactions.
     ///
     /// See also: <https://github.com/ethereum/pm/issues/328#issuecomment-853234014>
+    ///
+    /// After London this is the base fee of the latest block plus the suggested tip. Before London
+    /// the oracle samples the gas prices of legacy transactions, so the suggestion is the gas
+    /// price itself.
     fn gas_price(&self) -> impl Future<Output = EthResult<U256>> + Send {
-        let header = self.block(BlockNumberOrTag::Latest.into());
+        let block = self.block(BlockNumberOrTag::Latest.into());
         let suggested_tip = self.suggested_priority_fee();
         async move {
-            let (header, suggested_tip) = futures::try_join!(header, suggested_tip)?;
-            let base_fee = header.and_then(|h| h.base_fee_per_gas).unwrap_or_default();
+            let (block, suggested_tip) = futures::try_join!(block, suggested_tip)?;
+            let Some(block) = block else { return Ok(suggested_tip) };
+            if !LoadFee::provider(self)
+                .chain_spec()
+                .is_fork_active_at_block(EthereumHardfork::London, block.number)
+            {
+                return Ok(suggested_tip)
+            }
+            let base_fee = block.base_fee_per_gas.unwrap_or_default();
             Ok(suggested_tip + U256::from(base_fee))
         }
     }
@@ -4,31 +4,35 @@
 use std::time::{Duration, Instant};
 
 use futures::Future;
+use parking_lot::RwLock;
 use reth_chainspec::EthereumHardforks;
 use reth_evm::ConfigureEvm;
 use reth_execution_types::ExecutionOutcome;
 use reth_primitives::{
     constants::{eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE, EMPTY_ROOT_HASH},
-    proofs::calculate_transaction_root,
+    proofs::{calculate_transaction_root, calculate_withdrawals_root},
     revm::env::tx_env_with_recovered,
     revm_primitives::{
         BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EVMError, Env, ExecutionResult, InvalidTransaction,
         ResultAndState, SpecId,
     },
     Block, BlockNumber, Header, IntoRecoveredTransaction, Receipt, Requests,
-    SealedBlockWithSenders, SealedHeader, TransactionSignedEcRecovered, B256,
+    SealedBlockWithSenders, SealedHeader, TransactionSignedEcRecovered, Withdrawals, B256,
     EMPTY_OMMER_ROOT_HASH, U256,
 };
 use reth_provider::{
-    BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+    providers::BundleStateProvider, BlockReader, BlockReaderIdExt, ChainSpecProvider,
+    EvmEnvProvider, StateProviderBox, StateProviderFactory,
 };
 use reth_revm::{
     database::StateProviderDatabase, state_change::post_block_withdrawals_balance_increments,
 };
 use reth_rpc_eth_types::{
     pending_block::{pre_block_beacon_root_contract_call, pre_block_blockhashes_update},
-    EthApiError, EthResult, PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin,
+    EthResult, PendingBlock, PendingBlockBuildReport, PendingBlockCache, PendingBlockEnv,
+    PendingBlockEnvOrigin, SkippedTransaction, SkippedTransactionReason,
 };
+use reth_rpc_types::engine::PayloadAttributes;
 use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{db::states::bundle_state::BundleRetention, DatabaseCommit, State};
 use tokio::sync::Mutex;
@@ -53,19 +57,41 @@ pub trait LoadPendingBlock {
     /// Data access in default (L1) trait method implementations.
     fn pool(&self) -> impl TransactionPool;
 
-    /// Returns a handle to the pending block.
+    /// Returns a handle to the cached pending blocks.
     ///
     /// Data access in default (L1) trait method implementations.
-    fn pending_block(&self) -> &Mutex<Option<PendingBlock>>;
+    fn pending_block(&self) -> &Mutex<PendingBlockCache>;
+
+    /// Returns a handle to the reason the most recent local pending block build failed.
+    ///
+    /// Data access in default (L1) trait method implementations.
+    fn pending_block_build_error(&self) -> &RwLock<Option<String>>;
 
     /// Returns a handle for reading evm config.
     ///
     /// Data access in default (L1) trait method implementations.
     fn evm_config(&self) -> &impl ConfigureEvm;
 
+    /// Returns true if transactions submitted as private are included in the locally built
+    /// pending block.
+    ///
+    /// Defaults to `false`, so the publicly queryable pending block doesn't leak them.
+    fn include_private_in_pending(&self) -> bool {
+        false
+    }
+
+    /// Returns the payload attributes the locally built pending block is built with, if they
+    /// are supplied externally, e.g. by a custom block builder.
+    ///
+    /// Defaults to `None`, in which case the pending block is derived from the `latest` block.
+    fn pending_block_attributes(&self) -> Option<PayloadAttributes> {
+        None
+    }
+
     /// Configures the [`CfgEnvWithHandlerCfg`] and [`BlockEnv`] for the pending block
     ///
-    /// If no pending block is available, this will derive it from the `latest` block
+    /// If no pending block is available, this will derive it from the `latest` block, using the
+    /// [`Self::pending_block_attributes`] if set.
     fn pending_block_env_and_cfg(&self) -> EthResult<PendingBlockEnv> {
         let origin: PendingBlockEnvOrigin = if let Some(pending) =
             self.provider().pending_block_with_senders()?
@@ -74,16 +100,28 @@ pub trait LoadPendingBlock {
         } else {
             // no pending block from the CL yet, so we use the latest block and modify the env
             // values that we can
-            let latest =
-                self.provider().latest_header()?.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+            let chain_spec = self.provider().chain_spec();
+            // before the genesis block is committed there's no latest header, in which case the
+            // pending block is derived from the genesis header of the chain spec
+            let latest = match self.provider().latest_header()? {
+                Some(latest) => latest,
+                None => chain_spec.sealed_genesis_header(),
+            };
 
             let (mut latest_header, block_hash) = latest.split();
             // child block
             latest_header.number += 1;
-            // assumed child block is in the next slot: 12s
-            latest_header.timestamp += 12;
+            let attributes = self.pending_block_attributes();
+            if let Some(attributes) = &attributes {
+                latest_header.timestamp = attributes.timestamp;
+                latest_header.beneficiary = attributes.suggested_fee_recipient;
+                latest_header.mix_hash = attributes.prev_randao;
+                latest_header.parent_beacon_block_root = attributes.parent_beacon_block_root;
+            } else {
+                // assumed child block is in the next slot
+                latest_header.timestamp += chain_spec.block_time.as_secs();
+            }
             // base fee of the child block
-            let chain_spec = self.provider().chain_spec();
 
             latest_header.base_fee_per_gas = latest_header.next_block_base_fee(
                 chain_spec.base_fee_params_at_timestamp(latest_header.timestamp),
@@ -95,7 +133,12 @@ pub trait LoadPendingBlock {
             // we're reusing the same block hash because we need this to lookup the block's state
             let latest = SealedHeader::new(latest_header, block_hash);
 
-            PendingBlockEnvOrigin::DerivedFromLatest(latest)
+            match attributes {
+                Some(attributes) => {
+                    PendingBlockEnvOrigin::FromAttributes { header: latest, attributes }
+                }
+                None => PendingBlockEnvOrigin::DerivedFromLatest(latest),
+            }
         };
 
         let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
@@ -121,6 +164,19 @@ pub trait LoadPendingBlock {
         Self: SpawnBlocking,
     {
         async move {
+            // serve the actual pending block from memory as long as the provider reports the same
+            // pending block hash
+            if let Some(pending_header) = self.provider().pending_header()? {
+                let mut lock = self.pending_block().lock().await;
+                if let Some(block) = lock.actual_pending(pending_header.hash()) {
+                    return Ok(Some(block.clone()))
+                }
+                if let Some(block) = self.provider().pending_block_with_senders()? {
+                    lock.set_actual_pending(block.clone());
+                    return Ok(Some(block))
+                }
+            }
+
             let pending = self.pending_block_env_and_cfg()?;
             if pending.origin.is_actual_pending() {
                 return Ok(pending.origin.into_actual_pending())
@@ -128,41 +184,149 @@ pub trait LoadPendingBlock {
 
             let mut lock = self.pending_block().lock().await;
 
-            let now = Instant::now();
-
-            // check if the block is still good
-            if let Some(pending_block) = lock.as_ref() {
-                // this is guaranteed to be the `latest` header
-                if pending.block_env.number.to::<u64>() == pending_block.block.number &&
-                    pending.origin.header().hash() == pending_block.block.parent_hash &&
-                    now <= pending_block.expires_at
-                {
-                    return Ok(Some(pending_block.block.clone()))
-                }
+            // check if there's a block built on top of the `latest` header that is still good
+            if let Some(pending_block) = lock
+                .get(pending.origin.build_target_hash(), pending.block_env.number.to::<u64>())
+                .filter(|pending_block| pending_block.is_valid_for(&pending, Instant::now()))
+            {
+                return Ok(Some(pending_block.block.clone()))
             }
 
             // no pending block from the CL yet, so we need to build it ourselves via txpool
-            let pending_block = match self
+            let (pending_block, execution_outcome) = match self
                 .spawn_blocking_io(move |this| {
                     // we rebuild the block
                     this.build_block(pending)
                 })
                 .await
             {
-                Ok(block) => block,
+                Ok(built) => {
+                    *self.pending_block_build_error().write() = None;
+                    built
+                }
                 Err(err) => {
                     debug!(target: "rpc", "Failed to build pending block: {:?}", err);
+                    *self.pending_block_build_error().write() = Some(err.to_string());
                     return Ok(None)
                 }
             };
 
             let now = Instant::now();
-            *lock = Some(PendingBlock::new(pending_block.clone(), now + Duration::from_secs(1)));
+            lock.insert(PendingBlock::new(
+                pending_block.clone(),
+                execution_outcome,
+                now + Duration::from_secs(1),
+            ));
 
             Ok(Some(pending_block))
         }
     }
 
+    /// Returns the pending block together with its receipts.
+    ///
+    /// Like [`Self::local_pending_block`], this builds the pending block if the CL hasn't
+    /// provided one yet.
+    fn local_pending_block_and_receipts(
+        &self,
+    ) -> impl Future<Output = EthResult<Option<(SealedBlockWithSenders, Vec<Receipt>)>>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some(block) = self.local_pending_block().await? else { return Ok(None) };
+
+            if let Some((pending, receipts)) = self.provider().pending_block_and_receipts()? {
+                if pending.hash() == block.hash() {
+                    return Ok(Some((block, receipts)))
+                }
+            }
+
+            let mut lock = self.pending_block().lock().await;
+            let Some(pending_block) = lock
+                .get(block.parent_hash, block.number)
+                .filter(|pending_block| pending_block.block.hash() == block.hash())
+            else {
+                return Ok(None)
+            };
+            let receipts = pending_block
+                .execution_outcome
+                .receipts_by_block(block.number)
+                .iter()
+                .flatten()
+                .cloned()
+                .collect();
+
+            Ok(Some((block, receipts)))
+        }
+    }
+
+    /// Returns the locally built pending block together with the [`ExecutionOutcome`] of
+    /// executing it on top of its parent.
+    ///
+    /// Like [`Self::local_pending_block`], this builds the pending block, or waits for it if it's
+    /// currently being built. `None` is returned if the pending block can't be built, or if it is
+    /// received from the CL, since its state is then available through the provider.
+    fn local_pending_block_and_outcome(
+        &self,
+    ) -> impl Future<Output = EthResult<Option<(SealedBlockWithSenders, ExecutionOutcome)>>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            let Some(block) = self.local_pending_block().await? else { return Ok(None) };
+
+            let mut lock = self.pending_block().lock().await;
+            let execution_outcome = lock
+                .get(block.parent_hash, block.number)
+                .filter(|pending_block| pending_block.block.hash() == block.hash())
+                .map(|pending_block| pending_block.execution_outcome.clone());
+
+            Ok(execution_outcome.map(|execution_outcome| (block, execution_outcome)))
+        }
+    }
+
+    /// Returns the pending block without waiting for it to be built.
+    ///
+    /// This returns the pending block received from the CL, or the locally built pending block if
+    /// it is cached and hasn't expired yet. Unlike [`Self::local_pending_block`], `None` is
+    /// returned instead of building the pending block, or waiting for it while it is being built.
+    fn try_pending_block(&self) -> EthResult<Option<SealedBlockWithSenders>> {
+        let pending = self.pending_block_env_and_cfg()?;
+        if pending.origin.is_actual_pending() {
+            return Ok(pending.origin.into_actual_pending())
+        }
+
+        let Ok(mut lock) = self.pending_block().try_lock() else { return Ok(None) };
+        let pending_block = lock
+            .get(pending.origin.build_target_hash(), pending.block_env.number.to::<u64>())
+            .filter(|pending_block| pending_block.is_valid_for(&pending, Instant::now()))
+            .map(|pending_block| pending_block.block.clone());
+        Ok(pending_block)
+    }
+
+    /// Returns the `pending` state.
+    ///
+    /// If the pending block is built locally, this is the state after executing it on top of the
+    /// latest block: accounts and storage modified by the pending block are read from its bundle
+    /// state, all others fall through to the latest state. The pending block is built first, or
+    /// waited for if it's currently being built. Otherwise the pending state of the provider is
+    /// returned.
+    fn pending_state_provider(&self) -> impl Future<Output = EthResult<StateProviderBox>> + Send
+    where
+        Self: SpawnBlocking,
+    {
+        async move {
+            match self.local_pending_block_and_outcome().await? {
+                Some((block, execution_outcome)) => {
+                    let state = self.provider().history_by_block_hash(block.parent_hash)?;
+                    Ok(Box::new(BundleStateProvider::new(state, execution_outcome))
+                        as StateProviderBox)
+                }
+                None => Ok(self.provider().pending()?),
+            }
+        }
+    }
+
     /// Assembles a [`Receipt`] for a transaction, based on its [`ExecutionResult`].
     fn assemble_receipt(
         &self,
@@ -196,11 +360,28 @@ pub trait LoadPendingBlock {
 
     /// Builds a pending block using the configured provider and pool.
     ///
-    /// If the origin is the actual pending block, the block is built with withdrawals.
+    /// Returns the block and the [`ExecutionOutcome`] of executing it on top of its parent.
+    ///
+    /// If the origin is the actual pending block or supplied payload attributes, the block is built
+    /// with their withdrawals.
     ///
-    /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
-    /// block contract call using the parent beacon block root received from the CL.
-    fn build_block(&self, env: PendingBlockEnv) -> EthResult<SealedBlockWithSenders> {
+    /// After Cancun, if the origin is the actual pending block or supplied payload attributes, the
+    /// block includes the EIP-4788 pre block contract call using their parent beacon block root.
+    fn build_block(
+        &self,
+        env: PendingBlockEnv,
+    ) -> EthResult<(SealedBlockWithSenders, ExecutionOutcome)> {
+        let (block, execution_outcome, _) = self.build_block_with_report(env)?;
+        Ok((block, execution_outcome))
+    }
+
+    /// Builds a pending block like [`LoadPendingBlock::build_block`], and additionally returns a
+    /// [`PendingBlockBuildReport`] listing the included pool transactions and the skipped ones
+    /// with the reason they were skipped.
+    fn build_block_with_report(
+        &self,
+        env: PendingBlockEnv,
+    ) -> EthResult<(SealedBlockWithSenders, ExecutionOutcome, PendingBlockBuildReport)> {
         let PendingBlockEnv { cfg, block_env, origin } = env;
 
         let parent_hash = origin.build_target_hash();
@@ -226,25 +407,33 @@ pub trait LoadPendingBlock {
             PendingBlockEnvOrigin::ActualPending(ref block) => {
                 (block.withdrawals.clone(), block.withdrawals_root)
             }
+            PendingBlockEnvOrigin::FromAttributes { ref attributes, .. } => {
+                let withdrawals = attributes.withdrawals.clone().map(Withdrawals::new);
+                let withdrawals_root =
+                    withdrawals.as_ref().map(|withdrawals| calculate_withdrawals_root(withdrawals));
+                (withdrawals, withdrawals_root)
+            }
             PendingBlockEnvOrigin::DerivedFromLatest(_) => (None, None),
         };
 
         let chain_spec = self.provider().chain_spec();
 
-        let parent_beacon_block_root = if origin.is_actual_pending() {
-            // apply eip-4788 pre block contract call if we got the block from the CL with the real
-            // parent beacon block root
-            pre_block_beacon_root_contract_call(
-                &mut db,
-                chain_spec.as_ref(),
-                block_number,
-                &cfg,
-                &block_env,
-                origin.header().parent_beacon_block_root,
-            )?;
-            origin.header().parent_beacon_block_root
-        } else {
-            None
+        let parent_beacon_block_root = match origin {
+            // apply eip-4788 pre block contract call if we got the block from the CL, or the
+            // attributes, with the real parent beacon block root
+            PendingBlockEnvOrigin::ActualPending(_) |
+            PendingBlockEnvOrigin::FromAttributes { .. } => {
+                pre_block_beacon_root_contract_call(
+                    &mut db,
+                    chain_spec.as_ref(),
+                    block_number,
+                    &cfg,
+                    &block_env,
+                    origin.header().parent_beacon_block_root,
+                )?;
+                origin.header().parent_beacon_block_root
+            }
+            PendingBlockEnvOrigin::DerivedFromLatest(_) => None,
         };
         pre_block_blockhashes_update(
             &mut db,
@@ -254,7 +443,9 @@ pub trait LoadPendingBlock {
             parent_hash,
         )?;
 
+        let include_private = self.include_private_in_pending();
         let mut receipts = Vec::new();
+        let mut report = PendingBlockBuildReport::default();
 
         while let Some(pool_tx) = best_txs.next() {
             // ensure we still have capacity for this transaction
@@ -263,14 +454,22 @@ pub trait LoadPendingBlock {
                 // which also removes all dependent transaction from the iterator before we can
                 // continue
                 best_txs.mark_invalid(&pool_tx);
+                report.skipped.push(SkippedTransaction {
+                    hash: *pool_tx.hash(),
+                    reason: SkippedTransactionReason::GasLimitReached,
+                });
                 continue
             }
 
-            if pool_tx.origin.is_private() {
+            if !include_private && pool_tx.origin.is_private() {
                 // we don't want to leak any state changes made by private transactions, so we mark
                 // them as invalid here which removes all dependent transactions from the iterator
                 // before we can continue
                 best_txs.mark_invalid(&pool_tx);
+                report.skipped.push(SkippedTransaction {
+                    hash: *pool_tx.hash(),
+                    reason: SkippedTransactionReason::Private,
+                });
                 continue
             }
 
@@ -287,6 +486,10 @@ pub trait LoadPendingBlock {
                     // the iterator. This is similar to the gas limit condition
                     // for regular transactions above.
                     best_txs.mark_invalid(&pool_tx);
+                    report.skipped.push(SkippedTransaction {
+                        hash: tx.hash(),
+                        reason: SkippedTransactionReason::BlobGasLimitReached,
+                    });
                     continue
                 }
             }
@@ -302,13 +505,25 @@ pub trait LoadPendingBlock {
                 Err(err) => {
                     match err {
                         EVMError::Transaction(err) => {
-                            if matches!(err, InvalidTransaction::NonceTooLow { .. }) {
-                                // if the nonce is too low, we can skip this transaction
-                            } else {
-                                // if the transaction is invalid, we can skip it and all of its
-                                // descendants
+                            let reason = match err {
+                                InvalidTransaction::NonceTooLow { .. } => {
+                                    SkippedTransactionReason::NonceTooLow
+                                }
+                                InvalidTransaction::NonceTooHigh { .. } => {
+                                    SkippedTransactionReason::NonceGap
+                                }
+                                InvalidTransaction::LackOfFundForMaxFee { .. } => {
+                                    SkippedTransactionReason::InsufficientFunds
+                                }
+                                err => SkippedTransactionReason::Invalid(err.to_string()),
+                            };
+                            if reason != SkippedTransactionReason::NonceTooLow {
+                                // if the nonce is too low, we can skip this transaction,
+                                // otherwise the transaction is invalid and we skip it and all
+                                // of its descendants
                                 best_txs.mark_invalid(&pool_tx);
                             }
+                            report.skipped.push(SkippedTransaction { hash: tx.hash(), reason });
                             continue
                         }
                         err => {
@@ -339,6 +554,11 @@ pub trait LoadPendingBlock {
             // add gas used by the transaction to cumulative gas used, before creating the receipt
             cumulative_gas_used += gas_used;
 
+            report.included.push(tx.hash());
+            if !result.is_success() {
+                report.reverted.push(tx.hash());
+            }
+
             // Push transaction changeset and calculate header bloom filter for receipt.
             receipts.push(Some(self.assemble_receipt(&tx, result, cumulative_gas_used)));
 
@@ -419,6 +639,10 @@ pub trait LoadPendingBlock {
 
         // seal the block
         let block = Block { header, body: executed_txs, ommers: vec![], withdrawals, requests };
-        Ok(SealedBlockWithSenders { block: block.seal_slow(), senders })
+        Ok((
+            SealedBlockWithSenders { block: block.seal_slow(), senders },
+            execution_outcome,
+            report,
+        ))
     }
 }
@@ -4,6 +4,7 @@ use futures::Future;
 use reth_chainspec::ChainInfo;
 use reth_errors::RethResult;
 use reth_primitives::{Address, U64};
+use reth_rpc_eth_types::HealthStatus;
 use reth_rpc_types::SyncStatus;
 
 /// `Eth` API trait.
@@ -28,4 +29,18 @@ pub trait EthApiSpec: Send + Sync {
 
     /// Returns the [`SyncStatus`] of the network
     fn sync_status(&self) -> RethResult<SyncStatus>;
+
+    /// Returns the [`HealthStatus`] of the node.
+    ///
+    /// The node is healthy if it has peers and is synced to within a few blocks of the highest
+    /// block seen on the network.
+    fn health(&self) -> RethResult<HealthStatus>;
+
+    /// Returns the configured fee recipient of the blocks built by the node, if any.
+    ///
+    /// This isn't served by `eth_coinbase`, since the node itself doesn't configure a fee
+    /// recipient. Defaults to `None`.
+    fn fee_recipient(&self) -> Option<Address> {
+        None
+    }
 }
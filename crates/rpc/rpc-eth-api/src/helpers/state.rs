@@ -1,28 +1,41 @@
 //! Loads a pending block from database. Helper trait for `eth_` block, transaction, call and trace
 //! RPC methods.
 
-use futures::Future;
+use std::collections::HashSet;
+
+use futures::{Future, FutureExt};
 use reth_primitives::{
-    revm::env::fill_block_env_with_coinbase, Address, BlockId, BlockNumberOrTag, Bytes, Header,
-    B256, U256,
+    revm::env::fill_block_env_with_coinbase, Address, BlockId, Bytes, Header, B256, KECCAK_EMPTY,
+    U256,
 };
 use reth_provider::{BlockIdReader, StateProvider, StateProviderBox, StateProviderFactory};
 use reth_rpc_eth_types::{
     EthApiError, EthResult, EthStateCache, PendingBlockEnv, RpcInvalidTransactionError,
+    StorageRangePage,
 };
+use reth_rpc_server_types::constants::DEFAULT_MAX_PROOF_STORAGE_SLOTS;
 use reth_rpc_types::{serde_helpers::JsonStorageKey, EIP1186AccountProofResponse};
 use reth_rpc_types_compat::proof::from_primitive_account_proof;
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
-use revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg, SpecId};
+use revm_primitives::{AccountInfo, BlockEnv, CfgEnvWithHandlerCfg, SpecId};
 
 use super::{EthApiSpec, LoadPendingBlock, SpawnBlocking};
 
 /// Helper methods for `eth_` methods relating to state (accounts).
 pub trait EthState: LoadState + SpawnBlocking {
+    /// Returns the maximum number of distinct storage slots that can be proven in a single
+    /// `eth_getProof` request.
+    ///
+    /// Defaults to [`DEFAULT_MAX_PROOF_STORAGE_SLOTS`].
+    fn max_proof_storage_slots(&self) -> usize {
+        DEFAULT_MAX_PROOF_STORAGE_SLOTS
+    }
+
     /// Returns the number of transactions sent from an address at the given block identifier.
     ///
     /// If this is [`BlockNumberOrTag::Pending`](reth_primitives::BlockNumberOrTag) then this will
-    /// look up the highest transaction in pool and return the next nonce (highest + 1).
+    /// advance the on-chain nonce by the pool transactions of the sender with consecutive nonces,
+    /// stopping at the first nonce gap.
     fn transaction_count(
         &self,
         address: Address,
@@ -36,13 +49,12 @@ pub trait EthState: LoadState + SpawnBlocking {
         &self,
         address: Address,
         block_id: Option<BlockId>,
-    ) -> impl Future<Output = EthResult<Bytes>> + Send {
-        self.spawn_blocking_io(move |this| {
-            Ok(this
-                .state_at_block_id_or_latest(block_id)?
-                .account_code(address)?
-                .unwrap_or_default()
-                .original_bytes())
+    ) -> impl Future<Output = EthResult<Bytes>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        self.spawn_with_state_at(block_id, move |state| {
+            Ok(state.account_code(address)?.unwrap_or_default().original_bytes())
         })
     }
 
@@ -51,12 +63,12 @@ pub trait EthState: LoadState + SpawnBlocking {
         &self,
         address: Address,
         block_id: Option<BlockId>,
-    ) -> impl Future<Output = EthResult<U256>> + Send {
-        self.spawn_blocking_io(move |this| {
-            Ok(this
-                .state_at_block_id_or_latest(block_id)?
-                .account_balance(address)?
-                .unwrap_or_default())
+    ) -> impl Future<Output = EthResult<U256>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        self.spawn_with_state_at(block_id, move |state| {
+            Ok(state.account_balance(address)?.unwrap_or_default())
         })
     }
 
@@ -66,18 +78,77 @@ pub trait EthState: LoadState + SpawnBlocking {
         address: Address,
         index: JsonStorageKey,
         block_id: Option<BlockId>,
-    ) -> impl Future<Output = EthResult<B256>> + Send {
-        self.spawn_blocking_io(move |this| {
-            Ok(B256::new(
-                this.state_at_block_id_or_latest(block_id)?
-                    .storage(address, index.0)?
-                    .unwrap_or_default()
-                    .to_be_bytes(),
-            ))
+    ) -> impl Future<Output = EthResult<B256>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        self.spawn_with_state_at(block_id, move |state| {
+            Ok(B256::new(state.storage(address, index.0)?.unwrap_or_default().to_be_bytes()))
+        })
+    }
+
+    /// Returns up to `max_results` storage slots of the given account at the given block,
+    /// starting at the slot with the hashed key `start_key`.
+    ///
+    /// Slots are ordered by the keccak256 hash of their key. The returned page holds the hashed
+    /// key of the next slot, if any, which is the `start_key` of the next page. An account
+    /// without storage results in an empty page. Fails if the state of the block is pruned.
+    fn storage_range_at(
+        &self,
+        block_id: BlockId,
+        address: Address,
+        start_key: B256,
+        max_results: usize,
+    ) -> impl Future<Output = EthResult<StorageRangePage>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        self.spawn_with_state_at(Some(block_id), move |state| {
+            // read one more slot, which is the start of the next page
+            let slots = state.storage_range(address, start_key, max_results.saturating_add(1))?;
+            Ok(StorageRangePage::new(slots, max_results))
+        })
+    }
+
+    /// Returns balance, nonce and code of the given accounts at the given block, in the order of
+    /// `addresses`.
+    ///
+    /// The state at the block is opened once for all accounts. Accounts that don't exist are
+    /// returned as empty accounts.
+    fn get_accounts(
+        &self,
+        addresses: Vec<Address>,
+        block_id: Option<BlockId>,
+    ) -> impl Future<Output = EthResult<Vec<AccountInfo>>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
+        self.spawn_with_state_at(block_id, move |state| {
+            addresses
+                .into_iter()
+                .map(|address| {
+                    let Some(account) = state.basic_account(address)? else {
+                        return Ok(AccountInfo::default())
+                    };
+                    let code = match account.bytecode_hash {
+                        Some(_) => state.account_code(address)?.unwrap_or_default(),
+                        None => Default::default(),
+                    };
+                    Ok(AccountInfo::new(
+                        account.balance,
+                        account.nonce,
+                        account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+                        code.0,
+                    ))
+                })
+                .collect()
         })
     }
 
     /// Returns values stored of given account, with Merkle-proof, at given blocknumber.
+    ///
+    /// Repeated storage keys are only proven once. Requests for more distinct storage keys than
+    /// [`Self::max_proof_storage_slots`] are rejected.
     fn get_proof(
         &self,
         address: Address,
@@ -85,32 +156,40 @@ pub trait EthState: LoadState + SpawnBlocking {
         block_id: Option<BlockId>,
     ) -> EthResult<impl Future<Output = EthResult<EIP1186AccountProofResponse>> + Send>
     where
-        Self: EthApiSpec,
+        Self: EthApiSpec + LoadPendingBlock,
     {
-        let chain_info = self.chain_info()?;
         let block_id = block_id.unwrap_or_default();
 
-        // if we are trying to create a proof for the latest block, but have a BlockId as input
-        // that is not BlockNumberOrTag::Latest, then we need to figure out whether or not the
-        // BlockId corresponds to the latest block
-        let is_latest_block = match block_id {
-            BlockId::Number(BlockNumberOrTag::Number(num)) => num == chain_info.best_number,
-            BlockId::Hash(hash) => hash == chain_info.best_hash.into(),
-            BlockId::Number(BlockNumberOrTag::Latest) => true,
-            _ => false,
-        };
-
-        // TODO: remove when HistoricalStateProviderRef::proof is implemented
-        if !is_latest_block {
-            return Err(EthApiError::InvalidBlockRange)
+        let mut seen = HashSet::with_capacity(keys.len());
+        let storage_keys =
+            keys.into_iter().map(|key| key.0).filter(|key| seen.insert(*key)).collect::<Vec<_>>();
+        let max = self.max_proof_storage_slots();
+        if storage_keys.len() > max {
+            return Err(EthApiError::ProofStorageSlotsExceeded {
+                max,
+                requested: storage_keys.len(),
+            })
         }
 
-        Ok(self.spawn_tracing(move |this| {
-            let state = this.state_at_block_id(block_id)?;
-            let storage_keys = keys.iter().map(|key| key.0).collect::<Vec<_>>();
-            let proof = state.proof(address, &storage_keys)?;
-            Ok(from_primitive_account_proof(proof))
-        }))
+        Ok(async move {
+            // build the pending block before the proof is computed on the tracing pool
+            let pending_state = if block_id.is_pending() {
+                Some(self.pending_state_provider().await?)
+            } else {
+                None
+            };
+            self.spawn_tracing(move |this| {
+                let state = match pending_state {
+                    Some(state) => state,
+                    None => this.state_at_block_id(block_id)?,
+                };
+                let proof = state.proof(address, &storage_keys)?;
+                Ok(from_primitive_account_proof(proof))
+            })
+            // see `LoadState::spawn_with_state_at`
+            .boxed()
+            .await
+        })
     }
 }
 
@@ -165,6 +244,43 @@ pub trait LoadState {
         }
     }
 
+    /// Executes the closure with the state at the given [`BlockId`], or the latest state, on a
+    /// blocking IO task.
+    ///
+    /// Unlike [`Self::state_at_block_id_or_latest`], the `pending` state is the
+    /// [`LoadPendingBlock::pending_state_provider`], which includes the locally built pending
+    /// block.
+    fn spawn_with_state_at<F, R>(
+        &self,
+        block_id: Option<BlockId>,
+        f: F,
+    ) -> impl Future<Output = EthResult<R>> + Send
+    where
+        Self: LoadPendingBlock + SpawnBlocking,
+        F: FnOnce(StateProviderBox) -> EthResult<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        async move {
+            let pending_state = match block_id {
+                Some(block_id) if block_id.is_pending() => {
+                    Some(self.pending_state_provider().await?)
+                }
+                _ => None,
+            };
+            self.spawn_blocking_io(move |this| {
+                let state = match pending_state {
+                    Some(state) => state,
+                    None => this.state_at_block_id_or_latest(block_id)?,
+                };
+                f(state)
+            })
+            // boxed, since rustc fails to prove that the future is `Send` if the state provider is
+            // moved into the closure
+            .boxed()
+            .await
+        }
+    }
+
     /// Returns the revm evm env for the requested [`BlockId`]
     ///
     /// If the [`BlockId`] this will return the [`BlockId`] of the block the env was configured
@@ -218,7 +334,8 @@ pub trait LoadState {
     /// Returns the number of transactions sent from an address at the given block identifier.
     ///
     /// If this is [`BlockNumberOrTag::Pending`](reth_primitives::BlockNumberOrTag) then this will
-    /// look up the highest transaction in pool and return the next nonce (highest + 1).
+    /// advance the on-chain nonce by the pool transactions of the sender with consecutive nonces,
+    /// stopping at the first nonce gap.
     fn transaction_count(
         &self,
         address: Address,
@@ -229,15 +346,18 @@ pub trait LoadState {
     {
         self.spawn_blocking_io(move |this| {
             if block_id == Some(BlockId::pending()) {
-                let address_txs = this.pool().get_transactions_by_sender(address);
-                if let Some(highest_nonce) =
-                    address_txs.iter().map(|item| item.transaction.nonce()).max()
-                {
-                    let tx_count = highest_nonce
-                        .checked_add(1)
-                        .ok_or(RpcInvalidTransactionError::NonceMaxValue)?;
-                    return Ok(U256::from(tx_count))
+                let mut tx_count = this.latest_state()?.account_nonce(address)?.unwrap_or_default();
+                let pool_nonces = this
+                    .pool()
+                    .get_transactions_by_sender(address)
+                    .iter()
+                    .map(|item| item.transaction.nonce())
+                    .collect::<HashSet<_>>();
+                while pool_nonces.contains(&tx_count) {
+                    tx_count =
+                        tx_count.checked_add(1).ok_or(RpcInvalidTransactionError::NonceMaxValue)?;
                 }
+                return Ok(U256::from(tx_count))
             }
 
             let state = this.state_at_block_id_or_latest(block_id)?;
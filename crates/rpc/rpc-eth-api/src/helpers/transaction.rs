@@ -1,13 +1,14 @@
 //! Database access for `eth_` transaction RPC methods. Loads transaction and receipt data w.r.t.
 //! network.
 
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Instant};
 
 use alloy_dyn_abi::TypedData;
 use futures::Future;
 use reth_primitives::{
-    Address, BlockId, Bytes, FromRecoveredPooledTransaction, IntoRecoveredTransaction, Receipt,
-    SealedBlockWithSenders, TransactionMeta, TransactionSigned, TxHash, TxKind, B256, U256,
+    Address, BlockId, Bytes, FromRecoveredPooledTransaction, IntoRecoveredTransaction,
+    PooledTransactionsElementEcRecovered, Receipt, SealedBlockWithSenders, TransactionMeta,
+    TransactionSigned, TxHash, TxKind, B256, U256,
 };
 use reth_provider::{BlockReaderIdExt, ReceiptProvider, TransactionsProvider};
 use reth_rpc_eth_types::{
@@ -62,6 +63,13 @@ pub trait EthTransactions: LoadTransaction {
     /// Access to transaction forwarder in default (L1) trait method implementations.
     fn raw_tx_forwarder(&self) -> Option<Arc<dyn RawTransactionForwarder>>;
 
+    /// Returns the hook that validates raw transactions before they're submitted, if any.
+    ///
+    /// If no hook is set, all transactions are accepted.
+    fn tx_validation_hook(&self) -> Option<Arc<dyn TxValidationHook>> {
+        None
+    }
+
     /// Returns a handle for signing data.
     ///
     /// Singer access in default (L1) trait method implementations.
@@ -75,7 +83,10 @@ pub trait EthTransactions: LoadTransaction {
     fn transaction_by_hash(
         &self,
         hash: B256,
-    ) -> impl Future<Output = EthResult<Option<TransactionSource>>> + Send {
+    ) -> impl Future<Output = EthResult<Option<TransactionSource>>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
         LoadTransaction::transaction_by_hash(self, hash)
     }
 
@@ -93,27 +104,37 @@ pub trait EthTransactions: LoadTransaction {
     ///
     /// If this is a pooled EIP-4844 transaction, the blob sidecar is included.
     ///
-    /// Checks the pool and state.
+    /// Checks the pool, state and the locally built pending block.
     ///
     /// Returns `Ok(None)` if no matching transaction was found.
     fn raw_transaction_by_hash(
         &self,
         hash: B256,
-    ) -> impl Future<Output = EthResult<Option<Bytes>>> + Send {
+    ) -> impl Future<Output = EthResult<Option<Bytes>>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
         async move {
             // Note: this is mostly used to fetch pooled transactions so we check the pool first
-            if let Some(tx) =
-                self.pool().get_pooled_transaction_element(hash).map(|tx| tx.envelope_encoded())
+            if let Some(tx) = LoadTransaction::pool(self)
+                .get_pooled_transaction_element(hash)
+                .map(|tx| tx.envelope_encoded())
             {
                 return Ok(Some(tx))
             }
 
-            self.spawn_blocking_io(move |ref this| {
-                Ok(LoadTransaction::provider(this)
-                    .transaction_by_hash(hash)?
-                    .map(|tx| tx.envelope_encoded()))
-            })
-            .await
+            let tx = self
+                .spawn_blocking_io(move |ref this| {
+                    Ok(LoadTransaction::provider(this)
+                        .transaction_by_hash(hash)?
+                        .map(|tx| tx.envelope_encoded()))
+                })
+                .await?;
+
+            Ok(tx.or_else(|| {
+                self.pending_block_transaction_by_hash(hash)
+                    .map(|tx| tx.into_recovered().into_signed().envelope_encoded())
+            }))
         }
     }
 
@@ -121,7 +142,10 @@ pub trait EthTransactions: LoadTransaction {
     fn historical_transaction_by_hash_at(
         &self,
         hash: B256,
-    ) -> impl Future<Output = EthResult<Option<(TransactionSource, B256)>>> + Send {
+    ) -> impl Future<Output = EthResult<Option<(TransactionSource, B256)>>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
         async move {
             match self.transaction_by_hash_at(hash).await? {
                 None => Ok(None),
@@ -236,6 +260,13 @@ pub trait EthTransactions: LoadTransaction {
     /// Returns the hash of the transaction.
     fn send_raw_transaction(&self, tx: Bytes) -> impl Future<Output = EthResult<B256>> + Send {
         async move {
+            let recovered = recover_raw_transaction(tx.clone())?;
+
+            // rejected transactions are neither forwarded nor submitted to the pool
+            if let Some(hook) = self.tx_validation_hook() {
+                hook.validate_transaction(&recovered).map_err(EthApiError::TransactionRejected)?;
+            }
+
             // On optimism, transactions are forwarded directly to the sequencer to be included in
             // blocks that it builds.
             if let Some(client) = self.raw_tx_forwarder().as_ref() {
@@ -243,7 +274,6 @@ pub trait EthTransactions: LoadTransaction {
                 client.forward_raw_transaction(&tx).await?;
             }
 
-            let recovered = recover_raw_transaction(tx)?;
             let pool_transaction =
                 <Self::Pool as TransactionPool>::Transaction::from_recovered_pooled_transaction(
                     recovered,
@@ -533,18 +563,21 @@ pub trait LoadTransaction: SpawnBlocking {
 
     /// Returns the transaction by hash.
     ///
-    /// Checks the pool and state.
+    /// Checks the state, the locally built pending block and the pool.
     ///
     /// Returns `Ok(None)` if no matching transaction was found.
     fn transaction_by_hash(
         &self,
         hash: B256,
-    ) -> impl Future<Output = EthResult<Option<TransactionSource>>> + Send {
+    ) -> impl Future<Output = EthResult<Option<TransactionSource>>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
         async move {
             // Try to find the transaction on disk
             let mut resp = self
                 .spawn_blocking_io(move |this| {
-                    match this.provider().transaction_by_hash_with_meta(hash)? {
+                    match LoadTransaction::provider(&this).transaction_by_hash_with_meta(hash)? {
                         None => Ok(None),
                         Some((tx, meta)) => {
                             // Note: we assume this transaction is valid, because it's mined (or
@@ -568,9 +601,15 @@ pub trait LoadTransaction: SpawnBlocking {
                 .await?;
 
             if resp.is_none() {
-                // tx not found on disk, check pool
-                if let Some(tx) =
-                    self.pool().get(&hash).map(|tx| tx.transaction.to_recovered_transaction())
+                // tx not found on disk, check the locally built pending block
+                resp = self.pending_block_transaction_by_hash(hash);
+            }
+
+            if resp.is_none() {
+                // tx not found in the pending block, check pool
+                if let Some(tx) = LoadTransaction::pool(self)
+                    .get(&hash)
+                    .map(|tx| tx.transaction.to_recovered_transaction())
                 {
                     resp = Some(TransactionSource::Pool(tx));
                 }
@@ -580,19 +619,51 @@ pub trait LoadTransaction: SpawnBlocking {
         }
     }
 
+    /// Returns the transaction by hash if it's included in one of the cached, locally built
+    /// pending blocks.
+    ///
+    /// This never builds a new pending block, and skips the lookup if a pending block is
+    /// currently being built.
+    fn pending_block_transaction_by_hash(&self, hash: B256) -> Option<TransactionSource>
+    where
+        Self: LoadPendingBlock,
+    {
+        let lock = self.pending_block().try_lock().ok()?;
+        let now = Instant::now();
+
+        let tx = lock.iter().filter(|pending_block| !pending_block.is_expired(now)).find_map(
+            |pending_block| {
+                let block = &pending_block.block;
+                block.transactions_with_sender().enumerate().find_map(|(index, (sender, tx))| {
+                    (tx.hash() == hash).then(|| TransactionSource::PendingBlock {
+                        transaction: tx.clone().with_signer(*sender),
+                        index: index as u64,
+                        block_number: block.number,
+                        base_fee: block.base_fee_per_gas,
+                    })
+                })
+            },
+        );
+        tx
+    }
+
     /// Returns the transaction by including its corresponding [`BlockId`].
     ///
     /// Note: this supports pending transactions
     fn transaction_by_hash_at(
         &self,
         transaction_hash: B256,
-    ) -> impl Future<Output = EthResult<Option<(TransactionSource, BlockId)>>> + Send {
+    ) -> impl Future<Output = EthResult<Option<(TransactionSource, BlockId)>>> + Send
+    where
+        Self: LoadPendingBlock,
+    {
         async move {
             match self.transaction_by_hash(transaction_hash).await? {
                 None => Ok(None),
                 Some(tx) => {
                     let res = match tx {
-                        tx @ TransactionSource::Pool(_) => (tx, BlockId::pending()),
+                        tx @ (TransactionSource::Pool(_) |
+                        TransactionSource::PendingBlock { .. }) => (tx, BlockId::pending()),
                         TransactionSource::Block {
                             transaction,
                             index,
@@ -622,6 +693,8 @@ pub trait LoadTransaction: SpawnBlocking {
         &self,
         hash: B256,
     ) -> impl Future<Output = EthResult<Option<(TransactionSource, SealedBlockWithSenders)>>> + Send
+    where
+        Self: LoadPendingBlock,
     {
         async move {
             let (transaction, at) = match self.transaction_by_hash_at(hash).await? {
@@ -648,3 +721,15 @@ pub trait RawTransactionForwarder: fmt::Debug + Send + Sync + 'static {
     /// Forwards raw transaction bytes for `eth_sendRawTransaction`
     async fn forward_raw_transaction(&self, raw: &[u8]) -> EthResult<()>;
 }
+
+/// A policy check for transactions submitted via `eth_sendRawTransaction`.
+///
+/// The hook is invoked after the transaction is decoded and before it is forwarded or added to
+/// the pool, for example to enforce a gas price floor or to reject banned senders.
+pub trait TxValidationHook: fmt::Debug + Send + Sync + 'static {
+    /// Validates the transaction, returning the reason if it is rejected.
+    fn validate_transaction(
+        &self,
+        transaction: &PooledTransactionsElementEcRecovered,
+    ) -> Result<(), String>;
+}
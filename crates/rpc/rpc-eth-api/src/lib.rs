@@ -23,7 +23,7 @@ pub use core::EthApiServer;
 pub use filter::EthFilterApiServer;
 pub use pubsub::EthPubSubApiServer;
 
-pub use helpers::transaction::RawTransactionForwarder;
+pub use helpers::transaction::{RawTransactionForwarder, TxValidationHook};
 
 #[cfg(feature = "client")]
 pub use bundle::{EthBundleApiClient, EthCallBundleApiClient};
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use reth_rpc_server_types::constants::cache::{
     DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN,
-    DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_MISSING_ENTRIES_MAX_LEN, DEFAULT_MISSING_ENTRY_TTL_MS, DEFAULT_RECEIPT_CACHE_MAX_LEN,
 };
 
 /// Settings for the [`EthStateCache`](super::EthStateCache).
@@ -27,6 +27,34 @@ pub struct EthStateCacheConfig {
     ///
     /// Default is 512.
     pub max_concurrent_db_requests: usize,
+    /// Whether to warm the cache with the blocks and receipts of new canonical blocks.
+    ///
+    /// Default is true.
+    #[serde(default = "default_cache_new_blocks")]
+    pub cache_new_blocks: bool,
+    /// Max number of block hashes whose block or receipts weren't found that are remembered.
+    ///
+    /// Default is 1000.
+    #[serde(default = "default_max_missing_entries")]
+    pub max_missing_entries: u32,
+    /// How long, in milliseconds, a block or its receipts that weren't found are served as
+    /// missing without asking the provider again. Zero disables remembering missing entries.
+    ///
+    /// Default is 1000.
+    #[serde(default = "default_missing_entry_ttl_ms")]
+    pub missing_entry_ttl_ms: u64,
+}
+
+const fn default_cache_new_blocks() -> bool {
+    true
+}
+
+const fn default_max_missing_entries() -> u32 {
+    DEFAULT_MISSING_ENTRIES_MAX_LEN
+}
+
+const fn default_missing_entry_ttl_ms() -> u64 {
+    DEFAULT_MISSING_ENTRY_TTL_MS
 }
 
 impl Default for EthStateCacheConfig {
@@ -36,6 +64,9 @@ impl Default for EthStateCacheConfig {
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            cache_new_blocks: true,
+            max_missing_entries: DEFAULT_MISSING_ENTRIES_MAX_LEN,
+            missing_entry_ttl_ms: DEFAULT_MISSING_ENTRY_TTL_MS,
         }
     }
 }
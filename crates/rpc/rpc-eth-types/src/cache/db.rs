@@ -108,6 +108,15 @@ impl<'a> StateProvider for StateProviderTraitObjWrapper<'a> {
     ) -> reth_errors::ProviderResult<Option<reth_primitives::StorageValue>> {
         self.0.storage(account, storage_key)
     }
+
+    fn storage_range(
+        &self,
+        account: revm_primitives::Address,
+        start_key: B256,
+        limit: usize,
+    ) -> reth_errors::ProviderResult<Vec<(B256, reth_primitives::StorageValue)>> {
+        self.0.storage_range(account, start_key, limit)
+    }
 }
 
 /// Hack to get around 'higher-ranked lifetime error', see
@@ -13,12 +13,13 @@ use reth_provider::{
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use revm::primitives::{BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId};
-use schnellru::{ByLength, Limiter};
+use schnellru::{ByLength, Limiter, LruMap};
 use std::{
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{
     mpsc::{unbounded_channel, UnboundedSender},
@@ -59,6 +60,64 @@ type ReceiptsLruCache<L> =
 type EnvLruCache<L> =
     MultiConsumerLruCache<B256, (CfgEnvWithHandlerCfg, BlockEnv), L, EnvResponseSender>;
 
+/// Block hashes whose data recently wasn't found, e.g. because the block isn't available yet.
+///
+/// Lookups for these hashes are answered with `None` until the entry expires, instead of asking
+/// the provider again.
+#[derive(Debug)]
+struct MissingEntries {
+    /// When each missing entry was last looked up.
+    entries: LruMap<B256, Instant, ByLength>,
+    /// How long a missing entry is remembered.
+    ttl: Duration,
+}
+
+impl MissingEntries {
+    fn new(max_len: u32, ttl: Duration) -> Self {
+        Self { entries: LruMap::new(ByLength::new(max_len)), ttl }
+    }
+
+    /// Returns true if the data for the hash was recently missing, and drops expired entries.
+    fn contains(&mut self, hash: &B256) -> bool {
+        let Some(missing_since) = self.entries.peek(hash) else { return false };
+        if missing_since.elapsed() < self.ttl {
+            return true
+        }
+        self.entries.remove(hash);
+        false
+    }
+
+    /// Remembers that the data for the hash is missing.
+    fn insert(&mut self, hash: B256) {
+        if !self.ttl.is_zero() {
+            self.entries.insert(hash, Instant::now());
+        }
+    }
+
+    /// Forgets the hash, because its data is available now.
+    fn remove(&mut self, hash: &B256) {
+        self.entries.remove(hash);
+    }
+}
+
+/// A single receipt yielded by [`EthStateCache::receipts_stream`].
+#[derive(Debug, Clone)]
+pub struct StreamedReceipt {
+    /// Hash of the block that contains the receipt.
+    pub block_hash: B256,
+    /// Index of the receipt, and of its transaction, in the block.
+    pub index: usize,
+    /// All receipts of the block, shared with the cache.
+    receipts: Arc<Vec<Receipt>>,
+}
+
+impl StreamedReceipt {
+    /// Returns the receipt.
+    pub fn receipt(&self) -> &Receipt {
+        &self.receipts[self.index]
+    }
+}
+
 /// Provides async access to cached eth data
 ///
 /// This is the frontend for the async caching service which manages cached data on a different
@@ -74,22 +133,32 @@ impl EthStateCache {
         provider: Provider,
         action_task_spawner: Tasks,
         evm_config: EvmConfig,
-        max_blocks: u32,
-        max_receipts: u32,
-        max_envs: u32,
-        max_concurrent_db_operations: usize,
+        config: EthStateCacheConfig,
     ) -> (Self, EthStateCacheService<Provider, Tasks, EvmConfig>) {
+        let EthStateCacheConfig {
+            max_blocks,
+            max_receipts,
+            max_envs,
+            max_concurrent_db_requests,
+            cache_new_blocks,
+            max_missing_entries,
+            missing_entry_ttl_ms,
+        } = config;
+        let missing_entry_ttl = Duration::from_millis(missing_entry_ttl_ms);
         let (to_service, rx) = unbounded_channel();
         let service = EthStateCacheService {
             provider,
             full_block_cache: BlockLruCache::new(max_blocks, "blocks"),
             receipts_cache: ReceiptsLruCache::new(max_receipts, "receipts"),
             evm_env_cache: EnvLruCache::new(max_envs, "evm_env"),
+            missing_blocks: MissingEntries::new(max_missing_entries, missing_entry_ttl),
+            missing_receipts: MissingEntries::new(max_missing_entries, missing_entry_ttl),
             action_tx: to_service.clone(),
             action_rx: UnboundedReceiverStream::new(rx),
             action_task_spawner,
-            rate_limiter: Arc::new(Semaphore::new(max_concurrent_db_operations)),
+            rate_limiter: Arc::new(Semaphore::new(max_concurrent_db_requests)),
             evm_config,
+            cache_new_blocks,
         };
         let cache = Self { to_service };
         (cache, service)
@@ -126,17 +195,7 @@ impl EthStateCache {
         Tasks: TaskSpawner + Clone + 'static,
         EvmConfig: ConfigureEvm,
     {
-        let EthStateCacheConfig { max_blocks, max_receipts, max_envs, max_concurrent_db_requests } =
-            config;
-        let (this, service) = Self::create(
-            provider,
-            executor.clone(),
-            evm_config,
-            max_blocks,
-            max_receipts,
-            max_envs,
-            max_concurrent_db_requests,
-        );
+        let (this, service) = Self::create(provider, executor.clone(), evm_config, config);
         executor.spawn_critical("eth state cache", Box::pin(service));
         this
     }
@@ -249,6 +308,36 @@ impl EthStateCache {
         Ok(block.zip(receipts))
     }
 
+    /// Returns a stream over the receipts of the given blocks that yields one [`StreamedReceipt`]
+    /// per transaction, in block order.
+    ///
+    /// The receipts of a block are only requested once the stream reaches the block, so a
+    /// consumer that stops early, e.g. after collecting enough matching logs, never loads the
+    /// receipts of the remaining blocks. Blocks that aren't found are skipped.
+    pub fn receipts_stream(
+        &self,
+        block_hashes: Vec<B256>,
+    ) -> impl Stream<Item = ProviderResult<StreamedReceipt>> + Send + 'static {
+        let cache = self.clone();
+        futures::stream::iter(block_hashes)
+            .then(move |block_hash| {
+                let cache = cache.clone();
+                async move { (block_hash, cache.get_receipts(block_hash).await) }
+            })
+            .flat_map(|(block_hash, receipts)| {
+                let (receipts, err) = match receipts {
+                    Ok(receipts) => (receipts, None),
+                    Err(err) => (None, Some(Err(err))),
+                };
+                let receipts = receipts.into_iter().flat_map(move |receipts| {
+                    (0..receipts.len()).map(move |index| {
+                        Ok(StreamedReceipt { block_hash, index, receipts: receipts.clone() })
+                    })
+                });
+                futures::stream::iter(err.into_iter().chain(receipts))
+            })
+    }
+
     /// Requests the evm env config for the block hash.
     ///
     /// Returns an error if the corresponding header (required for populating the envs) was not
@@ -299,6 +388,10 @@ pub(crate) struct EthStateCacheService<
     receipts_cache: ReceiptsLruCache<LimitReceipts>,
     /// The LRU cache for revm environments
     evm_env_cache: EnvLruCache<LimitEnvs>,
+    /// Hashes of blocks that recently weren't found.
+    missing_blocks: MissingEntries,
+    /// Hashes of blocks whose receipts recently weren't found.
+    missing_receipts: MissingEntries,
     /// Sender half of the action channel.
     action_tx: UnboundedSender<CacheAction>,
     /// Receiver half of the action channel.
@@ -309,6 +402,8 @@ pub(crate) struct EthStateCacheService<
     rate_limiter: Arc<Semaphore>,
     /// The type that determines how to configure the EVM.
     evm_config: EvmConfig,
+    /// Whether new canonical blocks are inserted into the caches.
+    cache_new_blocks: bool,
 }
 
 impl<Provider, Tasks, EvmConfig> EthStateCacheService<Provider, Tasks, EvmConfig>
@@ -335,9 +430,14 @@ where
             }
         }
 
-        // cache good block
-        if let Ok(Some(block)) = res {
-            self.full_block_cache.insert(block_hash, block);
+        // cache good block, and remember missing blocks for a short while
+        match res {
+            Ok(Some(block)) => {
+                self.missing_blocks.remove(&block_hash);
+                self.full_block_cache.insert(block_hash, block);
+            }
+            Ok(None) => self.missing_blocks.insert(block_hash),
+            Err(_) => {}
         }
     }
 
@@ -353,9 +453,14 @@ where
             }
         }
 
-        // cache good receipts
-        if let Ok(Some(receipts)) = res {
-            self.receipts_cache.insert(block_hash, receipts);
+        // cache good receipts, and remember missing receipts for a short while
+        match res {
+            Ok(Some(receipts)) => {
+                self.missing_receipts.remove(&block_hash);
+                self.receipts_cache.insert(block_hash, receipts);
+            }
+            Ok(None) => self.missing_receipts.insert(block_hash),
+            Err(_) => {}
         }
     }
 
@@ -421,6 +526,10 @@ where
                                 let _ = response_tx.send(Ok(Some(block)));
                                 continue
                             }
+                            if this.missing_blocks.contains(&block_hash) {
+                                let _ = response_tx.send(Ok(None));
+                                continue
+                            }
 
                             // block is not in the cache, request it if this is the first consumer
                             if this.full_block_cache.queue(block_hash, Either::Left(response_tx)) {
@@ -449,6 +558,10 @@ where
                                 let _ = response_tx.send(Ok(Some(block.body.clone())));
                                 continue
                             }
+                            if this.missing_blocks.contains(&block_hash) {
+                                let _ = response_tx.send(Ok(None));
+                                continue
+                            }
 
                             // block is not in the cache, request it if this is the first consumer
                             if this.full_block_cache.queue(block_hash, Either::Right(response_tx)) {
@@ -477,6 +590,10 @@ where
                                 let _ = response_tx.send(Ok(Some(receipts)));
                                 continue
                             }
+                            if this.missing_receipts.contains(&block_hash) {
+                                let _ = response_tx.send(Ok(None));
+                                continue
+                            }
 
                             // block is not in the cache, request it if this is the first consumer
                             if this.receipts_cache.queue(block_hash, response_tx) {
@@ -561,11 +678,35 @@ where
                             }
                         }
                         CacheAction::CacheNewCanonicalChain { chain_change } => {
-                            for block in chain_change.blocks {
+                            // only warm the cache with the newest blocks, so that a large chain,
+                            // e.g. after a pipeline run, doesn't evict all hot entries
+                            let ChainChange { blocks, receipts } = chain_change;
+
+                            // committed blocks are available, even the ones that aren't cached, so
+                            // the missing entries are invalidated regardless of `cache_new_blocks`
+                            for block in &blocks {
+                                this.missing_blocks.remove(&block.hash());
+                            }
+                            for block_receipts in &receipts {
+                                this.missing_receipts.remove(&block_receipts.block_hash);
+                            }
+
+                            if !this.cache_new_blocks {
+                                continue
+                            }
+
+                            let skip_blocks = blocks
+                                .len()
+                                .saturating_sub(this.full_block_cache.max_warm_up_len());
+                            let skip_receipts = receipts
+                                .len()
+                                .saturating_sub(this.receipts_cache.max_warm_up_len());
+
+                            for block in blocks.into_iter().skip(skip_blocks) {
                                 this.on_new_block(block.hash(), Ok(Some(block.unseal())));
                             }
 
-                            for block_receipts in chain_change.receipts {
+                            for block_receipts in receipts.into_iter().skip(skip_receipts) {
                                 this.on_new_receipts(
                                     block_receipts.block_hash,
                                     Ok(Some(Arc::new(
@@ -637,7 +778,10 @@ impl ChainChange {
 /// Awaits for new chain events and directly inserts them into the cache so they're available
 /// immediately before they need to be fetched from disk.
 ///
-/// Reorged blocks are removed from the cache.
+/// Unless [`EthStateCacheConfig::cache_new_blocks`] is disabled, only the newest blocks of a chain
+/// are inserted, up to a quarter of the cache capacity.
+///
+/// Reorged blocks are always removed from the cache.
 pub async fn cache_new_blocks_task<St>(eth_state_cache: EthStateCache, mut events: St)
 where
     St: Stream<Item = CanonStateNotification> + Unpin + 'static,
@@ -656,3 +800,153 @@ where
             eth_state_cache.to_service.send(CacheAction::CacheNewCanonicalChain { chain_change });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_execution_types::ExecutionOutcome;
+    use reth_primitives::{Header, Receipts, SealedBlock};
+    use reth_provider::test_utils::MockEthProvider;
+
+    /// Returns a committed chain of blocks `1..=len` with a single receipt each.
+    fn committed_chain(len: u64) -> (Vec<B256>, CanonStateNotification) {
+        let blocks: Vec<_> = (1..=len)
+            .map(|number| SealedBlockWithSenders {
+                block: SealedBlock {
+                    header: Header { number, ..Default::default() }.seal_slow(),
+                    ..Default::default()
+                },
+                senders: vec![],
+            })
+            .collect();
+        let hashes = blocks.iter().map(|block| block.hash()).collect();
+        let receipts: Receipts = (1..=len)
+            .map(|number| vec![Some(Receipt { cumulative_gas_used: number, ..Default::default() })])
+            .collect();
+        let outcome = ExecutionOutcome::new(Default::default(), receipts, 1, vec![]);
+        let new = Arc::new(Chain::new(blocks, outcome, None));
+        (hashes, CanonStateNotification::Commit { new })
+    }
+
+    #[tokio::test]
+    async fn streams_receipts_per_transaction() {
+        let cache = EthStateCache::spawn(
+            MockEthProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+
+        let (hashes, notification) = committed_chain(3);
+        cache_new_blocks_task(cache.clone(), futures::stream::iter([notification])).await;
+
+        // unknown blocks are skipped
+        let mut block_hashes = hashes.clone();
+        block_hashes.insert(1, B256::random());
+        let receipts: Vec<_> =
+            cache.receipts_stream(block_hashes.clone()).map(Result::unwrap).collect().await;
+        assert_eq!(receipts.len(), 3);
+        for (number, (receipt, hash)) in (1..).zip(receipts.iter().zip(&hashes)) {
+            assert_eq!(receipt.block_hash, *hash);
+            assert_eq!(receipt.index, 0);
+            assert_eq!(receipt.receipt().cumulative_gas_used, number);
+        }
+
+        // a limited consumer stops before the receipts of the last block
+        let receipts: Vec<_> =
+            cache.receipts_stream(block_hashes).take(2).map(Result::unwrap).collect().await;
+        assert_eq!(
+            receipts.iter().map(|receipt| receipt.block_hash).collect::<Vec<_>>(),
+            hashes[..2]
+        );
+    }
+
+    #[tokio::test]
+    async fn caches_committed_receipts() {
+        let cache = EthStateCache::spawn(
+            MockEthProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+
+        let (hashes, notification) = committed_chain(1);
+        cache_new_blocks_task(cache.clone(), futures::stream::iter([notification])).await;
+
+        // the mock provider doesn't have any receipts, so these must be served by the cache
+        let receipts = cache.get_receipts(hashes[0]).await.unwrap().unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert!(cache.get_block_with_senders(hashes[0]).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn new_blocks_are_not_cached_if_disabled() {
+        let config = EthStateCacheConfig { cache_new_blocks: false, ..Default::default() };
+        let cache =
+            EthStateCache::spawn(MockEthProvider::default(), config, EthEvmConfig::default());
+
+        let (hashes, notification) = committed_chain(1);
+        cache_new_blocks_task(cache.clone(), futures::stream::iter([notification])).await;
+
+        assert!(cache.get_receipts(hashes[0]).await.unwrap().is_none());
+        assert!(cache.get_block_with_senders(hashes[0]).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn remembers_missing_blocks() {
+        let provider = MockEthProvider::default();
+        // new blocks aren't cached, so the lookups below are served by the provider
+        let config = EthStateCacheConfig {
+            missing_entry_ttl_ms: 60_000,
+            cache_new_blocks: false,
+            ..Default::default()
+        };
+        let cache = EthStateCache::spawn(provider.clone(), config, EthEvmConfig::default());
+
+        let (hashes, notification) = committed_chain(1);
+        let block = notification.tip().clone();
+        assert!(cache.get_transactions_and_receipts(hashes[0]).await.unwrap().is_none());
+
+        // the block became available, but the second lookup is served from the missing entries
+        // without asking the provider
+        provider.add_block(hashes[0], block.block.clone().unseal());
+        assert!(cache.get_transactions_and_receipts(hashes[0]).await.unwrap().is_none());
+        assert!(cache.get_block_with_senders(hashes[0]).await.unwrap().is_none());
+
+        // a committed block invalidates the missing entries
+        cache_new_blocks_task(cache.clone(), futures::stream::iter([notification])).await;
+        assert!(cache.get_transactions_and_receipts(hashes[0]).await.unwrap().is_some());
+        assert!(cache.get_block_with_senders(hashes[0]).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn missing_blocks_expire() {
+        let provider = MockEthProvider::default();
+        let config = EthStateCacheConfig { missing_entry_ttl_ms: 0, ..Default::default() };
+        let cache = EthStateCache::spawn(provider.clone(), config, EthEvmConfig::default());
+
+        let (hashes, notification) = committed_chain(1);
+        assert!(cache.get_block_with_senders(hashes[0]).await.unwrap().is_none());
+
+        // without a ttl the provider is asked again
+        provider.add_block(hashes[0], notification.tip().block.clone().unseal());
+        assert!(cache.get_block_with_senders(hashes[0]).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn warm_up_is_bounded_by_capacity() {
+        let config = EthStateCacheConfig { max_blocks: 8, max_receipts: 8, ..Default::default() };
+        let cache =
+            EthStateCache::spawn(MockEthProvider::default(), config, EthEvmConfig::default());
+
+        let (hashes, notification) = committed_chain(8);
+        cache_new_blocks_task(cache.clone(), futures::stream::iter([notification])).await;
+
+        // only the newest quarter of the capacity is cached
+        for hash in &hashes[..6] {
+            assert!(cache.get_receipts(*hash).await.unwrap().is_none());
+        }
+        for hash in &hashes[6..] {
+            assert!(cache.get_receipts(*hash).await.unwrap().is_some());
+        }
+    }
+}
@@ -115,4 +115,12 @@ where
             metrics: CacheMetrics::new_with_labels(&[("cache", cache_id.to_string())]),
         }
     }
+
+    /// Returns the max number of elements that should be inserted at once when warming the cache.
+    ///
+    /// This is a quarter of the capacity, so warming the cache never evicts the most recently used
+    /// elements.
+    pub fn max_warm_up_len(&self) -> usize {
+        self.cache.limiter().max_length().div_ceil(4) as usize
+    }
 }
@@ -0,0 +1,87 @@
+//! Cache for the results of `eth_call` requests.
+
+use reth_primitives::{keccak256, Bytes, B256};
+use reth_rpc_types::{state::EvmOverrides, TransactionRequest};
+use schnellru::{ByLength, LruMap};
+use serde::Serialize;
+
+/// Identifies an `eth_call` request that is executed on top of a specific block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EthCallCacheKey {
+    /// Hash of the block the call is executed on.
+    block_hash: B256,
+    /// Hash of the call request.
+    request_hash: B256,
+    /// Hash of the state and block overrides.
+    overrides_hash: B256,
+}
+
+impl EthCallCacheKey {
+    /// Creates the key for the `request` executed on top of the block with the given hash.
+    ///
+    /// The request and the overrides are hashed by their JSON encoding. Overrides that are equal
+    /// but encode differently, e.g. because of the order of their entries, only result in a cache
+    /// miss.
+    pub fn new(block_hash: B256, request: &TransactionRequest, overrides: &EvmOverrides) -> Self {
+        Self {
+            block_hash,
+            request_hash: json_hash(request),
+            overrides_hash: json_hash(&(&overrides.state, &overrides.block)),
+        }
+    }
+}
+
+/// Returns the keccak256 hash of the JSON encoding of the value.
+fn json_hash<T: Serialize>(value: &T) -> B256 {
+    keccak256(serde_json::to_vec(value).unwrap_or_default())
+}
+
+/// A size bounded LRU cache of `eth_call` outputs.
+///
+/// Calls on top of a block are deterministic, so their output can be served from the cache as
+/// long as the block is identified by its hash. A cache of size zero is disabled.
+#[derive(Debug)]
+pub struct EthCallCache {
+    outputs: LruMap<EthCallCacheKey, Bytes, ByLength>,
+    max_len: u32,
+}
+
+impl EthCallCache {
+    /// Creates a new cache holding the outputs of at most `max_len` calls.
+    pub fn new(max_len: u32) -> Self {
+        Self { outputs: LruMap::new(ByLength::new(max_len)), max_len }
+    }
+
+    /// Returns true if the cache can hold any outputs.
+    pub const fn is_enabled(&self) -> bool {
+        self.max_len > 0
+    }
+
+    /// Returns the cached output of the call, and promotes it to be the most recently used.
+    pub fn get(&mut self, key: &EthCallCacheKey) -> Option<Bytes> {
+        self.outputs.get(key).cloned()
+    }
+
+    /// Inserts the output of a call, evicting the least recently used one if the cache is full.
+    pub fn insert(&mut self, key: EthCallCacheKey, output: Bytes) {
+        if self.is_enabled() {
+            self.outputs.insert(key, output);
+        }
+    }
+
+    /// Returns the number of cached outputs.
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Returns true if no outputs are cached.
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+}
+
+impl Default for EthCallCache {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
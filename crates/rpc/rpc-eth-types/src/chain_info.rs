@@ -0,0 +1,49 @@
+//! Cached info about the tip of the canonical chain.
+
+use futures::{Stream, StreamExt};
+use reth_chainspec::ChainInfo;
+use reth_provider::CanonStateNotification;
+
+/// Snapshot of the [`ChainInfo`] of the canonical chain, so frequent requests like
+/// `eth_blockNumber` don't need to ask the provider.
+///
+/// The snapshot is updated by [`chain_info_cache_new_blocks_task`] whenever the canonical chain
+/// changes. Blocks that become canonical without a notification, e.g. during pipeline sync, aren't
+/// tracked, so the snapshot should be cleared while the node is syncing.
+#[derive(Debug, Default)]
+pub struct ChainInfoCache {
+    info: parking_lot::RwLock<Option<ChainInfo>>,
+}
+
+impl ChainInfoCache {
+    /// Returns the cached chain info, if any.
+    pub fn get(&self) -> Option<ChainInfo> {
+        *self.info.read()
+    }
+
+    /// Replaces the cached chain info.
+    pub fn set(&self, info: ChainInfo) {
+        *self.info.write() = Some(info);
+    }
+
+    /// Caches the given chain info, unless the cache was already updated with a more recent one.
+    pub fn set_if_empty(&self, info: ChainInfo) {
+        self.info.write().get_or_insert(info);
+    }
+
+    /// Removes the cached chain info.
+    pub fn clear(&self) {
+        *self.info.write() = None;
+    }
+}
+
+/// Awaits for new chain events and caches the tip of the new canonical chain.
+pub async fn chain_info_cache_new_blocks_task<St>(chain_info: &ChainInfoCache, mut events: St)
+where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        let tip = event.tip();
+        chain_info.set(ChainInfo { best_hash: tip.hash(), best_number: tip.number });
+    }
+}
@@ -4,7 +4,7 @@ use std::time::Duration;
 
 use alloy_sol_types::decode_revert_reason;
 use reth_errors::RethError;
-use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes};
+use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes, B256};
 use reth_rpc_server_types::result::{
     internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code,
 };
@@ -64,6 +64,24 @@ pub enum EthApiError {
     /// `eth_sendTransaction`) contains conflicting fields (legacy, EIP-1559)
     #[error("both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified")]
     ConflictingFeeFieldsInRequest,
+    /// Thrown when the gas limit of a call or gas estimation request exceeds the node's configured
+    /// gas cap
+    #[error("requested gas {requested} exceeds the configured gas cap {cap}")]
+    GasCapExceeded {
+        /// The configured gas cap.
+        cap: u64,
+        /// The gas limit of the request.
+        requested: u64,
+    },
+    /// Thrown when an `eth_getProof` request asks for more storage slots than the node's
+    /// configured limit
+    #[error("requested {requested} storage slots exceeds the configured limit {max}")]
+    ProofStorageSlotsExceeded {
+        /// The configured maximum number of storage slots.
+        max: usize,
+        /// The number of distinct storage slots in the request.
+        requested: usize,
+    },
     /// Errors related to invalid transactions
     #[error(transparent)]
     InvalidTransaction(#[from] RpcInvalidTransactionError),
@@ -82,6 +100,9 @@ pub enum EthApiError {
     /// Thrown when a requested transaction is not found
     #[error("transaction not found")]
     TransactionNotFound,
+    /// Thrown when a submitted transaction is rejected by the node's validation policy
+    #[error("transaction rejected: {0}")]
+    TransactionRejected(String),
     /// Some feature is unsupported
     #[error("unsupported")]
     Unsupported(&'static str),
@@ -100,6 +121,9 @@ pub enum EthApiError {
     /// response back to the request handler.
     #[error("internal blocking task error")]
     InternalBlockingTaskError,
+    /// Error thrown when a spawned blocking task panicked, with the panic message.
+    #[error("blocking task panicked: {0}")]
+    BlockingTaskPanicked(String),
     /// Error thrown when a spawned blocking task failed to deliver an anticipated response
     #[error("internal eth error")]
     InternalEthError,
@@ -121,6 +145,19 @@ pub enum EthApiError {
     /// Error encountered when converting a transaction type
     #[error("Transaction conversion error")]
     TransactionConversionError,
+    /// Error thrown when the genesis block in the database isn't the genesis of the configured
+    /// chain, e.g. because the node was started with the datadir of a different chain.
+    #[error(
+        "genesis hash mismatch for chain {chain_id}: expected {expected}, database has {stored}"
+    )]
+    GenesisHashMismatch {
+        /// The id of the configured chain.
+        chain_id: u64,
+        /// The genesis hash of the configured chain.
+        expected: B256,
+        /// The hash of the genesis block in the database.
+        stored: B256,
+    },
     /// Error thrown when tracing with a muxTracer fails
     #[error(transparent)]
     MuxTracerError(#[from] MuxError),
@@ -147,7 +184,14 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthApiError::Signing(_) |
             EthApiError::BothStateAndStateDiffInOverride(_) |
             EthApiError::InvalidTracerConfig |
+            EthApiError::ProofStorageSlotsExceeded { .. } |
             EthApiError::TransactionConversionError => invalid_params_rpc_err(error.to_string()),
+            err @ EthApiError::TransactionRejected(_) => {
+                rpc_error_with_code(EthRpcErrorCode::TransactionRejected.code(), err.to_string())
+            }
+            err @ EthApiError::GasCapExceeded { .. } => {
+                rpc_error_with_code(EthRpcErrorCode::InvalidInput.code(), err.to_string())
+            }
             EthApiError::InvalidTransaction(err) => err.into(),
             EthApiError::PoolError(err) => err.into(),
             EthApiError::PrevrandaoNotSet |
@@ -157,6 +201,7 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthApiError::TransactionNotFound |
             EthApiError::EvmCustom(_) |
             EthApiError::EvmPrecompile(_) |
+            EthApiError::GenesisHashMismatch { .. } |
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
             EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
@@ -171,9 +216,9 @@ impl From<EthApiError> for jsonrpsee_types::error::ErrorObject<'static> {
                 jsonrpsee_types::error::CALL_EXECUTION_FAILED_CODE,
                 err.to_string(),
             ),
-            err @ EthApiError::InternalBlockingTaskError | err @ EthApiError::InternalEthError => {
-                internal_rpc_err(err.to_string())
-            }
+            err @ EthApiError::InternalBlockingTaskError |
+            err @ EthApiError::InternalEthError |
+            err @ EthApiError::BlockingTaskPanicked(_) => internal_rpc_err(err.to_string()),
             err @ EthApiError::TransactionInputError(_) => invalid_params_rpc_err(err.to_string()),
             EthApiError::Other(err) => err.to_rpc_error(),
             EthApiError::MuxTracerError(msg) => internal_rpc_err(msg.to_string()),
@@ -314,9 +359,44 @@ pub enum RpcInvalidTransactionError {
     /// Contains the gas limit.
     #[error("out of gas: invalid operand to an opcode; {0}")]
     InvalidOperandOutOfGas(u64),
+    /// Thrown if `eth_estimateGas` runs out of gas at the configured gas cap, while the block
+    /// gas limit would have allowed for more gas.
+    /// Contains the gas cap.
+    #[error("out of gas: gas required exceeds the configured gas cap: {0}")]
+    GasCapReached(u64),
     /// Thrown if executing a transaction failed during estimate/call
     #[error(transparent)]
     Revert(RevertError),
+    /// The call stack depth limit was exceeded.
+    #[error("max call depth exceeded")]
+    CallTooDeep,
+    /// The stack limit was exceeded.
+    #[error("stack limit reached")]
+    StackOverflow,
+    /// An opcode was executed without enough items on the stack.
+    #[error("stack underflow")]
+    StackUnderflow,
+    /// An undefined opcode was executed.
+    #[error("invalid opcode")]
+    InvalidOpcode,
+    /// A jump targeted an invalid destination.
+    #[error("invalid jump destination")]
+    InvalidJump,
+    /// A state change was attempted during a static call.
+    #[error("write protection")]
+    WriteProtection,
+    /// Return data was read out of its bounds.
+    #[error("return data out of bounds")]
+    ReturnDataOutOfBounds,
+    /// A contract was created at an address that is already in use.
+    #[error("contract address collision")]
+    ContractAddressCollision,
+    /// The code of a created contract exceeds the size limit.
+    #[error("max code size exceeded")]
+    MaxCodeSizeExceeded,
+    /// The code of a created contract begins with `0xEF`.
+    #[error("invalid code: must not begin with 0xef")]
+    InvalidCodeStart,
     /// Unspecific EVM halt error.
     #[error("EVM error: {0:?}")]
     EvmHalt(HaltReason),
@@ -388,11 +468,29 @@ impl RpcInvalidTransactionError {
 
     /// Converts the halt error
     ///
-    /// Takes the configured gas limit of the transaction which is attached to the error
+    /// Takes the configured gas limit of the transaction which is attached to the error. Halt
+    /// reasons without a descriptive variant are reported as [`Self::EvmHalt`].
+    ///
+    /// The messages don't include details that the halt reason lacks, like the stack size or the
+    /// opcode, so they can differ from the ones of other clients.
     pub const fn halt(reason: HaltReason, gas_limit: u64) -> Self {
         match reason {
             HaltReason::OutOfGas(err) => Self::out_of_gas(err, gas_limit),
             HaltReason::NonceOverflow => Self::NonceMaxValue,
+            HaltReason::CallTooDeep => Self::CallTooDeep,
+            HaltReason::StackOverflow => Self::StackOverflow,
+            HaltReason::StackUnderflow => Self::StackUnderflow,
+            HaltReason::OpcodeNotFound => Self::InvalidOpcode,
+            HaltReason::InvalidJump => Self::InvalidJump,
+            HaltReason::StateChangeDuringStaticCall | HaltReason::CallNotAllowedInsideStatic => {
+                Self::WriteProtection
+            }
+            HaltReason::OutOfOffset => Self::ReturnDataOutOfBounds,
+            HaltReason::CreateCollision => Self::ContractAddressCollision,
+            HaltReason::CreateContractSizeLimit => Self::MaxCodeSizeExceeded,
+            HaltReason::CreateContractStartingWithEF => Self::InvalidCodeStart,
+            HaltReason::CreateInitCodeSizeLimit => Self::MaxInitCodeSizeExceeded,
+            HaltReason::OutOfFunds => Self::InsufficientFundsForTransfer,
             err => Self::EvmHalt(err),
         }
     }
@@ -679,4 +777,27 @@ mod tests {
         let err = EthApiError::ExecutionTimedOut(Duration::from_secs(10));
         assert_eq!(err.to_string(), "execution aborted (timeout = 10s)");
     }
+
+    #[test]
+    fn gas_cap_exceeded_error() {
+        let err = EthApiError::GasCapExceeded { cap: 100, requested: 200 };
+        let err = jsonrpsee_types::error::ErrorObject::from(err);
+        assert_eq!(err.code(), EthRpcErrorCode::InvalidInput.code());
+        assert_eq!(err.message(), "requested gas 200 exceeds the configured gas cap 100");
+    }
+
+    #[test]
+    fn halt_reasons_are_descriptive() {
+        let halt = |reason| RpcInvalidTransactionError::halt(reason, 100).to_string();
+        assert_eq!(halt(HaltReason::CallTooDeep), "max call depth exceeded");
+        assert_eq!(halt(HaltReason::StackOverflow), "stack limit reached");
+        assert_eq!(halt(HaltReason::StackUnderflow), "stack underflow");
+        assert_eq!(halt(HaltReason::InvalidJump), "invalid jump destination");
+        assert_eq!(halt(HaltReason::StateChangeDuringStaticCall), "write protection");
+        assert_eq!(
+            halt(HaltReason::OutOfGas(OutOfGasError::Basic)),
+            "out of gas: gas required exceeds allowance: 100"
+        );
+        assert_eq!(halt(HaltReason::EofAuxDataOverflow), "EVM error: EofAuxDataOverflow");
+    }
 }
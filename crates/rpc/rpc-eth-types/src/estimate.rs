@@ -0,0 +1,33 @@
+//! Types returned by the `eth_estimateGas` binary search.
+
+/// The bound that limited the gas range searched by `eth_estimateGas`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasEstimateBound {
+    /// The node's configured gas cap for `eth_estimateGas`.
+    GasCap,
+    /// The block gas limit, or the gas limit of the request if it is higher.
+    BlockGasLimit,
+    /// The gas the caller can afford with its balance at the requested gas price.
+    CallerAllowance,
+}
+
+/// The result of a gas estimation with the bounds the binary search operated in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEstimate {
+    /// The estimated gas limit.
+    pub gas: u64,
+    /// The intrinsic gas of the request, which is the lowest gas limit the search can yield.
+    pub floor: u64,
+    /// The gas limit the search started from.
+    pub highest: u64,
+    /// What limited the highest gas limit of the search.
+    pub bound: GasEstimateBound,
+}
+
+impl GasEstimate {
+    /// Returns true if the search was limited by the configured gas cap rather than by the block
+    /// gas limit or the caller's funds.
+    pub const fn is_capped(&self) -> bool {
+        matches!(self.bound, GasEstimateBound::GasCap)
+    }
+}
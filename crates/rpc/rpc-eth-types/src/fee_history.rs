@@ -3,24 +3,31 @@
 use std::{
     collections::{BTreeMap, VecDeque},
     fmt::Debug,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
     sync::{atomic::Ordering::SeqCst, Arc},
+    time::Instant,
 };
 
 use futures::{
     future::{Fuse, FusedFuture},
-    FutureExt, Stream, StreamExt,
+    FutureExt, Stream, StreamExt, TryStreamExt,
 };
 use metrics::atomics::AtomicU64;
-use reth_chainspec::ChainSpec;
+use reth_chainspec::{BaseFeeParams, ChainSpec};
+use reth_errors::{ProviderError, ProviderResult};
+use reth_fs_util::FsPathError;
 use reth_primitives::{
-    basefee::calc_next_block_base_fee,
     eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
-    Receipt, SealedBlock, TransactionSigned, B256,
+    Block, Header, Receipt, SealedBlock, TransactionSigned, B256, U256,
+};
+use reth_provider::{
+    BlockHashReader, BlockReader, BlockReaderIdExt, CanonStateNotification, ChainSpecProvider,
 };
-use reth_provider::{BlockReaderIdExt, CanonStateNotification, ChainSpecProvider};
 use reth_rpc_types::TxGasAndReward;
+use schnellru::{ByLength, LruMap};
 use serde::{Deserialize, Serialize};
-use tracing::trace;
+use tracing::{debug, field, instrument, trace, warn, Span};
 
 use reth_rpc_server_types::constants::gas_oracle::MAX_HEADER_HISTORY;
 
@@ -36,13 +43,22 @@ pub struct FeeHistoryCache {
 
 impl FeeHistoryCache {
     /// Creates new `FeeHistoryCache` instance, initialize it with the more recent data, set bounds
+    ///
+    /// A zero `max_blocks` or `resolution` in the config is raised to 1.
     pub fn new(eth_cache: EthStateCache, config: FeeHistoryCacheConfig) -> Self {
+        let config = config.sanitized();
+        // memoize the tips of as many blocks as the cache holds
+        let block_tips =
+            LruMap::new(ByLength::new(config.max_blocks.try_into().unwrap_or(u32::MAX)));
         let inner = FeeHistoryCacheInner {
             lower_bound: Default::default(),
             upper_bound: Default::default(),
+            len: Default::default(),
             config,
             entries: Default::default(),
             eth_cache,
+            requested_percentiles: Default::default(),
+            block_tips: parking_lot::Mutex::new(block_tips),
         };
         Self { inner: Arc::new(inner) }
     }
@@ -73,35 +89,79 @@ impl FeeHistoryCache {
     }
 
     /// Insert block data into the cache.
+    ///
+    /// The approximated rewards are not calculated yet, instead they're computed from the
+    /// transactions and receipts of the block, loaded through the [`EthStateCache`], when they're
+    /// first requested, see [`Self::get_history`].
+    #[instrument(target = "rpc::fee", level = "trace", skip_all)]
     async fn insert_blocks<I>(&self, blocks: I)
     where
-        I: IntoIterator<Item = (SealedBlock, Arc<Vec<Receipt>>)>,
+        I: IntoIterator<Item = SealedBlock>,
+    {
+        self.insert_blocks_with_hashes(
+            blocks.into_iter().map(|block| (block.hash(), block.unseal())),
+        )
+        .await
+    }
+
+    /// Insert block data into the cache, using the given hashes instead of sealing the blocks.
+    async fn insert_blocks_with_hashes<I>(&self, blocks: I)
+    where
+        I: IntoIterator<Item = (B256, Block)>,
     {
+        let rewards_len = self.predefined_percentiles().len();
+        let new_entries = blocks
+            .into_iter()
+            .map(|(block_hash, block)| {
+                let block_number = block.number;
+                trace!(target: "rpc::fee", block_number, ?block_hash, "Inserting fee history entry");
+                let mut fee_history_entry = FeeHistoryEntry::from_block_with_hash(&block, block_hash);
+                fee_history_entry.pending_rewards = Some(rewards_len);
+                (block_number, fee_history_entry)
+            })
+            .collect::<Vec<_>>();
+
         let mut entries = self.inner.entries.write().await;
+        entries.extend(new_entries);
+        self.update_bounds(&mut entries);
+    }
 
-        let percentiles = self.predefined_percentiles();
-        // Insert all new blocks and calculate approximated rewards
-        for (block, receipts) in blocks {
-            let mut fee_history_entry = FeeHistoryEntry::new(&block);
-            fee_history_entry.rewards = calculate_reward_percentiles_for_block(
-                &percentiles,
-                fee_history_entry.gas_used,
-                fee_history_entry.base_fee_per_gas,
-                &block.body,
-                &receipts,
-            )
-            .unwrap_or_default();
-            entries.insert(block.number, fee_history_entry);
-        }
+    /// Removes all entries from the cache.
+    pub async fn clear(&self) {
+        let mut entries = self.inner.entries.write().await;
+        entries.clear();
+        self.update_bounds(&mut entries);
+    }
+
+    /// Removes all entries at or above the given block number, e.g. of blocks that were reverted
+    /// by a reorg, and updates the cache bounds accordingly.
+    pub async fn invalidate_from(&self, block_number: u64) {
+        let mut entries = self.inner.entries.write().await;
+        let removed = entries.split_off(&block_number);
+        trace!(target: "rpc::fee", block_number, removed = removed.len(), "Invalidated fee history entries");
+        self.update_bounds(&mut entries);
+    }
 
+    /// Enforces the configured `max_blocks` and [`FeeHistoryCacheEviction`] policy on the given
+    /// entries and updates the cache bounds accordingly.
+    fn update_bounds(&self, entries: &mut BTreeMap<u64, FeeHistoryEntry>) {
         // enforce bounds by popping the oldest entries
         while entries.len() > self.inner.config.max_blocks as usize {
             entries.pop_first();
         }
 
-        if entries.len() == 0 {
+        if let FeeHistoryCacheEviction::MaxBytes(max_bytes) = self.inner.config.eviction {
+            let mut size = entries.values().map(FeeHistoryEntry::approximate_size).sum::<usize>();
+            while entries.len() > 1 && size as u64 > max_bytes {
+                let (_, entry) = entries.pop_first().expect("Contains more than one entry");
+                size -= entry.approximate_size();
+            }
+        }
+
+        if entries.is_empty() {
             self.inner.upper_bound.store(0, SeqCst);
             self.inner.lower_bound.store(0, SeqCst);
+            self.inner.len.store(0, SeqCst);
             return
         }
 
@@ -116,6 +176,146 @@ impl FeeHistoryCache {
         let lower_bound = *entries.first_entry().expect("Contains at least one entry").key();
         self.inner.upper_bound.store(upper_bound, SeqCst);
         self.inner.lower_bound.store(lower_bound, SeqCst);
+        self.inner.len.store(entries.len() as u64, SeqCst);
+    }
+
+    /// Writes the cached entries whose rewards were already computed to the snapshot file at the
+    /// given path.
+    ///
+    /// The snapshot can be loaded with [`Self::load_snapshot`] after a restart, so that the
+    /// rewards of the cached blocks don't need to be recomputed. Entries with pending rewards are
+    /// skipped, they're cheap to restore without the snapshot. The snapshot is serialized and
+    /// written on a blocking task, to a temporary file that then replaces the previous snapshot,
+    /// so a crash while writing can't leave a corrupt snapshot behind.
+    pub async fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), FsPathError> {
+        let path = path.as_ref().to_path_buf();
+        let snapshot = {
+            let entries = self.inner.entries.read().await;
+            FeeHistorySnapshot {
+                resolution: self.resolution(),
+                entries: entries
+                    .iter()
+                    .filter(|(_, entry)| !entry.has_pending_rewards())
+                    .map(|(number, entry)| (*number, entry.clone()))
+                    .collect(),
+            }
+        };
+        let task_path = path.clone();
+        tokio::task::spawn_blocking(move || {
+            let contents = serde_json::to_vec(&snapshot)
+                .map_err(|err| FsPathError::WriteJson { source: err, path: task_path.clone() })?;
+            let mut tmp_path = task_path.clone().into_os_string();
+            tmp_path.push(".tmp");
+            reth_fs_util::write(&tmp_path, contents)?;
+            reth_fs_util::rename(tmp_path, task_path)
+        })
+        .await
+        .map_err(|err| FsPathError::Write { source: std::io::Error::other(err), path })?
+    }
+
+    /// Loads the entries of the snapshot file at the given path into the cache.
+    ///
+    /// Entries are only reused if their `header_hash` still matches the canonical hash at that
+    /// block number, all other entries are discarded and will be recomputed. A snapshot that was
+    /// written with a different resolution is discarded entirely, since its reward grid doesn't
+    /// match the configured percentiles.
+    ///
+    /// Returns the number of entries that were loaded.
+    pub async fn load_snapshot<Provider>(
+        &self,
+        path: impl AsRef<Path>,
+        provider: &Provider,
+    ) -> Result<usize, FsPathError>
+    where
+        Provider: BlockHashReader,
+    {
+        let path = path.as_ref();
+        let contents = reth_fs_util::read(path)?;
+        let snapshot: FeeHistorySnapshot = serde_json::from_slice(&contents)
+            .map_err(|err| FsPathError::ReadJson { source: err, path: path.into() })?;
+
+        if snapshot.resolution != self.resolution() {
+            debug!(target: "rpc::fee", resolution=snapshot.resolution, "Discarding fee history snapshot with mismatching resolution");
+            return Ok(0)
+        }
+
+        let mut entries = self.inner.entries.write().await;
+        let mut loaded = 0;
+        for (block_number, entry) in snapshot.entries {
+            // only reuse entries that are still canonical
+            if provider.block_hash(block_number).ok().flatten() != Some(entry.header_hash) {
+                trace!(target: "rpc::fee", ?block_number, "Discarding non-canonical fee history snapshot entry");
+                continue
+            }
+            entries.insert(block_number, entry);
+            loaded += 1;
+        }
+
+        self.update_bounds(&mut entries);
+
+        Ok(loaded)
+    }
+
+    /// Fills the cache with the most recent `max_blocks` blocks up to and including `last_block`,
+    /// skipping blocks that are already cached, e.g. loaded from a snapshot.
+    ///
+    /// Blocks are read in chunks of the configured `backfill_chunk_size`, and the task yields
+    /// after each chunk, so a cold start doesn't read the entire range in one burst. The receipts
+    /// of a chunk are fetched with at most `backfill_concurrency` concurrent requests.
+    pub async fn backfill<Provider>(
+        &self,
+        provider: &Provider,
+        last_block: u64,
+    ) -> ProviderResult<()>
+    where
+        Provider: BlockReader,
+    {
+        let config = self.config();
+        let first_block = last_block.saturating_sub(config.max_blocks - 1);
+        let chunk_size = config.backfill_chunk_size;
+        let concurrency = config.backfill_concurrency.unwrap_or(chunk_size as usize).max(1);
+
+        let mut chunk_start = first_block;
+        while chunk_start <= last_block {
+            let chunk_end = chunk_start.saturating_add(chunk_size - 1).min(last_block);
+            trace!(target: "rpc::fee", chunk_start, chunk_end, "Backfilling fee history cache");
+
+            let missing = {
+                let entries = self.inner.entries.read().await;
+                (chunk_start..=chunk_end)
+                    .filter(|number| !entries.contains_key(number))
+                    .collect::<Vec<_>>()
+            };
+            if !missing.is_empty() {
+                // the hashes are known to the provider, so the blocks don't need to be sealed
+                let hashes = provider.canonical_hashes_range(chunk_start, chunk_end + 1)?;
+                let blocks = provider
+                    .block_range(chunk_start..=chunk_end)?
+                    .into_iter()
+                    .filter(|block| missing.contains(&block.number))
+                    .filter_map(|block| {
+                        let hash = *hashes.get((block.number - chunk_start) as usize)?;
+                        Some((hash, block))
+                    });
+                // the receipts aren't kept, but fetching them skips blocks whose receipts aren't
+                // available yet and warms the state cache the rewards are computed from
+                let blocks = futures::stream::iter(blocks)
+                    .map(|(hash, block)| async move {
+                        let receipts = self.inner.eth_cache.get_receipts(hash).await?;
+                        Ok::<_, ProviderError>(receipts.map(|_| (hash, block)))
+                    })
+                    .buffered(concurrency)
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                self.insert_blocks_with_hashes(blocks.into_iter().flatten()).await;
+            }
+
+            chunk_start = chunk_end + 1;
+            // give other tasks a chance to run between chunks
+            tokio::task::yield_now().await;
+        }
+
+        Ok(())
     }
 
     /// Get `UpperBound` value for `FeeHistoryCache`
@@ -128,43 +328,280 @@ impl FeeHistoryCache {
         self.inner.lower_bound.load(SeqCst)
     }
 
+    /// Returns the range of blocks the cache can serve and how it is configured.
+    pub fn info(&self) -> FeeHistoryCacheInfo {
+        FeeHistoryCacheInfo {
+            lower_bound: self.lower_bound(),
+            upper_bound: self.upper_bound(),
+            max_blocks: self.config().max_blocks,
+            resolution: self.resolution(),
+            len: self.inner.len.load(SeqCst),
+        }
+    }
+
     /// Collect fee history for given range.
     ///
     /// This function retrieves fee history entries from the cache for the specified range.
     /// If the requested range (`start_block` to `end_block`) is within the cache bounds,
     /// it returns the corresponding entries.
     /// Otherwise it returns None.
+    ///
+    /// If `with_rewards` is set, the rewards of entries that weren't requested before are computed
+    /// and memoized in the cache, otherwise the `rewards` of such entries are empty. If the block
+    /// of such an entry isn't available anymore, e.g. because it was reorged, `None` is
+    /// returned, and an error is returned if the rewards can't be computed.
+    ///
+    /// The lookup is traced in a `get_history` span that records whether it was a cache hit and
+    /// how long it took to compute the rewards.
+    #[instrument(
+        target = "rpc::fee",
+        level = "trace",
+        skip(self),
+        fields(cache_hit = field::Empty, rewards_elapsed = field::Empty)
+    )]
     pub async fn get_history(
         &self,
         start_block: u64,
         end_block: u64,
-    ) -> Option<Vec<FeeHistoryEntry>> {
+        with_rewards: bool,
+    ) -> Result<Option<Vec<FeeHistoryEntry>>, EthApiError> {
         let lower_bound = self.lower_bound();
         let upper_bound = self.upper_bound();
         if start_block >= lower_bound && end_block <= upper_bound {
-            let entries = self.inner.entries.read().await;
-            let result = entries
-                .range(start_block..=end_block)
-                .map(|(_, fee_entry)| fee_entry.clone())
-                .collect::<Vec<_>>();
+            let mut result = {
+                let entries = self.inner.entries.read().await;
+                entries
+                    .range(start_block..=end_block)
+                    .map(|(number, fee_entry)| (*number, fee_entry.clone()))
+                    .collect::<Vec<_>>()
+            };
 
             if result.is_empty() {
-                return None
+                Span::current().record("cache_hit", false);
+                return Ok(None)
             }
 
-            Some(result)
+            if with_rewards && result.iter().any(|(_, entry)| entry.has_pending_rewards()) {
+                // the rewards are computed without holding the lock and then written back
+                let started = Instant::now();
+                let computed = self.compute_rewards(&mut result).await;
+                Span::current().record("rewards_elapsed", field::debug(started.elapsed()));
+                if !computed? {
+                    Span::current().record("cache_hit", false);
+                    return Ok(None)
+                }
+
+                let mut entries = self.inner.entries.write().await;
+                for (number, entry) in &result {
+                    if let Some(cached) = entries.get_mut(number) {
+                        if cached.header_hash == entry.header_hash && cached.has_pending_rewards() {
+                            cached.rewards.clone_from(&entry.rewards);
+                            cached.reward_percentiles.clone_from(&entry.reward_percentiles);
+                            cached.pending_rewards = None;
+                        }
+                    }
+                }
+            }
+
+            Span::current().record("cache_hit", true);
+            Ok(Some(result.into_iter().map(|(_, entry)| entry).collect()))
         } else {
-            None
+            Span::current().record("cache_hit", false);
+            Ok(None)
+        }
+    }
+
+    /// Collect fee history for given range like [`Self::get_history`], but only if all entries
+    /// are still canonical.
+    ///
+    /// Entries are keyed by block number, so after a reorg the cache can hold entries of blocks
+    /// that were replaced until the new blocks are processed. If the `header_hash` of any entry
+    /// doesn't match the canonical hash at its number, the range is treated as a cache miss and
+    /// `None` is returned.
+    pub async fn get_canonical_history<Provider>(
+        &self,
+        start_block: u64,
+        end_block: u64,
+        with_rewards: bool,
+        provider: &Provider,
+    ) -> Result<Option<Vec<FeeHistoryEntry>>, EthApiError>
+    where
+        Provider: BlockHashReader,
+    {
+        let Some(entries) = self.get_history(start_block, end_block, with_rewards).await? else {
+            return Ok(None)
+        };
+
+        let canonical_hashes = provider.canonical_hashes_range(start_block, end_block + 1)?;
+        let is_canonical = entries.len() == canonical_hashes.len() &&
+            entries
+                .iter()
+                .zip(&canonical_hashes)
+                .all(|(entry, hash)| entry.header_hash == *hash);
+        if !is_canonical {
+            trace!(target: "rpc::fee", ?start_block, ?end_block, "Fee history cache holds non-canonical entries");
+            return Ok(None)
         }
+
+        Ok(Some(entries))
+    }
+
+    /// Records the percentiles of a reward request.
+    ///
+    /// If [`FeeHistoryCacheConfig::adaptive_window`] is set, the rewards of new entries are only
+    /// computed for the percentiles of the most recently requested distinct sets, otherwise this
+    /// is a no-op.
+    pub fn record_requested_percentiles(&self, percentiles: &[f64]) {
+        let Some(window) = self.config().adaptive_window else { return };
+        self.inner.requested_percentiles.lock().record(percentiles, window);
     }
 
     /// Generates predefined set of percentiles
     ///
-    /// This returns 100 * resolution points
+    /// This returns 100 * resolution points, or the recently requested percentiles if the
+    /// adaptive mode is enabled and any were recorded, see
+    /// [`Self::record_requested_percentiles`].
     pub fn predefined_percentiles(&self) -> Vec<f64> {
+        self.adaptive_percentiles().unwrap_or_else(|| self.uniform_percentiles())
+    }
+
+    /// Returns the grid of 100 * resolution points.
+    fn uniform_percentiles(&self) -> Vec<f64> {
         let res = self.resolution() as f64;
         (0..=100 * self.resolution()).map(|p| p as f64 / res).collect()
     }
+
+    /// Returns the recently requested percentiles if the adaptive mode is enabled and any were
+    /// recorded.
+    fn adaptive_percentiles(&self) -> Option<Vec<f64>> {
+        self.config().adaptive_window?;
+        let percentiles = self.inner.requested_percentiles.lock().percentiles();
+        (!percentiles.is_empty()).then_some(percentiles)
+    }
+
+    /// Computes the pending rewards of the given entries for the
+    /// [`Self::predefined_percentiles`].
+    ///
+    /// Returns `false` if the block of an entry isn't available anymore, in which case the
+    /// remaining entries are left pending.
+    async fn compute_rewards(
+        &self,
+        entries: &mut [(u64, FeeHistoryEntry)],
+    ) -> Result<bool, EthApiError> {
+        let adaptive = self.adaptive_percentiles();
+        let is_adaptive = adaptive.is_some();
+        let percentiles = adaptive.unwrap_or_else(|| self.uniform_percentiles());
+        for (_, entry) in entries {
+            if !entry.has_pending_rewards() {
+                continue
+            }
+            let Some(tips) =
+                self.load_block_tips(entry.header_hash, entry.base_fee_per_gas).await?
+            else {
+                return Ok(false)
+            };
+            entry.reward_percentiles = if is_adaptive { percentiles.clone() } else { Vec::new() };
+            entry.rewards = reward_percentiles_from_tips(&percentiles, entry.gas_used, &tips);
+            entry.pending_rewards = None;
+        }
+        Ok(true)
+    }
+
+    /// Returns the tips of the block with the given hash like [`Self::block_tips`], loading its
+    /// transactions and receipts through the [`EthStateCache`] unless the tips are memoized.
+    ///
+    /// Returns `None` if the block isn't available.
+    async fn load_block_tips(
+        &self,
+        block_hash: B256,
+        base_fee_per_gas: u64,
+    ) -> Result<Option<Arc<Vec<TxGasAndReward>>>, EthApiError> {
+        if let Some(tips) = self.memoized_block_tips(block_hash) {
+            return Ok(Some(tips))
+        }
+        let Some((transactions, receipts)) =
+            self.inner.eth_cache.get_transactions_and_receipts(block_hash).await?
+        else {
+            return Ok(None)
+        };
+        self.block_tips(block_hash, base_fee_per_gas, &transactions, &receipts).map(Some)
+    }
+
+    /// Returns the gas used and effective tip of the transactions of the block with the given
+    /// hash, sorted by tip, see [`sorted_transaction_tips`].
+    ///
+    /// The tips are memoized for as many blocks as the cache holds, so rewards requested
+    /// repeatedly for the same block, e.g. for percentiles the cached entries don't cover, don't
+    /// recompute them.
+    pub fn block_tips(
+        &self,
+        block_hash: B256,
+        base_fee_per_gas: u64,
+        transactions: &[TransactionSigned],
+        receipts: &[Receipt],
+    ) -> Result<Arc<Vec<TxGasAndReward>>, EthApiError> {
+        if let Some(tips) = self.memoized_block_tips(block_hash) {
+            return Ok(tips)
+        }
+        let tips = Arc::new(sorted_transaction_tips(base_fee_per_gas, transactions, receipts)?);
+        self.inner.block_tips.lock().insert(block_hash, tips.clone());
+        Ok(tips)
+    }
+
+    /// Returns the memoized tips of the block with the given hash, see [`Self::block_tips`].
+    pub fn memoized_block_tips(&self, block_hash: B256) -> Option<Arc<Vec<TxGasAndReward>>> {
+        self.inner.block_tips.lock().get(&block_hash).cloned()
+    }
+}
+
+/// The distinct percentile sets of the most recent reward requests, see
+/// [`FeeHistoryCacheConfig::adaptive_window`].
+#[derive(Debug, Default)]
+struct RequestedPercentiles {
+    /// The requested sets, the most recently requested one last.
+    sets: VecDeque<Vec<f64>>,
+}
+
+impl RequestedPercentiles {
+    /// Marks the set as the most recently requested one and forgets the least recently requested
+    /// sets beyond the window.
+    fn record(&mut self, percentiles: &[f64], window: usize) {
+        if let Some(index) = self.sets.iter().position(|set| set == percentiles) {
+            let set = self.sets.remove(index).expect("index is in bounds");
+            self.sets.push_back(set);
+        } else {
+            self.sets.push_back(percentiles.to_vec());
+        }
+        while self.sets.len() > window {
+            self.sets.pop_front();
+        }
+    }
+
+    /// Returns the sorted union of all recorded sets.
+    fn percentiles(&self) -> Vec<f64> {
+        let mut percentiles = self.sets.iter().flatten().copied().collect::<Vec<_>>();
+        percentiles.sort_by(f64::total_cmp);
+        percentiles.dedup();
+        percentiles
+    }
+}
+
+/// The state of the [`FeeHistoryCache`], see [`FeeHistoryCache::info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistoryCacheInfo {
+    /// The oldest cached block.
+    pub lower_bound: u64,
+    /// The newest cached block.
+    pub upper_bound: u64,
+    /// Max number of blocks in cache.
+    pub max_blocks: u64,
+    /// Percentile approximation resolution.
+    pub resolution: u64,
+    /// Number of cached blocks.
+    ///
+    /// This is less than the size of the bounds if blocks in between are missing.
+    pub len: u64,
 }
 
 /// Settings for the [`FeeHistoryCache`].
@@ -180,14 +617,101 @@ pub struct FeeHistoryCacheConfig {
     ///
     /// Default is 4 which means 0.25
     pub resolution: u64,
+    /// Optional path of the on-disk snapshot of the cached entries.
+    ///
+    /// If set, the snapshot is loaded on startup and periodically rewritten, so the cache doesn't
+    /// need to be rebuilt from disk after a restart.
+    #[serde(default)]
+    pub snapshot_path: Option<PathBuf>,
+    /// How cached entries are evicted in addition to the `max_blocks` bound.
+    #[serde(default)]
+    pub eviction: FeeHistoryCacheEviction,
+    /// Number of blocks read at once when the cache is backfilled on startup.
+    #[serde(default = "default_backfill_chunk_size")]
+    pub backfill_chunk_size: u64,
+    /// Maximum number of concurrent receipt requests per backfill chunk.
+    ///
+    /// Defaults to the chunk size if unset.
+    #[serde(default)]
+    pub backfill_concurrency: Option<usize>,
+    /// Number of distinct recently requested percentile sets the rewards are computed for.
+    ///
+    /// If set, the rewards of cached blocks are only computed for the percentiles of these sets
+    /// instead of the grid of the configured resolution, which saves memory and time if clients
+    /// keep requesting the same percentiles. Requests for other percentiles are served from disk.
+    #[serde(default)]
+    pub adaptive_window: Option<usize>,
+}
+
+/// Default number of blocks read at once when the [`FeeHistoryCache`] is backfilled.
+pub const DEFAULT_BACKFILL_CHUNK_SIZE: u64 = 64;
+
+const fn default_backfill_chunk_size() -> u64 {
+    DEFAULT_BACKFILL_CHUNK_SIZE
+}
+
+impl FeeHistoryCacheConfig {
+    /// Returns the config with a zero `max_blocks` or `resolution` raised to 1, since the cache
+    /// can't work with either being zero.
+    pub fn sanitized(mut self) -> Self {
+        if self.max_blocks == 0 {
+            warn!(target: "rpc::fee", "Fee history cache max blocks must be non-zero, using 1");
+            self.max_blocks = 1;
+        }
+        if self.resolution == 0 {
+            warn!(target: "rpc::fee", "Fee history cache resolution must be non-zero, using 1");
+            self.resolution = 1;
+        }
+        if self.backfill_chunk_size == 0 {
+            warn!(target: "rpc::fee", "Fee history cache backfill chunk size must be non-zero, using 1");
+            self.backfill_chunk_size = 1;
+        }
+        if self.adaptive_window == Some(0) {
+            warn!(target: "rpc::fee", "Fee history cache adaptive window must be non-zero, using 1");
+            self.adaptive_window = Some(1);
+        }
+        self
+    }
 }
 
 impl Default for FeeHistoryCacheConfig {
     fn default() -> Self {
-        Self { max_blocks: MAX_HEADER_HISTORY + 100, resolution: 4 }
+        Self {
+            max_blocks: MAX_HEADER_HISTORY + 100,
+            resolution: 4,
+            snapshot_path: None,
+            eviction: FeeHistoryCacheEviction::default(),
+            backfill_chunk_size: DEFAULT_BACKFILL_CHUNK_SIZE,
+            backfill_concurrency: None,
+            adaptive_window: None,
+        }
     }
 }
 
+/// Eviction policy of the [`FeeHistoryCache`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeHistoryCacheEviction {
+    /// The cache is only bounded by the number of blocks.
+    #[default]
+    MaxBlocks,
+    /// The oldest entries are also evicted once the approximate size of all entries exceeds the
+    /// given number of bytes.
+    ///
+    /// The size of an entry is dominated by its rewards, which grow with the configured
+    /// resolution, so this caps the memory of the cache independently of the resolution.
+    MaxBytes(u64),
+}
+
+/// On-disk representation of the [`FeeHistoryCache`] entries.
+#[derive(Debug, Serialize, Deserialize)]
+struct FeeHistorySnapshot {
+    /// The resolution the rewards of the entries were computed with.
+    resolution: u64,
+    /// All cached entries keyed by block number.
+    entries: Vec<(u64, FeeHistoryEntry)>,
+}
+
 /// Container type for shared state in [`FeeHistoryCache`]
 #[derive(Debug)]
 struct FeeHistoryCacheInner {
@@ -195,16 +719,28 @@ struct FeeHistoryCacheInner {
     lower_bound: AtomicU64,
     /// Stores the upper bound of the cache
     upper_bound: AtomicU64,
+    /// Stores the number of entries in the cache
+    len: AtomicU64,
     /// Config for `FeeHistoryCache`, consists of resolution for percentile approximation
     /// and max number of blocks
     config: FeeHistoryCacheConfig,
     /// Stores the entries of the cache
     entries: tokio::sync::RwLock<BTreeMap<u64, FeeHistoryEntry>>,
     eth_cache: EthStateCache,
+    /// The recently requested percentiles, only tracked in the adaptive mode.
+    requested_percentiles: parking_lot::Mutex<RequestedPercentiles>,
+    /// The sorted transaction tips of recently requested blocks, keyed by block hash.
+    block_tips: parking_lot::Mutex<LruMap<B256, Arc<Vec<TxGasAndReward>>, ByLength>>,
 }
 
+/// Number of new chain events after which the fee history snapshot is rewritten, if configured.
+const SNAPSHOT_INTERVAL: usize = 32;
+
 /// Awaits for new chain events and directly inserts them into the cache so they're available
 /// immediately before they need to be fetched from disk.
+///
+/// If a snapshot path is configured, the snapshot is loaded before processing any events and
+/// rewritten every [`SNAPSHOT_INTERVAL`] events and once the stream ends.
 pub async fn fee_history_cache_new_blocks_task<St, Provider>(
     fee_history_cache: FeeHistoryCache,
     mut events: St,
@@ -218,6 +754,30 @@ pub async fn fee_history_cache_new_blocks_task<St, Provider>(
     let mut missing_blocks = VecDeque::new();
     let mut fetch_missing_block = Fuse::terminated();
 
+    let snapshot_path = fee_history_cache.config().snapshot_path.clone();
+    if let Some(path) = &snapshot_path {
+        match fee_history_cache.load_snapshot(path, &provider).await {
+            Ok(loaded) => {
+                debug!(target: "rpc::fee", ?path, loaded, "Loaded fee history snapshot");
+            }
+            Err(err) => {
+                debug!(target: "rpc::fee", %err, "Failed to load fee history snapshot");
+            }
+        }
+    }
+    // fill the cache with the most recent blocks that weren't restored from the snapshot
+    match provider.best_block_number() {
+        Ok(best_block) => {
+            if let Err(err) = fee_history_cache.backfill(&provider, best_block).await {
+                debug!(target: "rpc::fee", %err, "Failed to backfill fee history cache");
+            }
+        }
+        Err(err) => {
+            debug!(target: "rpc::fee", %err, "Failed to read best block for fee history backfill");
+        }
+    }
+    let mut events_since_snapshot = 0;
+
     loop {
         if fetch_missing_block.is_terminated() {
             if let Some(block_number) = missing_blocks.pop_front() {
@@ -237,35 +797,109 @@ pub async fn fee_history_cache_new_blocks_task<St, Provider>(
         tokio::select! {
             res = &mut fetch_missing_block =>  {
                 if let Ok(res) = res {
-                    fee_history_cache.insert_blocks(res.into_iter()).await;
+                    fee_history_cache.insert_blocks(res.map(|(block, _)| block)).await;
                 }
             }
             event = events.next() =>  {
                 let Some(event) = event else {
                      // the stream ended, we are done
+                    if let Some(path) = &snapshot_path {
+                        if let Err(err) = fee_history_cache.save_snapshot(path).await {
+                            debug!(target: "rpc::fee", %err, "Failed to write fee history snapshot");
+                        }
+                    }
                     break;
                 };
-                let (blocks, receipts): (Vec<_>, Vec<_>) = event
-                    .committed()
-                    .blocks_and_receipts()
-                    .map(|(block, receipts)| {
-                        (block.block.clone(), Arc::new(receipts.iter().flatten().cloned().collect::<Vec<_>>()))
-                    })
-                    .unzip();
-                fee_history_cache.insert_blocks(blocks.into_iter().zip(receipts)).await;
+                if let Some(reverted) = event.reverted() {
+                    // the reverted blocks must not be served if the new chain is shorter
+                    fee_history_cache.invalidate_from(reverted.first().number).await;
+                }
+                let blocks = event.committed().blocks_iter().map(|block| block.block.clone()).collect::<Vec<_>>();
+                fee_history_cache.insert_blocks(blocks).await;
 
                 // keep track of missing blocks
                 missing_blocks = fee_history_cache.missing_consecutive_blocks().await;
+
+                if let Some(path) = &snapshot_path {
+                    events_since_snapshot += 1;
+                    if events_since_snapshot >= SNAPSHOT_INTERVAL {
+                        events_since_snapshot = 0;
+                        if let Err(err) = fee_history_cache.save_snapshot(path).await {
+                            debug!(target: "rpc::fee", %err, "Failed to write fee history snapshot");
+                        }
+                    }
+                }
             }
         }
     }
 }
 
+/// Returns the ratio of gas used to the gas limit of a block.
+///
+/// Blocks with a zero gas limit have a ratio of zero instead of `NaN`, which would be serialized
+/// as `null`.
+pub fn gas_used_ratio(gas_used: u64, gas_limit: u64) -> f64 {
+    if gas_limit == 0 {
+        return 0.0
+    }
+    gas_used as f64 / gas_limit as f64
+}
+
+/// Returns the base fee of the block after a block with the given gas usage and base fee, according
+/// to the EIP-1559 spec.
+///
+/// Unlike [`calc_next_block_base_fee`](reth_primitives::basefee::calc_next_block_base_fee), the
+/// arithmetic is done in [`U256`], so extreme base fees or misconfigured base fee params can't
+/// overflow, and the result saturates at [`u64::MAX`] instead of wrapping. A block with a zero gas
+/// target keeps its base fee.
+pub fn next_block_base_fee(
+    gas_used: u64,
+    gas_limit: u64,
+    base_fee: u64,
+    base_fee_params: BaseFeeParams,
+) -> u64 {
+    let gas_target = U256::from(gas_limit)
+        .checked_div(U256::from(base_fee_params.elasticity_multiplier))
+        .unwrap_or_default();
+    let denominator = gas_target * U256::from(base_fee_params.max_change_denominator);
+    if denominator.is_zero() {
+        return base_fee
+    }
+
+    let (gas_used, base_fee) = (U256::from(gas_used), U256::from(base_fee));
+    let next_base_fee = match gas_used.cmp(&gas_target) {
+        std::cmp::Ordering::Equal => base_fee,
+        std::cmp::Ordering::Greater => {
+            // the base fee increases by at least 1
+            base_fee + (base_fee * (gas_used - gas_target) / denominator).max(U256::from(1))
+        }
+        std::cmp::Ordering::Less => {
+            base_fee.saturating_sub(base_fee * (gas_target - gas_used) / denominator)
+        }
+    };
+    next_base_fee.saturating_to()
+}
+
+/// Returns the range of blocks served by `eth_feeHistory` for a request of `block_count` blocks
+/// that ends at `newest_block`.
+///
+/// Like geth, the block count is clamped to `max_block_count` and the range is clamped at the
+/// genesis block, so fewer blocks are returned near genesis. Returns `None` if no block is served.
+pub fn fee_history_range(
+    block_count: u64,
+    newest_block: u64,
+    max_block_count: u64,
+) -> Option<RangeInclusive<u64>> {
+    let block_count = block_count.min(max_block_count).min(newest_block.saturating_add(1));
+    (block_count > 0).then(|| newest_block + 1 - block_count..=newest_block)
+}
+
 /// Calculates reward percentiles for transactions in a block header.
 /// Given a list of percentiles and a sealed block header, this function computes
 /// the corresponding rewards for the transactions at each percentile.
 ///
-/// The results are returned as a vector of U256 values.
+/// The results are returned as a vector of U256 values. Empty blocks return a zero row, and an
+/// error is returned if the number of receipts doesn't match the number of transactions.
 pub fn calculate_reward_percentiles_for_block(
     percentiles: &[f64],
     gas_used: u64,
@@ -273,6 +907,29 @@ pub fn calculate_reward_percentiles_for_block(
     transactions: &[TransactionSigned],
     receipts: &[Receipt],
 ) -> Result<Vec<u128>, EthApiError> {
+    let tips = sorted_transaction_tips(base_fee_per_gas, transactions, receipts)?;
+    Ok(reward_percentiles_from_tips(percentiles, gas_used, &tips))
+}
+
+/// Returns the gas used and effective tip of the transactions of a block, sorted by tip in
+/// ascending order.
+///
+/// An error is returned if the number of receipts doesn't match the number of transactions.
+pub fn sorted_transaction_tips(
+    base_fee_per_gas: u64,
+    transactions: &[TransactionSigned],
+    receipts: &[Receipt],
+) -> Result<Vec<TxGasAndReward>, EthApiError> {
+    // Empty blocks have no tips
+    if transactions.is_empty() {
+        return Ok(Vec::new())
+    }
+
+    // Every transaction needs a receipt to derive its gas usage
+    if transactions.len() != receipts.len() {
+        return Err(EthApiError::InternalEthError)
+    }
+
     let mut transactions = transactions
         .iter()
         .zip(receipts)
@@ -286,6 +943,9 @@ pub fn calculate_reward_percentiles_for_block(
             let gas_used = receipt.cumulative_gas_used - *previous_gas;
             *previous_gas = receipt.cumulative_gas_used;
 
+            // The tip of legacy and EIP-2930 transactions is their gas price minus the base fee.
+            // There is no tip if the fee cap is below the base fee, which can't happen for
+            // transactions included in a valid block.
             Some(TxGasAndReward {
                 gas_used,
                 reward: tx.effective_tip_per_gas(Some(base_fee_per_gas)).unwrap_or_default(),
@@ -296,33 +956,57 @@ pub fn calculate_reward_percentiles_for_block(
     // Sort the transactions by their rewards in ascending order
     transactions.sort_by_key(|tx| tx.reward);
 
+    Ok(transactions)
+}
+
+/// Calculates reward percentiles of a block from the tips of its transactions, as returned by
+/// [`sorted_transaction_tips`].
+///
+/// Empty blocks return a zero row.
+pub fn reward_percentiles_from_tips(
+    percentiles: &[f64],
+    gas_used: u64,
+    tips: &[TxGasAndReward],
+) -> Vec<u128> {
     // Find the transaction that corresponds to the given percentile
     //
     // We use a `tx_index` here that is shared across all percentiles, since we know
     // the percentiles are monotonically increasing.
     let mut tx_index = 0;
-    let mut cumulative_gas_used = transactions.first().map(|tx| tx.gas_used).unwrap_or_default();
-    let mut rewards_in_block = Vec::new();
-    for percentile in percentiles {
-        // Empty blocks should return in a zero row
-        if transactions.is_empty() {
-            rewards_in_block.push(0);
-            continue
-        }
-
-        let threshold = (gas_used as f64 * percentile / 100.) as u64;
-        while cumulative_gas_used < threshold && tx_index < transactions.len() - 1 {
+    let mut cumulative_gas_used = tips.first().map(|tx| tx.gas_used).unwrap_or_default();
+    let mut rewards_in_block = Vec::with_capacity(percentiles.len());
+    for &percentile in percentiles {
+        let threshold = reward_threshold(gas_used, percentile);
+        while cumulative_gas_used < threshold {
+            let Some(tx) = tips.get(tx_index + 1) else { break };
             tx_index += 1;
-            cumulative_gas_used += transactions[tx_index].gas_used;
+            cumulative_gas_used += tx.gas_used;
         }
-        rewards_in_block.push(transactions[tx_index].reward);
+        let reward = tips.get(tx_index).map(|tx| tx.reward).unwrap_or_default();
+        rewards_in_block.push(reward);
     }
 
-    Ok(rewards_in_block)
+    rewards_in_block
+}
+
+/// The precision of the percentiles in [`reward_threshold`], i.e. percentiles are rounded to
+/// millionths of a percent.
+const PERCENTILE_SCALE: u128 = 1_000_000;
+
+/// Returns the gas used by the transactions of a block up to the given percentile, i.e.
+/// `gas_used * percentile / 100`.
+///
+/// The percentile is converted to a fixed point number with [`PERCENTILE_SCALE`] and the
+/// threshold is computed in integer arithmetic, so it's exact for any gas used and doesn't depend
+/// on floating point rounding.
+pub fn reward_threshold(gas_used: u64, percentile: f64) -> u64 {
+    let numerator = (percentile * PERCENTILE_SCALE as f64).round() as u128;
+    let threshold = gas_used as u128 * numerator / (100 * PERCENTILE_SCALE);
+    threshold.try_into().unwrap_or(u64::MAX)
 }
 
 /// A cached entry for a block's fee history.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FeeHistoryEntry {
     /// The base fee per gas for this block.
     pub base_fee_per_gas: u64,
@@ -348,9 +1032,22 @@ pub struct FeeHistoryEntry {
     /// Hash of the block.
     pub header_hash: B256,
     /// Approximated rewards for the configured percentiles.
+    ///
+    /// Empty until the rewards were requested for the first time, see
+    /// [`FeeHistoryEntry::has_pending_rewards`].
     pub rewards: Vec<u128>,
+    /// The percentiles the `rewards` were computed for, if they were computed for the requested
+    /// percentiles in the adaptive mode of the cache.
+    ///
+    /// Empty if the rewards are the grid of the configured resolution.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reward_percentiles: Vec<f64>,
     /// The timestamp of the block.
     pub timestamp: u64,
+    /// The number of rewards that will be computed once they're requested, if they weren't
+    /// computed yet.
+    #[serde(skip)]
+    pending_rewards: Option<usize>,
 }
 
 impl FeeHistoryEntry {
@@ -358,30 +1055,102 @@ impl FeeHistoryEntry {
     ///
     /// Note: This does not calculate the rewards for the block.
     pub fn new(block: &SealedBlock) -> Self {
+        Self::from_header_with_hash(block.header(), block.hash(), block.blob_gas_used())
+    }
+
+    /// Creates a new entry from a block whose hash is already known, e.g. from the provider's
+    /// header index, without sealing the block.
+    ///
+    /// Note: This does not calculate the rewards for the block.
+    pub fn from_block_with_hash(block: &Block, hash: B256) -> Self {
+        let blob_gas_used = block.body.iter().filter_map(|tx| tx.blob_gas_used()).sum();
+        Self::from_header_with_hash(&block.header, hash, blob_gas_used)
+    }
+
+    fn from_header_with_hash(header: &Header, hash: B256, blob_gas_used: u64) -> Self {
         Self {
-            base_fee_per_gas: block.base_fee_per_gas.unwrap_or_default(),
-            gas_used_ratio: block.gas_used as f64 / block.gas_limit as f64,
-            base_fee_per_blob_gas: block.blob_fee(),
-            blob_gas_used_ratio: block.blob_gas_used() as f64 /
+            base_fee_per_gas: header.base_fee_per_gas.unwrap_or_default(),
+            gas_used_ratio: gas_used_ratio(header.gas_used, header.gas_limit),
+            base_fee_per_blob_gas: header.blob_fee(),
+            blob_gas_used_ratio: blob_gas_used as f64 /
                 reth_primitives::constants::eip4844::MAX_DATA_GAS_PER_BLOCK as f64,
-            excess_blob_gas: block.excess_blob_gas,
-            blob_gas_used: block.blob_gas_used,
-            gas_used: block.gas_used,
-            header_hash: block.hash(),
-            gas_limit: block.gas_limit,
+            excess_blob_gas: header.excess_blob_gas,
+            blob_gas_used: header.blob_gas_used,
+            gas_used: header.gas_used,
+            header_hash: hash,
+            gas_limit: header.gas_limit,
             rewards: Vec::new(),
-            timestamp: block.timestamp,
+            reward_percentiles: Vec::new(),
+            timestamp: header.timestamp,
+            pending_rewards: None,
+        }
+    }
+
+    /// Returns true if the rewards of this entry still need to be computed.
+    pub const fn has_pending_rewards(&self) -> bool {
+        self.pending_rewards.is_some()
+    }
+
+    /// Returns the reward at the given percentile, linearly interpolated between the two closest
+    /// percentiles of the reward grid that was computed with the given resolution.
+    ///
+    /// Percentiles outside of `0..=100` are clamped to the endpoints of the grid.
+    pub fn reward_at_percentile(&self, percentile: f64, resolution: u64) -> u128 {
+        let position = percentile.clamp(0.0, 100.0) * resolution as f64;
+        let lower_index = position.floor() as usize;
+        let Some(&lower) = self.rewards.get(lower_index) else { return 0 };
+        let Some(&upper) = self.rewards.get(lower_index + 1) else { return lower };
+
+        let fraction = position - lower_index as f64;
+        if upper >= lower {
+            lower + ((upper - lower) as f64 * fraction).round() as u128
+        } else {
+            lower - ((lower - upper) as f64 * fraction).round() as u128
+        }
+    }
+
+    /// Returns true if the rewards of this entry cover all of the given percentiles.
+    ///
+    /// This is always the case for rewards computed for the grid of the configured resolution,
+    /// which are interpolated, see [`Self::reward_at_percentile`].
+    pub fn has_rewards_for(&self, percentiles: &[f64]) -> bool {
+        self.reward_percentiles.is_empty() ||
+            percentiles.iter().all(|percentile| self.reward_percentiles.contains(percentile))
+    }
+
+    /// Returns the reward at the given percentile.
+    ///
+    /// Rewards computed for the grid of the configured resolution are interpolated, rewards
+    /// computed for requested percentiles are only returned for these exact percentiles.
+    pub fn reward_for_percentile(&self, percentile: f64, resolution: u64) -> Option<u128> {
+        if self.reward_percentiles.is_empty() {
+            return Some(self.reward_at_percentile(percentile, resolution))
         }
+        let index = self.reward_percentiles.iter().position(|p| *p == percentile)?;
+        self.rewards.get(index).copied()
+    }
+
+    /// Returns the approximate number of bytes this entry occupies in memory.
+    ///
+    /// Pending rewards are accounted for as if they were already computed. Entries don't keep the
+    /// data of their block, so this covers all memory an entry holds.
+    pub fn approximate_size(&self) -> usize {
+        let rewards_len = self.pending_rewards.unwrap_or(self.rewards.len());
+        std::mem::size_of::<Self>() +
+            rewards_len * std::mem::size_of::<u128>() +
+            self.reward_percentiles.len() * std::mem::size_of::<f64>()
     }
 
     /// Returns the base fee for the next block according to the EIP-1559 spec.
+    ///
+    /// See also [`next_block_base_fee`].
     pub fn next_block_base_fee(&self, chain_spec: &ChainSpec) -> u64 {
-        calc_next_block_base_fee(
-            self.gas_used as u128,
-            self.gas_limit as u128,
-            self.base_fee_per_gas as u128,
+        next_block_base_fee(
+            self.gas_used,
+            self.gas_limit,
+            self.base_fee_per_gas,
             chain_spec.base_fee_params_at_timestamp(self.timestamp),
-        ) as u64
+        )
     }
 
     /// Returns the blob fee for the next block according to the EIP-4844 spec.
@@ -400,3 +1169,677 @@ impl FeeHistoryEntry {
         Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Transaction, TxEip1559, TxLegacy};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_testing_utils::generators::{self, sign_tx_with_random_key_pair};
+
+    fn fee_history_cache(provider: &MockEthProvider) -> FeeHistoryCache {
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default())
+    }
+
+    fn sealed_block(number: u64) -> SealedBlock {
+        let header = Header {
+            number,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        };
+        Block { header, ..Default::default() }.seal_slow()
+    }
+
+    /// Signed, so the senders of blocks loaded through the state cache can be recovered.
+    fn transaction(max_priority_fee_per_gas: u128) -> TransactionSigned {
+        sign_tx_with_random_key_pair(
+            &mut generators::rng(),
+            Transaction::Eip1559(TxEip1559 {
+                max_fee_per_gas: 100,
+                max_priority_fee_per_gas,
+                gas_limit: 21_000,
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn legacy_transaction(gas_price: u128) -> TransactionSigned {
+        sign_tx_with_random_key_pair(
+            &mut generators::rng(),
+            Transaction::Legacy(TxLegacy { gas_price, gas_limit: 21_000, ..Default::default() }),
+        )
+    }
+
+    fn receipt(cumulative_gas_used: u64) -> Receipt {
+        Receipt { cumulative_gas_used, ..Default::default() }
+    }
+
+    /// Adds the block and its receipts to the provider the rewards are loaded from.
+    fn add_block(provider: &MockEthProvider, block: &SealedBlock, receipts: Vec<Receipt>) {
+        provider.add_block(block.hash(), block.clone().unseal());
+        provider.add_receipts(block.hash(), receipts);
+    }
+
+    #[test]
+    fn reward_percentiles_empty_block() {
+        let rewards =
+            calculate_reward_percentiles_for_block(&[10., 50., 90.], 0, 7, &[], &[]).unwrap();
+        assert_eq!(rewards, vec![0; 3]);
+    }
+
+    #[test]
+    fn reward_percentiles_single_transaction() {
+        let rewards = calculate_reward_percentiles_for_block(
+            &[0., 50., 100.],
+            21_000,
+            7,
+            &[transaction(3)],
+            &[receipt(21_000)],
+        )
+        .unwrap();
+        assert_eq!(rewards, vec![3; 3]);
+    }
+
+    #[test]
+    fn reward_thresholds_are_exact() {
+        assert_eq!(reward_threshold(200_000, 0.), 0);
+        assert_eq!(reward_threshold(200_000, 25.), 50_000);
+        assert_eq!(reward_threshold(200_000, 100.), 200_000);
+        assert_eq!(reward_threshold(1_000_001, 12.5), 125_000);
+        // `u64::MAX as f64` rounds up to 2^64, so the float computation is off by one
+        assert_eq!(reward_threshold(u64::MAX, 50.), u64::MAX / 2);
+        assert_eq!(reward_threshold(u64::MAX, 100.), u64::MAX);
+    }
+
+    #[test]
+    fn reward_percentiles_match_geth() {
+        // tips of 5, 1, 3 and 2 using 50k, 21k, 100k and 29k gas, so sorted by tip the cumulative
+        // gas used is 21k, 50k, 150k and 200k
+        let transactions = [transaction(5), transaction(1), transaction(3), transaction(2)];
+        let receipts = [receipt(50_000), receipt(71_000), receipt(171_000), receipt(200_000)];
+
+        // geth selects the first transaction whose cumulative gas used reaches the threshold,
+        // thresholds that coincide with a cumulative gas used select that transaction
+        let rewards = calculate_reward_percentiles_for_block(
+            &[0., 10., 25., 50., 75., 99.5, 100.],
+            200_000,
+            7,
+            &transactions,
+            &receipts,
+        )
+        .unwrap();
+        assert_eq!(rewards, vec![1, 1, 2, 3, 3, 5, 5]);
+    }
+
+    #[test]
+    fn reward_percentiles_mixed_legacy_and_eip1559() {
+        // the legacy transaction is priced just above the base fee of 7, so its tip is 1
+        let transactions = [transaction(3), legacy_transaction(8), transaction(5)];
+        let receipts = [receipt(21_000), receipt(42_000), receipt(63_000)];
+
+        let rewards = calculate_reward_percentiles_for_block(
+            &[0., 40., 60., 100.],
+            63_000,
+            7,
+            &transactions,
+            &receipts,
+        )
+        .unwrap();
+        assert_eq!(rewards, vec![1, 3, 3, 5]);
+    }
+
+    #[test]
+    fn interpolated_rewards_match_direct_percentiles() {
+        // 80 transactions with equal gas usage and tips of 1 to 80
+        let transactions = (1..=80).map(transaction).collect::<Vec<_>>();
+        let receipts = (1..=80).map(|i| receipt(i * 21_000)).collect::<Vec<_>>();
+        let gas_used = 80 * 21_000;
+
+        let resolution = 4;
+        let mut entry = FeeHistoryEntry::new(&sealed_block(1));
+        entry.rewards = calculate_reward_percentiles_for_block(
+            &(0..=100 * resolution).map(|p| p as f64 / resolution as f64).collect::<Vec<_>>(),
+            gas_used,
+            7,
+            &transactions,
+            &receipts,
+        )
+        .unwrap();
+
+        for percentile in [0., 12.3, 33.7, 50., 66.6, 99.9, 100.] {
+            let direct = calculate_reward_percentiles_for_block(
+                &[percentile],
+                gas_used,
+                7,
+                &transactions,
+                &receipts,
+            )
+            .unwrap()[0];
+            let interpolated = entry.reward_at_percentile(percentile, resolution);
+            assert!(
+                interpolated.abs_diff(direct) <= 1,
+                "percentile {percentile}: interpolated {interpolated}, direct {direct}"
+            );
+        }
+    }
+
+    #[test]
+    fn interpolates_between_grid_points() {
+        let resolution = 4;
+        let mut entry = FeeHistoryEntry::new(&sealed_block(1));
+        entry.rewards = (0..=100 * resolution as u128).map(|i| i * 10).collect();
+
+        // 33.7 lies between the grid points 33.5 and 33.75
+        assert_eq!(entry.reward_at_percentile(33.7, resolution), 1348);
+        assert_eq!(entry.reward_at_percentile(33.75, resolution), 1350);
+        // endpoints are not extrapolated
+        assert_eq!(entry.reward_at_percentile(0., resolution), 0);
+        assert_eq!(entry.reward_at_percentile(-1., resolution), 0);
+        assert_eq!(entry.reward_at_percentile(100., resolution), 4000);
+        assert_eq!(entry.reward_at_percentile(101., resolution), 4000);
+    }
+
+    #[test]
+    fn reward_percentiles_mismatching_receipts() {
+        let transactions = [transaction(3), transaction(5)];
+
+        let err = calculate_reward_percentiles_for_block(
+            &[50.],
+            42_000,
+            7,
+            &transactions,
+            &[receipt(21_000)],
+        )
+        .unwrap_err();
+        assert!(matches!(err, EthApiError::InternalEthError));
+
+        let err = calculate_reward_percentiles_for_block(
+            &[50.],
+            42_000,
+            7,
+            &transactions[..1],
+            &[receipt(21_000), receipt(42_000)],
+        )
+        .unwrap_err();
+        assert!(matches!(err, EthApiError::InternalEthError));
+    }
+
+    #[tokio::test]
+    async fn rewards_are_computed_on_first_request() {
+        let provider = MockEthProvider::default();
+        let cache = fee_history_cache(&provider);
+        let block = SealedBlock { body: vec![transaction(1), transaction(3)], ..sealed_block(0) };
+        add_block(&provider, &block, vec![receipt(5_000_000), receipt(15_000_000)]);
+        cache.insert_blocks([block]).await;
+
+        // requests without percentiles don't compute the rewards
+        let entry = cache.get_history(0, 0, false).await.unwrap().unwrap().remove(0);
+        assert!(entry.has_pending_rewards());
+        assert!(entry.rewards.is_empty());
+
+        let entry = cache.get_history(0, 0, true).await.unwrap().unwrap().remove(0);
+        assert!(!entry.has_pending_rewards());
+        assert_eq!(entry.rewards.len(), cache.predefined_percentiles().len());
+        assert_eq!(entry.reward_at_percentile(25.0, cache.resolution()), 1);
+        assert_eq!(entry.reward_at_percentile(75.0, cache.resolution()), 3);
+
+        // the computed rewards are memoized
+        assert_eq!(cache.get_history(0, 0, false).await.unwrap().unwrap().remove(0), entry);
+    }
+
+    #[tokio::test]
+    async fn rewards_of_unavailable_or_invalid_blocks() {
+        let provider = MockEthProvider::default();
+        let cache = fee_history_cache(&provider);
+        // block 0 has mismatching receipts and block 1 isn't available
+        let block = SealedBlock { body: vec![transaction(1), transaction(3)], ..sealed_block(0) };
+        add_block(&provider, &block, vec![receipt(5_000_000)]);
+        cache.insert_blocks([block, sealed_block(1)]).await;
+
+        let err = cache.get_history(0, 0, true).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InternalEthError));
+        assert!(cache.get_history(1, 1, true).await.unwrap().is_none());
+        // the entries are still served without rewards
+        assert_eq!(cache.get_history(0, 1, false).await.unwrap().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rewards_are_only_computed_for_requested_range() {
+        let provider = MockEthProvider::default();
+        let cache = fee_history_cache(&provider);
+        let blocks = (0..20).map(|number| {
+            let block =
+                SealedBlock { body: vec![transaction(1), transaction(3)], ..sealed_block(number) };
+            add_block(&provider, &block, vec![receipt(5_000_000), receipt(15_000_000)]);
+            block
+        });
+        cache.insert_blocks(blocks.collect::<Vec<_>>()).await;
+
+        let entries = cache.get_history(10, 14, true).await.unwrap().unwrap();
+        assert_eq!(entries.len(), 5);
+
+        // only the entries of the requested window have their rewards computed
+        let entries = cache.inner.entries.read().await;
+        let computed = entries
+            .iter()
+            .filter(|(_, entry)| !entry.has_pending_rewards())
+            .map(|(number, _)| *number)
+            .collect::<Vec<_>>();
+        assert_eq!(computed, (10..=14).collect::<Vec<_>>());
+        assert!(entries[&15].rewards.is_empty());
+    }
+
+    #[tokio::test]
+    async fn adaptive_grid_narrows_to_requested_percentiles() {
+        let provider = MockEthProvider::default();
+        let eth_cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        let config = FeeHistoryCacheConfig { adaptive_window: Some(4), ..Default::default() };
+        let cache = FeeHistoryCache::new(eth_cache, config);
+        assert_eq!(cache.predefined_percentiles().len(), 401);
+
+        for _ in 0..3 {
+            cache.record_requested_percentiles(&[10., 50., 90.]);
+        }
+        assert_eq!(cache.predefined_percentiles(), vec![10., 50., 90.]);
+
+        let block = SealedBlock { body: vec![transaction(1), transaction(3)], ..sealed_block(0) };
+        add_block(&provider, &block, vec![receipt(5_000_000), receipt(15_000_000)]);
+        cache.insert_blocks([block]).await;
+
+        let entry = cache.get_history(0, 0, true).await.unwrap().unwrap().remove(0);
+        assert_eq!(entry.rewards, vec![1, 3, 3]);
+        assert_eq!(entry.reward_percentiles, vec![10., 50., 90.]);
+        assert!(entry.has_rewards_for(&[10., 90.]));
+        assert_eq!(entry.reward_for_percentile(90., cache.resolution()), Some(3));
+
+        // novel percentiles aren't covered and need to be computed from the block
+        assert!(!entry.has_rewards_for(&[25.]));
+        assert_eq!(entry.reward_for_percentile(25., cache.resolution()), None);
+
+        // sets beyond the window are forgotten
+        for percentile in [1., 2., 3., 4.] {
+            cache.record_requested_percentiles(&[percentile]);
+        }
+        assert_eq!(cache.predefined_percentiles(), vec![1., 2., 3., 4.]);
+    }
+
+    #[tokio::test]
+    async fn get_history_is_traced() {
+        use std::sync::Mutex;
+        use tracing::{
+            field::Visit,
+            span::{Attributes, Id, Record},
+            Subscriber,
+        };
+        use tracing_subscriber::{
+            layer::{Context, SubscriberExt},
+            Layer,
+        };
+
+        /// Records the names of new spans and the fields recorded on them.
+        #[derive(Clone, Default)]
+        struct SpanRecorder(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for SpanRecorder {
+            fn record_debug(&mut self, field: &field::Field, value: &dyn Debug) {
+                self.0.lock().unwrap().push(format!("{}={value:?}", field.name()));
+            }
+        }
+
+        impl<S: Subscriber> Layer<S> for SpanRecorder {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+                self.0.lock().unwrap().push(attrs.metadata().name().to_string());
+            }
+
+            fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+                values.record(&mut self.clone());
+            }
+        }
+
+        let recorder = SpanRecorder::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+        let provider = MockEthProvider::default();
+        let cache = fee_history_cache(&provider);
+        let block = SealedBlock { body: vec![transaction(1)], ..sealed_block(0) };
+        add_block(&provider, &block, vec![receipt(21_000)]);
+        cache.insert_blocks([block]).await;
+        assert!(cache.get_history(0, 0, true).await.unwrap().is_some());
+        assert!(cache.get_history(1, 1, false).await.unwrap().is_none());
+
+        let recorded = recorder.0.lock().unwrap().clone();
+        assert_eq!(recorded.iter().filter(|name| *name == "get_history").count(), 2);
+        assert!(recorded.contains(&"insert_blocks".to_string()));
+        assert!(recorded.contains(&"cache_hit=true".to_string()));
+        assert!(recorded.contains(&"cache_hit=false".to_string()));
+        assert!(recorded.iter().any(|field| field.starts_with("rewards_elapsed=")));
+    }
+
+    #[tokio::test]
+    async fn snapshot_roundtrip_reuses_canonical_entries() {
+        let provider = MockEthProvider::default();
+        let blocks = (0..5).map(sealed_block).collect::<Vec<_>>();
+        for block in &blocks {
+            provider.add_block(block.hash(), block.clone().unseal());
+        }
+
+        let cache = fee_history_cache(&provider);
+        cache.insert_blocks(blocks[..4].to_vec()).await;
+        // only entries with computed rewards are written
+        cache.get_history(0, 3, true).await.unwrap().unwrap();
+        cache.insert_blocks([blocks[4].clone()]).await;
+        assert_eq!((cache.lower_bound(), cache.upper_bound()), (0, 4));
+        // block 3 is not part of the canonical chain anymore
+        provider.blocks.lock().remove(&blocks[3].hash());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fee_history.json");
+        cache.save_snapshot(&path).await.unwrap();
+        // the temporary file was moved into place
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let reloaded = fee_history_cache(&provider);
+        let loaded = reloaded.load_snapshot(&path, &provider).await.unwrap();
+        assert_eq!(loaded, 3);
+        assert_eq!((reloaded.lower_bound(), reloaded.upper_bound()), (0, 2));
+        assert_eq!(
+            reloaded.get_history(0, 2, true).await.unwrap(),
+            cache.get_history(0, 2, true).await.unwrap()
+        );
+        assert!(reloaded.missing_consecutive_blocks().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn non_canonical_entries_are_not_served() {
+        let provider = MockEthProvider::default();
+        let blocks = (0..3).map(sealed_block).collect::<Vec<_>>();
+        for block in &blocks {
+            provider.add_block(block.hash(), block.clone().unseal());
+        }
+
+        let cache = fee_history_cache(&provider);
+        cache.insert_blocks(blocks.clone()).await;
+        let entries = cache.get_canonical_history(0, 2, false, &provider).await.unwrap();
+        assert_eq!(entries.unwrap().len(), 3);
+
+        // block 1 is reorged out, the cache still holds its entry
+        let reorged = &blocks[1];
+        provider.blocks.lock().remove(&reorged.hash());
+        let replacement = SealedBlock {
+            header: Header { gas_used: 0, ..reorged.header.header().clone() }.seal_slow(),
+            ..reorged.clone()
+        };
+        provider.add_block(replacement.hash(), replacement.unseal());
+
+        assert!(cache.get_canonical_history(0, 2, false, &provider).await.unwrap().is_none());
+        assert!(cache.get_canonical_history(1, 1, false, &provider).await.unwrap().is_none());
+        assert!(cache.get_canonical_history(2, 2, false, &provider).await.unwrap().is_some());
+        // the unverified lookup still serves the stale entry
+        assert!(cache.get_history(0, 2, false).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn backfill_in_chunks() {
+        let provider = MockEthProvider::default();
+        let blocks = (0..250).map(sealed_block).collect::<Vec<_>>();
+        for block in &blocks {
+            provider.add_block(block.hash(), block.clone().unseal());
+        }
+        let config = FeeHistoryCacheConfig { max_blocks: 200, ..Default::default() };
+        let eth_cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        let cache = FeeHistoryCache::new(eth_cache.clone(), config.clone());
+
+        // record the bounds other tasks observe while the backfill is running
+        let (done_tx, mut done_rx) = tokio::sync::oneshot::channel::<()>();
+        let observer = tokio::spawn({
+            let cache = cache.clone();
+            async move {
+                let mut observed = Vec::new();
+                while done_rx.try_recv().is_err() {
+                    observed.push(cache.upper_bound());
+                    tokio::task::yield_now().await;
+                }
+                observed
+            }
+        });
+        cache.backfill(&provider, 249).await.unwrap();
+        done_tx.send(()).unwrap();
+        let observed = observer.await.unwrap();
+
+        assert_eq!((cache.lower_bound(), cache.upper_bound()), (50, 249));
+        assert!(cache.missing_consecutive_blocks().await.is_empty());
+        // the first chunk of 64 blocks was visible before the backfill completed
+        assert!(observed.contains(&113), "{observed:?}");
+
+        // the contents match inserting all blocks at once
+        let expected = FeeHistoryCache::new(eth_cache, config);
+        expected.insert_blocks(blocks.into_iter()).await;
+        assert_eq!(
+            cache.get_history(50, 249, true).await.unwrap(),
+            expected.get_history(50, 249, true).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_uses_known_block_hashes() {
+        let provider = MockEthProvider::default();
+        let block = sealed_block(0);
+        // a hash that differs from the sealed one shows the block wasn't sealed again
+        let known_hash = B256::with_last_byte(1);
+        assert_ne!(known_hash, block.hash());
+        provider.add_block(known_hash, block.clone().unseal());
+        let cache = fee_history_cache(&provider);
+
+        cache.backfill(&provider, 0).await.unwrap();
+
+        let entry = cache.get_history(0, 0, false).await.unwrap().unwrap().remove(0);
+        assert_eq!(entry.header_hash, known_hash);
+        assert_eq!(entry.gas_used_ratio, FeeHistoryEntry::new(&block).gas_used_ratio);
+    }
+
+    #[tokio::test]
+    async fn backfill_skips_blocks_with_unavailable_receipts() {
+        let provider = MockEthProvider::default();
+        for number in 0..3 {
+            let mut block = sealed_block(number).unseal();
+            // the receipts of the middle block aren't available
+            if number == 1 {
+                block.body.push(transaction(1));
+            }
+            provider.add_block(block.clone().seal_slow().hash(), block);
+        }
+        let cache = fee_history_cache(&provider);
+
+        cache.backfill(&provider, 2).await.unwrap();
+
+        assert_eq!((cache.lower_bound(), cache.upper_bound()), (0, 2));
+        assert!(cache.get_history(0, 0, false).await.unwrap().is_some());
+        assert!(cache.get_history(2, 2, false).await.unwrap().is_some());
+        assert!(cache.get_history(1, 1, false).await.unwrap().is_none());
+        // the gap is fetched again by the new blocks task
+        assert_eq!(cache.missing_consecutive_blocks().await, [1]);
+    }
+
+    #[tokio::test]
+    async fn info_reports_bounds() {
+        let provider = MockEthProvider::default();
+        let cache = fee_history_cache(&provider);
+        let info = cache.info();
+        assert_eq!((info.lower_bound, info.upper_bound, info.len), (0, 0, 0));
+
+        // blocks 10..=14 with block 12 missing
+        let blocks = [10, 11, 13, 14].map(sealed_block);
+        cache.insert_blocks(blocks.into_iter()).await;
+
+        let config = FeeHistoryCacheConfig::default();
+        assert_eq!(
+            cache.info(),
+            FeeHistoryCacheInfo {
+                lower_bound: 10,
+                upper_bound: 14,
+                max_blocks: config.max_blocks,
+                resolution: config.resolution,
+                len: 4,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn invalidate_from_drops_entries_above() {
+        let cache = fee_history_cache(&MockEthProvider::default());
+        let blocks = (10..=14).map(sealed_block);
+        cache.insert_blocks(blocks).await;
+
+        cache.invalidate_from(12).await;
+        let info = cache.info();
+        assert_eq!((info.lower_bound, info.upper_bound, info.len), (10, 11, 2));
+        assert!(cache.get_history(10, 11, false).await.unwrap().is_some());
+        assert!(cache.get_history(12, 12, false).await.unwrap().is_none());
+
+        // invalidating above the cached blocks is a no-op
+        cache.invalidate_from(20).await;
+        let info = cache.info();
+        assert_eq!((info.lower_bound, info.upper_bound, info.len), (10, 11, 2));
+
+        cache.invalidate_from(0).await;
+        let info = cache.info();
+        assert_eq!((info.lower_bound, info.upper_bound, info.len), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn clear_resets_bounds() {
+        let cache = fee_history_cache(&MockEthProvider::default());
+        let blocks = (10..=14).map(sealed_block);
+        cache.insert_blocks(blocks).await;
+
+        cache.clear().await;
+        let info = cache.info();
+        assert_eq!((info.lower_bound, info.upper_bound, info.len), (0, 0, 0));
+        assert!(cache.get_history(10, 14, false).await.unwrap().is_none());
+
+        // new blocks are cached again
+        cache.insert_blocks([sealed_block(15)]).await;
+        let info = cache.info();
+        assert_eq!((info.lower_bound, info.upper_bound, info.len), (15, 15, 1));
+    }
+
+    #[tokio::test]
+    async fn zero_config_values_are_raised() {
+        let cache = EthStateCache::spawn(
+            MockEthProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+        let config = FeeHistoryCacheConfig { max_blocks: 0, resolution: 0, ..Default::default() };
+        let fee_history_cache = FeeHistoryCache::new(cache, config);
+
+        assert_eq!(fee_history_cache.config().max_blocks, 1);
+        assert_eq!(fee_history_cache.resolution(), 1);
+        assert_eq!(fee_history_cache.predefined_percentiles().len(), 101);
+    }
+
+    #[tokio::test]
+    async fn evicts_entries_above_byte_budget() {
+        // empty blocks get a zero row for all 401 percentiles of the default resolution
+        let mut entry = FeeHistoryEntry::new(&sealed_block(0));
+        entry.rewards = vec![0; 401];
+        let entry_size = entry.approximate_size() as u64;
+
+        let eth_cache = EthStateCache::spawn(
+            MockEthProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+        let config = FeeHistoryCacheConfig {
+            eviction: FeeHistoryCacheEviction::MaxBytes(4 * entry_size),
+            ..Default::default()
+        };
+        let cache = FeeHistoryCache::new(eth_cache, config);
+        cache.insert_blocks((0..10).map(sealed_block)).await;
+
+        // only the newest four entries fit into the budget even though `max_blocks` is larger
+        assert_eq!((cache.lower_bound(), cache.upper_bound()), (6, 9));
+        assert!(cache.get_history(5, 9, false).await.unwrap().is_none());
+        assert_eq!(cache.get_history(6, 9, false).await.unwrap().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn pending_rewards_are_sized_as_computed() {
+        let provider = MockEthProvider::default();
+        let cache = fee_history_cache(&provider);
+        let block = SealedBlock { body: (1..=100).map(transaction).collect(), ..sealed_block(0) };
+        let receipts = (1..=100).map(|i| receipt(i * 21_000)).collect();
+        add_block(&provider, &block, receipts);
+        cache.insert_blocks([block]).await;
+
+        // the size doesn't depend on the transactions of the block, only on the rewards
+        let pending = cache.get_history(0, 0, false).await.unwrap().unwrap().remove(0);
+        let mut empty = FeeHistoryEntry::new(&sealed_block(0));
+        empty.rewards = vec![0; cache.predefined_percentiles().len()];
+        assert_eq!(pending.approximate_size(), empty.approximate_size());
+
+        let computed = cache.get_history(0, 0, true).await.unwrap().unwrap().remove(0);
+        assert_eq!(computed.approximate_size(), pending.approximate_size());
+    }
+
+    #[test]
+    fn zero_gas_limit_has_finite_gas_used_ratio() {
+        let block = Block { header: Header::default(), ..Default::default() }.seal_slow();
+        let entry = FeeHistoryEntry::new(&block);
+        assert_eq!(entry.gas_used_ratio, 0.0);
+        assert!(entry.blob_gas_used_ratio.is_finite());
+
+        assert_eq!(FeeHistoryEntry::new(&sealed_block(1)).gas_used_ratio, 0.5);
+    }
+
+    #[test]
+    fn next_block_base_fee_saturates() {
+        let params = BaseFeeParams::ethereum();
+        let near_max = u64::MAX - 1;
+
+        // a full block raises the base fee beyond `u64::MAX`, which saturates instead of wrapping
+        assert_eq!(next_block_base_fee(30_000_000, 30_000_000, near_max, params), u64::MAX);
+        // an empty block lowers it by an eighth
+        assert_eq!(next_block_base_fee(0, 30_000_000, near_max, params), near_max - near_max / 8);
+        assert_eq!(next_block_base_fee(15_000_000, 30_000_000, near_max, params), near_max);
+
+        // misconfigured params neither overflow nor divide by zero
+        let huge = BaseFeeParams::new(u128::MAX, 2);
+        assert_eq!(next_block_base_fee(30_000_000, 30_000_000, near_max, huge), u64::MAX);
+        assert_eq!(next_block_base_fee(30_000_000, 30_000_000, 7, BaseFeeParams::new(8, 0)), 7);
+        assert_eq!(next_block_base_fee(1, 0, 7, params), 7);
+
+        // the result matches the unchecked calculation for regular base fees
+        for gas_used in [0, 10_000_000, 15_000_000, 20_000_000, 30_000_000] {
+            assert_eq!(
+                next_block_base_fee(gas_used, 30_000_000, 1_000_000_000, params) as u128,
+                reth_primitives::basefee::calc_next_block_base_fee(
+                    gas_used as u128,
+                    30_000_000,
+                    1_000_000_000,
+                    params
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn fee_history_range_is_clamped() {
+        assert_eq!(fee_history_range(5, 100, 1024), Some(96..=100));
+        // clamped to the maximum block count
+        assert_eq!(fee_history_range(2000, 5000, 1024), Some(3977..=5000));
+        // clamped at genesis
+        assert_eq!(fee_history_range(10, 3, 1024), Some(0..=3));
+        assert_eq!(fee_history_range(10, 0, 1024), Some(0..=0));
+        assert_eq!(fee_history_range(0, 100, 1024), None);
+        assert_eq!(fee_history_range(10, 100, 0), None);
+    }
+}
@@ -4,17 +4,23 @@
 use std::fmt::{self, Debug, Formatter};
 
 use derive_more::{Deref, DerefMut, From, Into};
-use reth_primitives::{constants::GWEI_TO_WEI, BlockNumberOrTag, B256, U256};
-use reth_provider::BlockReaderIdExt;
+use futures::{future::BoxFuture, Stream, StreamExt};
+use reth_chainspec::ChainSpec;
+use reth_primitives::{
+    constants::GWEI_TO_WEI, BlockNumberOrTag, Header, SealedBlockWithSenders, TransactionSigned,
+    B256, U256,
+};
+use reth_provider::{BlockReaderIdExt, CanonStateNotification, ChainSpecProvider};
 use reth_rpc_server_types::constants;
 use schnellru::{ByLength, LruMap};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
-use tracing::warn;
+use tracing::{debug, warn};
 
 use reth_rpc_server_types::constants::gas_oracle::{
     DEFAULT_GAS_PRICE_BLOCKS, DEFAULT_GAS_PRICE_PERCENTILE, DEFAULT_IGNORE_GAS_PRICE,
-    DEFAULT_MAX_GAS_PRICE, MAX_HEADER_HISTORY, SAMPLE_NUMBER,
+    DEFAULT_MAX_GAS_PRICE, DEFAULT_MIN_SUGGESTED_PRIORITY_FEE,
+    DEFAULT_OP_MIN_SUGGESTED_PRIORITY_FEE, MAX_HEADER_HISTORY, SAMPLE_NUMBER,
 };
 
 use super::{EthApiError, EthResult, EthStateCache, RpcInvalidTransactionError};
@@ -45,8 +51,30 @@ pub struct GasPriceOracleConfig {
     /// The maximum gas price to use for the estimate
     pub max_price: Option<U256>,
 
+    /// The minimum gas price to use for the estimate.
+    ///
+    /// On quiet chains most blocks are empty or only contain transactions with very low tips, so
+    /// the sampled estimate would be close to zero. If unset, the default of the chain is used,
+    /// see [`default_min_suggested_priority_fee`]. Zero disables the minimum.
+    #[serde(default)]
+    pub min_suggested_priority_fee: Option<U256>,
+
     /// The minimum gas price, under which the sample will be ignored
     pub ignore_price: Option<U256>,
+
+    /// The maximum number of blocks that can be requested in a single `eth_feeHistory` call.
+    ///
+    /// If set, requests for more blocks are rejected instead of truncated to
+    /// [`max_header_history`](Self::max_header_history).
+    #[serde(default)]
+    pub max_fee_history_blocks: Option<u64>,
+
+    /// The number of blocks whose lowest effective tips are cached.
+    ///
+    /// Defaults to the larger of five times [`blocks`](Self::blocks) and
+    /// [`max_block_history`](Self::max_block_history).
+    #[serde(default)]
+    pub max_cached_blocks: Option<u32>,
 }
 
 impl Default for GasPriceOracleConfig {
@@ -58,11 +86,28 @@ impl Default for GasPriceOracleConfig {
             max_block_history: MAX_HEADER_HISTORY,
             default: None,
             max_price: Some(DEFAULT_MAX_GAS_PRICE),
+            min_suggested_priority_fee: None,
             ignore_price: Some(DEFAULT_IGNORE_GAS_PRICE),
+            max_fee_history_blocks: None,
+            max_cached_blocks: None,
         }
     }
 }
 
+/// Returns the default minimum priority fee recommended by the [`GasPriceOracle`] on the given
+/// chain.
+///
+/// The tips on OP chains are orders of magnitude lower than on Ethereum, so they use a much lower
+/// minimum.
+#[allow(clippy::missing_const_for_fn)]
+pub fn default_min_suggested_priority_fee(chain_spec: &ChainSpec) -> U256 {
+    if chain_spec.is_optimism() {
+        DEFAULT_OP_MIN_SUGGESTED_PRIORITY_FEE
+    } else {
+        DEFAULT_MIN_SUGGESTED_PRIORITY_FEE
+    }
+}
+
 /// Calculates a gas price depending on recent blocks.
 #[derive(Debug)]
 pub struct GasPriceOracle<Provider> {
@@ -81,9 +126,11 @@ pub struct GasPriceOracle<Provider> {
 
 impl<Provider> GasPriceOracle<Provider>
 where
-    Provider: BlockReaderIdExt,
+    Provider: BlockReaderIdExt + ChainSpecProvider,
 {
     /// Creates and returns the [`GasPriceOracle`].
+    ///
+    /// If the config has no minimum priority fee, the default of the provider's chain is used.
     pub fn new(
         provider: Provider,
         mut oracle_config: GasPriceOracleConfig,
@@ -94,10 +141,15 @@ where
             warn!(prev_percentile = ?oracle_config.percentile, "Invalid configured gas price percentile, assuming 100.");
             oracle_config.percentile = 100;
         }
+        oracle_config
+            .min_suggested_priority_fee
+            .get_or_insert_with(|| default_min_suggested_priority_fee(&provider.chain_spec()));
         let ignore_price = oracle_config.ignore_price.map(|price| price.saturating_to());
 
         // this is the number of blocks that we will cache the values for
-        let cached_values = (oracle_config.blocks * 5).max(oracle_config.max_block_history as u32);
+        let cached_values = oracle_config.max_cached_blocks.unwrap_or_else(|| {
+            (oracle_config.blocks * 5).max(oracle_config.max_block_history as u32)
+        });
         let inner = Mutex::new(GasPriceOracleInner {
             last_price: Default::default(),
             lowest_effective_tip_cache: EffectiveTipLruCache(LruMap::new(ByLength::new(
@@ -107,7 +159,12 @@ where
 
         Self { provider, oracle_config, cache, ignore_price, inner }
     }
+}
 
+impl<Provider> GasPriceOracle<Provider>
+where
+    Provider: BlockReaderIdExt,
+{
     /// Returns the configuration of the gas price oracle.
     pub const fn config(&self) -> &GasPriceOracleConfig {
         &self.oracle_config
@@ -185,6 +242,13 @@ where
             inner.last_price.price
         };
 
+        // raise to the min price, the max price takes precedence if it's lower
+        if let Some(min_price) = self.oracle_config.min_suggested_priority_fee {
+            if price < min_price {
+                price = min_price;
+            }
+        }
+
         // constrain to the max price
         if let Some(max_price) = self.oracle_config.max_price {
             if price > max_price {
@@ -197,6 +261,35 @@ where
         Ok(price)
     }
 
+    /// Caches the lowest effective tip values of the given new canonical blocks and recomputes the
+    /// suggested tip for the new head.
+    ///
+    /// This keeps the oracle warm, so that queries don't need to fetch the recent blocks first.
+    pub async fn on_new_blocks<'a>(
+        &self,
+        blocks: impl IntoIterator<Item = &'a SealedBlockWithSenders>,
+    ) {
+        {
+            let mut inner = self.inner.lock().await;
+            for block in blocks {
+                match self.lowest_effective_tips(&block.header, &block.body, SAMPLE_NUMBER) {
+                    Ok(values) => {
+                        inner
+                            .lowest_effective_tip_cache
+                            .insert(block.hash(), (block.parent_hash, values));
+                    }
+                    Err(err) => {
+                        debug!(target: "rpc::gas_oracle", %err, block_hash=?block.hash(), "Failed to compute lowest effective tips");
+                    }
+                }
+            }
+        }
+
+        if let Err(err) = self.suggest_tip_cap().await {
+            debug!(target: "rpc::gas_oracle", %err, "Failed to refresh suggested tip");
+        }
+    }
+
     /// Get the `limit` lowest effective tip values for the given block. If the oracle has a
     /// configured `ignore_price` threshold, then tip values under that threshold will be ignored
     /// before returning a result.
@@ -210,20 +303,34 @@ where
         limit: usize,
     ) -> EthResult<Option<(B256, Vec<U256>)>> {
         // check the cache (this will hit the disk if the block is not cached)
-        let mut block = match self.cache.get_block(block_hash).await? {
+        let block = match self.cache.get_block(block_hash).await? {
             Some(block) => block,
             None => return Ok(None),
         };
 
-        let base_fee_per_gas = block.base_fee_per_gas;
-        let parent_hash = block.parent_hash;
+        let prices = self.lowest_effective_tips(&block.header, &block.body, limit)?;
+
+        Ok(Some((block.parent_hash, prices)))
+    }
+
+    /// Returns the `limit` lowest effective tip values of the given block's transactions, ignoring
+    /// tips under the configured `ignore_price` threshold and transactions sent by the
+    /// beneficiary.
+    fn lowest_effective_tips(
+        &self,
+        header: &Header,
+        transactions: &[TransactionSigned],
+        limit: usize,
+    ) -> EthResult<Vec<U256>> {
+        let base_fee_per_gas = header.base_fee_per_gas;
 
         // sort the functions by ascending effective tip first
-        block.body.sort_by_cached_key(|tx| tx.effective_tip_per_gas(base_fee_per_gas));
+        let mut transactions = transactions.iter().collect::<Vec<_>>();
+        transactions.sort_by_cached_key(|tx| tx.effective_tip_per_gas(base_fee_per_gas));
 
         let mut prices = Vec::with_capacity(limit);
 
-        for tx in &block.body {
+        for tx in transactions {
             let mut effective_gas_tip = None;
             // ignore transactions with a tip under the configured threshold
             if let Some(ignore_under) = self.ignore_price {
@@ -236,7 +343,7 @@ where
 
             // check if the sender was the coinbase, if so, ignore
             if let Some(sender) = tx.recover_signer() {
-                if sender == block.beneficiary {
+                if sender == header.beneficiary {
                     continue
                 }
             }
@@ -255,7 +362,39 @@ where
             }
         }
 
-        Ok(Some((parent_hash, prices)))
+        Ok(prices)
+    }
+}
+
+/// Awaits for new chain events and feeds the committed blocks into the [`GasPriceOracle`], so the
+/// suggested tip is already computed when it is requested.
+pub async fn gas_oracle_new_blocks_task<St, Provider>(
+    gas_oracle: &GasPriceOracle<Provider>,
+    mut events: St,
+) where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+    Provider: BlockReaderIdExt,
+{
+    while let Some(event) = events.next().await {
+        gas_oracle.on_new_blocks(event.committed().blocks_iter()).await;
+    }
+}
+
+/// Suggests the priority fee for `eth_maxPriorityFeePerGas` and `eth_gasPrice`.
+///
+/// This is implemented by the [`GasPriceOracle`], other implementations can serve the suggestion
+/// from an external feed instead.
+pub trait GasOracle: Send + Sync {
+    /// Suggests a max priority fee (the tip) for new transactions.
+    fn suggest_tip_cap(&self) -> BoxFuture<'_, EthResult<U256>>;
+}
+
+impl<Provider> GasOracle for GasPriceOracle<Provider>
+where
+    Provider: BlockReaderIdExt,
+{
+    fn suggest_tip_cap(&self) -> BoxFuture<'_, EthResult<U256>> {
+        Box::pin(Self::suggest_tip_cap(self))
     }
 }
 
@@ -304,10 +443,225 @@ impl Default for GasCap {
     }
 }
 
+/// Gas limit caps for the different groups of RPC methods that execute calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasCaps {
+    /// Cap for `eth_call`, `eth_callMany` and `eth_createAccessList`.
+    pub call: u64,
+    /// Cap for `eth_estimateGas`.
+    pub estimate: u64,
+    /// Cap for `debug_trace*` and `trace_*` calls.
+    pub trace: u64,
+}
+
+impl Default for GasCaps {
+    fn default() -> Self {
+        RPC_DEFAULT_GAS_CAP.into()
+    }
+}
+
+impl From<u64> for GasCaps {
+    /// Applies the same cap to all method groups.
+    fn from(cap: u64) -> Self {
+        Self { call: cap, estimate: cap, trace: cap }
+    }
+}
+
+impl From<GasCap> for GasCaps {
+    fn from(cap: GasCap) -> Self {
+        cap.0.into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Block, Signature, Transaction, TxEip1559};
+    use reth_provider::{test_utils::MockEthProvider, Chain, ExecutionOutcome};
+
     use super::*;
 
+    fn block_with_tips(number: u64, parent_hash: B256, tips: &[u128]) -> SealedBlockWithSenders {
+        let body = tips
+            .iter()
+            .map(|&max_priority_fee_per_gas| {
+                TransactionSigned::from_transaction_and_signature(
+                    Transaction::Eip1559(TxEip1559 {
+                        max_fee_per_gas: 1_000,
+                        max_priority_fee_per_gas,
+                        gas_limit: 21_000,
+                        ..Default::default()
+                    }),
+                    Signature::default(),
+                )
+            })
+            .collect();
+        let header =
+            Header { number, parent_hash, base_fee_per_gas: Some(7), ..Default::default() };
+        SealedBlockWithSenders {
+            block: Block { header, body, ..Default::default() }.seal_slow(),
+            senders: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn new_blocks_refresh_suggested_tip() {
+        // the provider only knows the headers, so the oracle can't fetch the blocks on its own
+        let provider = MockEthProvider::default();
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        let config = GasPriceOracleConfig {
+            min_suggested_priority_fee: Some(U256::ZERO),
+            ..Default::default()
+        };
+        let oracle = GasPriceOracle::new(provider.clone(), config, cache);
+
+        let mut parent_hash = B256::ZERO;
+        let blocks = (1..=3)
+            .map(|number| {
+                let block = block_with_tips(number, parent_hash, &[number as u128 * 10]);
+                provider.add_header(block.hash(), block.header.header().clone());
+                parent_hash = block.hash();
+                block
+            })
+            .collect::<Vec<_>>();
+        let head = blocks.last().unwrap().hash();
+
+        let chain = Chain::new(blocks, ExecutionOutcome::default(), None);
+        let events =
+            futures::stream::iter([CanonStateNotification::Commit { new: Arc::new(chain) }]);
+        gas_oracle_new_blocks_task(&oracle, events).await;
+
+        let last_price = oracle.inner.lock().await.last_price.clone();
+        assert_eq!(last_price.block_hash, head);
+        // the 60th percentile of the tips 10, 20 and 30
+        assert_eq!(last_price.price, U256::from(20));
+        assert_eq!(oracle.suggest_tip_cap().await.unwrap(), U256::from(20));
+    }
+
+    #[tokio::test]
+    async fn suggested_tip_is_raised_to_min_on_quiet_chain() {
+        // the provider only knows the headers, so the blocks are passed to the oracle directly
+        let provider = MockEthProvider::default();
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        let config = GasPriceOracleConfig {
+            blocks: 1,
+            min_suggested_priority_fee: Some(U256::from(100)),
+            ..Default::default()
+        };
+        let oracle = GasPriceOracle::new(provider.clone(), config, cache);
+
+        let mut parent_hash = B256::ZERO;
+        let mut blocks = Vec::new();
+        let mut add_block = |number, tips: &[u128]| {
+            let block = block_with_tips(number, parent_hash, tips);
+            provider.add_header(block.hash(), block.header.header().clone());
+            parent_hash = block.hash();
+            block
+        };
+
+        // blocks with tiny tips
+        blocks.push(add_block(0, &[]));
+        for number in 1..=3 {
+            blocks.push(add_block(number, &[5]));
+        }
+        oracle.on_new_blocks(&blocks).await;
+        assert_eq!(oracle.suggest_tip_cap().await.unwrap(), U256::from(100));
+
+        // followed by empty blocks
+        let blocks = (4..=6).map(|number| add_block(number, &[])).collect::<Vec<_>>();
+        oracle.on_new_blocks(&blocks).await;
+        assert_eq!(oracle.suggest_tip_cap().await.unwrap(), U256::from(100));
+
+        // a higher sampled tip is not lowered
+        oracle.on_new_blocks(&[add_block(7, &[500])]).await;
+        assert_eq!(oracle.suggest_tip_cap().await.unwrap(), U256::from(500));
+    }
+
+    #[tokio::test]
+    async fn suggested_tip_uses_configured_percentile() {
+        // ten blocks with a single tip each, since only the lowest tips of a block are sampled
+        let provider = MockEthProvider::default();
+        let mut parent_hash = B256::ZERO;
+        let blocks = (1..=10)
+            .map(|number| {
+                let block = block_with_tips(number, parent_hash, &[number as u128 * 10]);
+                provider.add_header(block.hash(), block.header.header().clone());
+                parent_hash = block.hash();
+                block
+            })
+            .collect::<Vec<_>>();
+
+        let suggestion = |percentile| {
+            let cache =
+                EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+            let config = GasPriceOracleConfig {
+                blocks: 10,
+                percentile,
+                min_suggested_priority_fee: Some(U256::ZERO),
+                ..Default::default()
+            };
+            let oracle = GasPriceOracle::new(provider.clone(), config, cache);
+            let blocks = blocks.clone();
+            async move {
+                oracle.on_new_blocks(&blocks).await;
+                oracle.suggest_tip_cap().await.unwrap()
+            }
+        };
+
+        let median = suggestion(50).await;
+        let high = suggestion(90).await;
+        assert_eq!(median, U256::from(50));
+        assert_eq!(high, U256::from(90));
+        assert!(high > median);
+
+        // percentiles above 100 are clamped
+        assert_eq!(suggestion(150).await, U256::from(100));
+    }
+
+    #[test]
+    fn min_suggested_priority_fee_sanity() {
+        assert_eq!(DEFAULT_MIN_SUGGESTED_PRIORITY_FEE, U256::from(GWEI_TO_WEI));
+        assert_eq!(DEFAULT_OP_MIN_SUGGESTED_PRIORITY_FEE, U256::from(GWEI_TO_WEI / 1000));
+    }
+
+    #[tokio::test]
+    async fn min_suggested_priority_fee_depends_on_chain() {
+        let min_suggested_priority_fee = |provider: MockEthProvider, config| {
+            let cache =
+                EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+            GasPriceOracle::new(provider, config, cache).config().min_suggested_priority_fee
+        };
+
+        let provider = MockEthProvider::default();
+        assert_eq!(
+            min_suggested_priority_fee(provider, GasPriceOracleConfig::default()),
+            Some(DEFAULT_MIN_SUGGESTED_PRIORITY_FEE)
+        );
+
+        let provider = MockEthProvider {
+            chain_spec: Arc::new(ChainSpec {
+                chain: reth_chainspec::Chain::optimism_mainnet(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            min_suggested_priority_fee(provider.clone(), GasPriceOracleConfig::default()),
+            Some(DEFAULT_OP_MIN_SUGGESTED_PRIORITY_FEE)
+        );
+
+        // a configured minimum is kept
+        let config = GasPriceOracleConfig {
+            min_suggested_priority_fee: Some(U256::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(min_suggested_priority_fee(provider, config), Some(U256::ZERO));
+    }
+
     #[test]
     fn max_price_sanity() {
         assert_eq!(DEFAULT_MAX_GAS_PRICE, U256::from(500_000_000_000u64));
@@ -0,0 +1,72 @@
+//! Health of the node as reported by the `eth` API.
+
+use std::time::Duration;
+
+/// The number of blocks the node may lag behind the highest block seen on the network and still
+/// be considered synced.
+pub const DEFAULT_MAX_BLOCKS_BEHIND: u64 = 64;
+
+/// Health and readiness of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthStatus {
+    /// Number of connected peers.
+    pub peers: usize,
+    /// The best block of the node.
+    pub best_block: u64,
+    /// The highest block seen on the network.
+    pub highest_block: u64,
+    /// The time elapsed since the timestamp of the best block, if the block is known.
+    pub latest_block_age: Option<Duration>,
+    /// The number of blocks the node may lag behind the highest block and still be synced.
+    pub max_blocks_behind: u64,
+}
+
+impl HealthStatus {
+    /// Returns true if the node is connected to at least one peer.
+    pub const fn has_peers(&self) -> bool {
+        self.peers > 0
+    }
+
+    /// Returns the number of blocks the node is behind the highest block seen on the network.
+    pub const fn blocks_behind(&self) -> u64 {
+        self.highest_block.saturating_sub(self.best_block)
+    }
+
+    /// Returns true if the node is at most [`Self::max_blocks_behind`] blocks behind the highest
+    /// block seen on the network.
+    pub const fn is_synced(&self) -> bool {
+        self.blocks_behind() <= self.max_blocks_behind
+    }
+
+    /// Returns true if the node has peers and is synced.
+    pub const fn is_healthy(&self) -> bool {
+        self.has_peers() && self.is_synced()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healthy_within_max_blocks_behind() {
+        let mut status = HealthStatus {
+            peers: 3,
+            best_block: 100,
+            highest_block: 100 + DEFAULT_MAX_BLOCKS_BEHIND,
+            latest_block_age: Some(Duration::from_secs(12)),
+            max_blocks_behind: DEFAULT_MAX_BLOCKS_BEHIND,
+        };
+        assert!(status.is_healthy());
+
+        status.highest_block += 1;
+        assert!(!status.is_synced());
+        assert!(!status.is_healthy());
+
+        status.highest_block = 0;
+        assert_eq!(status.blocks_behind(), 0);
+        status.peers = 0;
+        assert!(!status.has_peers());
+        assert!(!status.is_healthy());
+    }
+}
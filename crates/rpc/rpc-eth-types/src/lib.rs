@@ -9,26 +9,44 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 pub mod cache;
+pub mod call_cache;
+pub mod chain_info;
 pub mod error;
+pub mod estimate;
 pub mod fee_history;
 pub mod gas_oracle;
+pub mod health;
 pub mod id_provider;
 pub mod logs_utils;
 pub mod pending_block;
 pub mod receipt;
 pub mod revm_utils;
+pub mod storage_range;
 pub mod transaction;
 pub mod utils;
 
 pub use cache::{
     config::EthStateCacheConfig, db::StateCacheDb, multi_consumer::MultiConsumerLruCache,
-    EthStateCache,
+    EthStateCache, StreamedReceipt,
 };
+pub use call_cache::{EthCallCache, EthCallCacheKey};
+pub use chain_info::ChainInfoCache;
 pub use error::{EthApiError, EthResult, RevertError, RpcInvalidTransactionError, SignError};
-pub use fee_history::{FeeHistoryCache, FeeHistoryCacheConfig, FeeHistoryEntry};
-pub use gas_oracle::{GasCap, GasPriceOracle, GasPriceOracleConfig, GasPriceOracleResult};
+pub use estimate::{GasEstimate, GasEstimateBound};
+pub use fee_history::{
+    FeeHistoryCache, FeeHistoryCacheConfig, FeeHistoryCacheEviction, FeeHistoryCacheInfo,
+    FeeHistoryEntry,
+};
+pub use gas_oracle::{
+    GasCap, GasCaps, GasOracle, GasPriceOracle, GasPriceOracleConfig, GasPriceOracleResult,
+};
+pub use health::{HealthStatus, DEFAULT_MAX_BLOCKS_BEHIND};
 pub use id_provider::EthSubscriptionIdProvider;
 pub use logs_utils::EthFilterError;
-pub use pending_block::{PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin};
+pub use pending_block::{
+    PendingBlock, PendingBlockBuildReport, PendingBlockCache, PendingBlockEnv,
+    PendingBlockEnvOrigin, SkippedTransaction, SkippedTransactionReason,
+};
 pub use receipt::ReceiptBuilder;
+pub use storage_range::StorageRangePage;
 pub use transaction::TransactionSource;
@@ -2,11 +2,13 @@
 //!
 //! Log parsing for building filter.
 
+use std::time::Duration;
+
 use reth_chainspec::ChainInfo;
-use reth_primitives::{BlockNumHash, Receipt, TxHash};
+use reth_primitives::{BlockNumHash, Bloom, Receipt, TxHash, TxNumber};
 use reth_provider::{BlockReader, ProviderError};
 use reth_rpc_server_types::result::rpc_error_with_code;
-use reth_rpc_types::{FilterId, FilteredParams, Log};
+use reth_rpc_types::{BloomFilter, FilterId, FilteredParams, Log};
 
 use crate::EthApiError;
 
@@ -23,8 +25,31 @@ pub enum EthFilterError {
     #[error("query exceeds max block range {0}")]
     QueryExceedsMaxBlocks(u64),
     /// Query result is too large.
-    #[error("query exceeds max results {0}")]
-    QueryExceedsMaxResults(usize),
+    ///
+    /// The query can be resumed with the range of blocks whose logs fit into a response, if the
+    /// logs of at least one block fit.
+    #[error("query exceeds max results {max_logs}{}", retry_range(*.from_block, *.to_block))]
+    QueryExceedsMaxResults {
+        /// Maximum number of logs that can be returned in a response.
+        max_logs: usize,
+        /// The first block of the query.
+        from_block: u64,
+        /// The last block whose logs fit into a response, if any.
+        to_block: Option<u64>,
+    },
+    /// Query took longer than the time budget.
+    ///
+    /// The query can be resumed with the range of blocks that were processed in time, if at least
+    /// one block was processed.
+    #[error("query exceeds time budget {budget:?}{}", retry_range(*.from_block, *.to_block))]
+    QueryExceedsTimeBudget {
+        /// The time budget of a query.
+        budget: Duration,
+        /// The first block of the query.
+        from_block: u64,
+        /// The last block that was processed in time, if any.
+        to_block: Option<u64>,
+    },
     /// Error serving request in `eth_` namespace.
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
@@ -46,19 +71,42 @@ impl From<EthFilterError> for jsonrpsee_types::error::ErrorObject<'static> {
             EthFilterError::EthAPIError(err) => err.into(),
             err @ EthFilterError::InvalidBlockRangeParams |
             err @ EthFilterError::QueryExceedsMaxBlocks(_) |
-            err @ EthFilterError::QueryExceedsMaxResults(_) => {
+            err @ EthFilterError::QueryExceedsMaxResults { .. } |
+            err @ EthFilterError::QueryExceedsTimeBudget { .. } => {
                 rpc_error_with_code(jsonrpsee_types::error::INVALID_PARAMS_CODE, err.to_string())
             }
         }
     }
 }
 
+/// Formats the range a query can be retried with, if any block of the query was completed.
+fn retry_range(from_block: u64, to_block: Option<u64>) -> String {
+    to_block
+        .map(|to_block| format!(", retry with the range {from_block}-{to_block}"))
+        .unwrap_or_default()
+}
+
 impl From<ProviderError> for EthFilterError {
     fn from(err: ProviderError) -> Self {
         Self::EthAPIError(err.into())
     }
 }
 
+/// Returns the logs of a block's receipts together with the index of their transaction and their
+/// index in the entire block, as `(tx_index, log_index, log)`.
+///
+/// The receipts must be all receipts of the block in order, starting at the first one.
+pub fn enumerate_block_logs<'a>(
+    receipts: impl IntoIterator<Item = &'a Receipt>,
+) -> impl Iterator<Item = (u64, u64, &'a reth_primitives::Log)> {
+    receipts
+        .into_iter()
+        .enumerate()
+        .flat_map(|(tx_index, receipt)| receipt.logs.iter().map(move |log| (tx_index as u64, log)))
+        .enumerate()
+        .map(|(log_index, (tx_index, log))| (tx_index, log_index as u64, log))
+}
+
 /// Returns all matching of a block's receipts when the transaction hashes are known.
 pub fn matching_block_logs_with_tx_hashes<'a, I>(
     filter: &FilteredParams,
@@ -69,30 +117,21 @@ pub fn matching_block_logs_with_tx_hashes<'a, I>(
 where
     I: IntoIterator<Item = (TxHash, &'a Receipt)>,
 {
-    let mut all_logs = Vec::new();
-    // Tracks the index of a log in the entire block.
-    let mut log_index: u64 = 0;
-    // Iterate over transaction hashes and receipts and append matching logs.
-    for (receipt_idx, (tx_hash, receipt)) in tx_hashes_and_receipts.into_iter().enumerate() {
-        for log in &receipt.logs {
-            if log_matches_filter(block_num_hash, log, filter) {
-                let log = Log {
-                    inner: log.clone(),
-                    block_hash: Some(block_num_hash.hash),
-                    block_number: Some(block_num_hash.number),
-                    transaction_hash: Some(tx_hash),
-                    // The transaction and receipt index is always the same.
-                    transaction_index: Some(receipt_idx as u64),
-                    log_index: Some(log_index),
-                    removed,
-                    block_timestamp: None,
-                };
-                all_logs.push(log);
-            }
-            log_index += 1;
-        }
-    }
-    all_logs
+    let (tx_hashes, receipts): (Vec<_>, Vec<_>) = tx_hashes_and_receipts.into_iter().unzip();
+    enumerate_block_logs(receipts)
+        .filter(|(_, _, log)| log_matches_filter(block_num_hash, log, filter))
+        .map(|(tx_index, log_index, log)| Log {
+            inner: log.clone(),
+            block_hash: Some(block_num_hash.hash),
+            block_number: Some(block_num_hash.number),
+            transaction_hash: Some(tx_hashes[tx_index as usize]),
+            // The transaction and receipt index is always the same.
+            transaction_index: Some(tx_index),
+            log_index: Some(log_index),
+            removed,
+            block_timestamp: None,
+        })
+        .collect()
 }
 
 /// Appends all matching logs of a block's receipts.
@@ -106,29 +145,83 @@ pub fn append_matching_block_logs(
     removed: bool,
     block_timestamp: u64,
 ) -> Result<(), EthFilterError> {
-    // Tracks the index of a log in the entire block.
-    let mut log_index: u64 = 0;
+    let mut appender =
+        BlockLogsAppender::new(provider, filter, block_num_hash, removed, block_timestamp);
+    for (receipt_idx, receipt) in receipts.iter().enumerate() {
+        appender.append_receipt_logs(all_logs, receipt_idx, receipt)?;
+    }
+    Ok(())
+}
 
-    // Lazy loaded number of the first transaction in the block.
-    // This is useful for blocks with multiple matching logs because it prevents
-    // re-querying the block body indices.
-    let mut loaded_first_tx_num = None;
+/// Appends the matching logs of a block's receipts one receipt at a time, so the receipts don't
+/// need to be loaded at once.
+///
+/// See also [`append_matching_block_logs`].
+#[derive(Debug)]
+pub struct BlockLogsAppender<'a, P> {
+    provider: P,
+    filter: &'a FilteredParams,
+    block_num_hash: BlockNumHash,
+    removed: bool,
+    block_timestamp: u64,
+    /// Tracks the index of a log in the entire block.
+    log_index: u64,
+    /// Lazy loaded number of the first transaction in the block.
+    ///
+    /// This is useful for blocks with multiple matching logs because it prevents re-querying the
+    /// block body indices.
+    first_tx_num: Option<TxNumber>,
+}
+
+impl<'a, P: BlockReader> BlockLogsAppender<'a, P> {
+    /// Creates a new appender for the logs of the given block.
+    pub const fn new(
+        provider: P,
+        filter: &'a FilteredParams,
+        block_num_hash: BlockNumHash,
+        removed: bool,
+        block_timestamp: u64,
+    ) -> Self {
+        Self {
+            provider,
+            filter,
+            block_num_hash,
+            removed,
+            block_timestamp,
+            log_index: 0,
+            first_tx_num: None,
+        }
+    }
+
+    /// Returns the block the logs are appended for.
+    pub const fn block_num_hash(&self) -> BlockNumHash {
+        self.block_num_hash
+    }
+
+    /// Appends the matching logs of the receipt at the given index in the block.
+    ///
+    /// The receipts of the block must be appended in order, starting at the first one.
+    pub fn append_receipt_logs(
+        &mut self,
+        all_logs: &mut Vec<Log>,
+        receipt_idx: usize,
+        receipt: &Receipt,
+    ) -> Result<(), EthFilterError> {
+        let block_num_hash = self.block_num_hash;
 
-    // Iterate over receipts and append matching logs.
-    for (receipt_idx, receipt) in receipts.iter().enumerate() {
         // The transaction hash of the current receipt.
         let mut transaction_hash = None;
 
         for log in &receipt.logs {
-            if log_matches_filter(block_num_hash, log, filter) {
-                let first_tx_num = match loaded_first_tx_num {
+            if log_matches_filter(block_num_hash, log, self.filter) {
+                let first_tx_num = match self.first_tx_num {
                     Some(num) => num,
                     None => {
                         let block_body_indices =
-                            provider.block_body_indices(block_num_hash.number)?.ok_or(
+                            self.provider.block_body_indices(block_num_hash.number)?.ok_or(
                                 ProviderError::BlockBodyIndicesNotFound(block_num_hash.number),
                             )?;
-                        loaded_first_tx_num = Some(block_body_indices.first_tx_num);
+                        self.first_tx_num = Some(block_body_indices.first_tx_num);
                         block_body_indices.first_tx_num
                     }
                 };
@@ -137,7 +230,8 @@ pub fn append_matching_block_logs(
                 if transaction_hash.is_none() {
                     // This is safe because Transactions and Receipts have the same keys.
                     let transaction_id = first_tx_num + receipt_idx as u64;
-                    let transaction = provider
+                    let transaction = self
+                        .provider
                         .transaction_by_id(transaction_id)?
                         .ok_or(ProviderError::TransactionNotFound(transaction_id.into()))?;
 
@@ -151,16 +245,16 @@ pub fn append_matching_block_logs(
                     transaction_hash,
                     // The transaction and receipt index is always the same.
                     transaction_index: Some(receipt_idx as u64),
-                    log_index: Some(log_index),
-                    removed,
-                    block_timestamp: Some(block_timestamp),
+                    log_index: Some(self.log_index),
+                    removed: self.removed,
+                    block_timestamp: Some(self.block_timestamp),
                 };
                 all_logs.push(log);
             }
-            log_index += 1;
+            self.log_index += 1;
         }
+        Ok(())
     }
-    Ok(())
 }
 
 /// Returns true if the log matches the filter and should be included
@@ -180,6 +274,21 @@ pub fn log_matches_filter(
     true
 }
 
+/// Returns true if a block with the given logs bloom may contain logs matching the address and
+/// topics bloom filters, see [`FilteredParams::address_filter`] and
+/// [`FilteredParams::topics_filter`].
+///
+/// Blocks for which this returns false can be skipped without loading their receipts.
+#[inline]
+pub fn log_matches_bloom(
+    bloom: Bloom,
+    address_filter: &BloomFilter,
+    topics_filter: &[BloomFilter],
+) -> bool {
+    FilteredParams::matches_address(bloom, address_filter) &&
+        FilteredParams::matches_topics(bloom, topics_filter)
+}
+
 /// Computes the block range based on the filter range and current block numbers
 pub fn get_filter_block_range(
     from_block: Option<u64>,
@@ -208,10 +317,70 @@ pub fn get_filter_block_range(
 
 #[cfg(test)]
 mod tests {
+    use reth_primitives::{Address, B256};
     use reth_rpc_types::Filter;
 
     use super::*;
 
+    #[test]
+    fn bloom_prefilter_skips_non_matching_blocks() {
+        let address = Address::random();
+        let topic = B256::random();
+
+        // only the block at index 3 contains a log of the address with the topic
+        let blooms = (0..10)
+            .map(|idx| {
+                let mut bloom = Bloom::default();
+                if idx == 3 {
+                    bloom.accrue_raw_log(address, &[topic]);
+                } else {
+                    bloom.accrue_raw_log(Address::random(), &[B256::random()]);
+                }
+                bloom
+            })
+            .collect::<Vec<_>>();
+
+        let filter = Filter::new().address(address).event_signature(topic);
+        let address_filter = FilteredParams::address_filter(&filter.address);
+        let topics_filter = FilteredParams::topics_filter(&filter.topics);
+        let matching = blooms
+            .iter()
+            .enumerate()
+            .filter(|(_, bloom)| log_matches_bloom(**bloom, &address_filter, &topics_filter))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        assert_eq!(matching, vec![3]);
+
+        // an empty filter matches every block
+        let filter = Filter::new();
+        let address_filter = FilteredParams::address_filter(&filter.address);
+        let topics_filter = FilteredParams::topics_filter(&filter.topics);
+        assert!(blooms.iter().all(|bloom| log_matches_bloom(
+            *bloom,
+            &address_filter,
+            &topics_filter
+        )));
+    }
+
+    #[test]
+    fn block_logs_have_contiguous_indices() {
+        let receipt = |num_logs: usize| Receipt {
+            logs: (0..num_logs)
+                .map(|_| reth_primitives::Log { address: Address::random(), ..Default::default() })
+                .collect(),
+            ..Default::default()
+        };
+        let receipts = [receipt(2), receipt(0), receipt(3), receipt(1)];
+
+        let indices = enumerate_block_logs(&receipts)
+            .map(|(tx_index, log_index, _)| (tx_index, log_index))
+            .collect::<Vec<_>>();
+        assert_eq!(indices, vec![(0, 0), (0, 1), (2, 2), (2, 3), (2, 4), (3, 5)]);
+
+        let logs = enumerate_block_logs(&receipts).map(|(_, _, log)| log);
+        assert!(logs.eq(receipts.iter().flat_map(|receipt| &receipt.logs)));
+    }
+
     #[test]
     fn test_log_range_from_and_to() {
         let from = 14000000u64;
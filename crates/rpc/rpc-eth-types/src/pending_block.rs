@@ -5,14 +5,23 @@
 use std::{fmt, time::Instant};
 
 use derive_more::Constructor;
+use futures::{Stream, StreamExt};
 use reth_chainspec::ChainSpec;
-use reth_primitives::{BlockId, BlockNumberOrTag, SealedBlockWithSenders, SealedHeader, B256};
-use reth_provider::ProviderError;
+use reth_execution_types::ExecutionOutcome;
+use reth_primitives::{
+    BlockId, BlockNumber, BlockNumberOrTag, SealedBlockWithSenders, SealedHeader, TxHash, B256,
+    U256,
+};
+use reth_provider::{CanonStateNotification, ProviderError};
 use reth_revm::state_change::{apply_beacon_root_contract_call, apply_blockhashes_update};
+use reth_rpc_server_types::constants::DEFAULT_PENDING_BLOCK_CACHE_SIZE;
+use reth_rpc_types::engine::PayloadAttributes;
 use revm_primitives::{
     db::{Database, DatabaseCommit},
     BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg,
 };
+use schnellru::{ByLength, LruMap};
+use tokio::sync::Mutex;
 
 use super::{EthApiError, EthResult};
 
@@ -104,6 +113,17 @@ pub enum PendingBlockEnvOrigin {
     ///  - the block number
     ///  - fees
     DerivedFromLatest(SealedHeader),
+    /// The header of the latest block, modified by externally supplied payload attributes.
+    ///
+    /// Like [`PendingBlockEnvOrigin::DerivedFromLatest`], but the timestamp, fee recipient,
+    /// prevRandao and parent beacon block root of the pending block are taken from the
+    /// attributes, and the pending block includes their withdrawals.
+    FromAttributes {
+        /// The _modified_ header of the latest block.
+        header: SealedHeader,
+        /// The attributes the pending block is built with.
+        attributes: PayloadAttributes,
+    },
 }
 
 impl PendingBlockEnvOrigin {
@@ -127,19 +147,21 @@ impl PendingBlockEnvOrigin {
     pub fn state_block_id(&self) -> BlockId {
         match self {
             Self::ActualPending(_) => BlockNumberOrTag::Pending.into(),
-            Self::DerivedFromLatest(header) => BlockId::Hash(header.hash().into()),
+            Self::DerivedFromLatest(header) | Self::FromAttributes { header, .. } => {
+                BlockId::Hash(header.hash().into())
+            }
         }
     }
 
     /// Returns the hash of the block the pending block should be built on.
     ///
     /// For the [`PendingBlockEnvOrigin::ActualPending`] this is the parent hash of the block.
-    /// For the [`PendingBlockEnvOrigin::DerivedFromLatest`] this is the hash of the _latest_
-    /// header.
+    /// For the [`PendingBlockEnvOrigin::DerivedFromLatest`] and
+    /// [`PendingBlockEnvOrigin::FromAttributes`] this is the hash of the _latest_ header.
     pub fn build_target_hash(&self) -> B256 {
         match self {
             Self::ActualPending(block) => block.parent_hash,
-            Self::DerivedFromLatest(header) => header.hash(),
+            Self::DerivedFromLatest(header) | Self::FromAttributes { header, .. } => header.hash(),
         }
     }
 
@@ -147,7 +169,7 @@ impl PendingBlockEnvOrigin {
     pub fn header(&self) -> &SealedHeader {
         match self {
             Self::ActualPending(block) => &block.header,
-            Self::DerivedFromLatest(header) => header,
+            Self::DerivedFromLatest(header) | Self::FromAttributes { header, .. } => header,
         }
     }
 }
@@ -157,6 +179,289 @@ impl PendingBlockEnvOrigin {
 pub struct PendingBlock {
     /// The cached pending block
     pub block: SealedBlockWithSenders,
+    /// The state changes and receipts of the pending block
+    pub execution_outcome: ExecutionOutcome,
     /// Timestamp when the pending block is considered outdated
     pub expires_at: Instant,
 }
+
+impl PendingBlock {
+    /// Returns the timestamp when the pending block is considered outdated.
+    pub const fn expires_at(&self) -> Instant {
+        self.expires_at
+    }
+
+    /// Returns true if the pending block is outdated at the given time.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now > self.expires_at
+    }
+
+    /// Returns true if the pending block can still be served for the given [`PendingBlockEnv`] at
+    /// the given time.
+    ///
+    /// This is the case if the block was built on top of the env's build target, has the env's
+    /// block number and hasn't expired yet.
+    pub fn is_valid_for(&self, env: &PendingBlockEnv, now: Instant) -> bool {
+        self.block.parent_hash == env.origin.build_target_hash() &&
+            U256::from(self.block.number) == env.block_env.number &&
+            !self.is_expired(now)
+    }
+}
+
+/// Report of how the pool transactions were handled when building a pending block locally.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingBlockBuildReport {
+    /// Hashes of the transactions included in the block, in block order.
+    pub included: Vec<TxHash>,
+    /// Hashes of the included transactions whose execution reverted.
+    ///
+    /// Reverted transactions still pay for their gas, so they are part of the block.
+    pub reverted: Vec<TxHash>,
+    /// Transactions that were left out of the block, in the order they were yielded by the pool.
+    ///
+    /// Descendants of a skipped transaction are never yielded by the pool, so they aren't listed.
+    pub skipped: Vec<SkippedTransaction>,
+}
+
+impl PendingBlockBuildReport {
+    /// Returns the reason the transaction with the given hash was skipped, if it was.
+    pub fn skip_reason(&self, hash: &TxHash) -> Option<&SkippedTransactionReason> {
+        self.skipped.iter().find(|skipped| skipped.hash == *hash).map(|skipped| &skipped.reason)
+    }
+}
+
+/// A pool transaction that was left out of a locally built pending block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedTransaction {
+    /// Hash of the transaction.
+    pub hash: TxHash,
+    /// Why the transaction was skipped.
+    pub reason: SkippedTransactionReason,
+}
+
+/// Why a pool transaction was left out of a locally built pending block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkippedTransactionReason {
+    /// The gas limit of the transaction exceeds the gas left in the block.
+    GasLimitReached,
+    /// The blob gas of the transaction exceeds the blob gas left in the block.
+    BlobGasLimitReached,
+    /// The transaction was submitted as private, which is kept out of the pending block.
+    Private,
+    /// The nonce of the transaction is lower than the nonce of the sender.
+    NonceTooLow,
+    /// The nonce of the transaction is higher than the nonce of the sender.
+    NonceGap,
+    /// The sender can't pay for the gas and value of the transaction.
+    InsufficientFunds,
+    /// The transaction is invalid for any other reason.
+    Invalid(String),
+}
+
+/// LRU cache of locally built pending blocks, keyed by their parent hash and block number.
+///
+/// Caching more than one pending block lets requests for pending blocks on top of different
+/// parents, e.g. around a reorg, be served without rebuilding.
+///
+/// The cache also holds the most recent actual pending block received from the CL, keyed by its
+/// hash.
+#[derive(Debug)]
+pub struct PendingBlockCache {
+    blocks: LruMap<(B256, BlockNumber), PendingBlock, ByLength>,
+    actual_pending: Option<SealedBlockWithSenders>,
+}
+
+impl PendingBlockCache {
+    /// Creates a new cache holding at most `max_len` pending blocks.
+    pub fn new(max_len: u32) -> Self {
+        Self { blocks: LruMap::new(ByLength::new(max_len.max(1))), actual_pending: None }
+    }
+
+    /// Returns the cached actual pending block if it has the given hash.
+    pub fn actual_pending(&self, hash: B256) -> Option<&SealedBlockWithSenders> {
+        self.actual_pending.as_ref().filter(|block| block.hash() == hash)
+    }
+
+    /// Replaces the cached actual pending block.
+    pub fn set_actual_pending(&mut self, block: SealedBlockWithSenders) {
+        self.actual_pending = Some(block);
+    }
+
+    /// Returns the pending block built on top of the given parent, and promotes it to be the most
+    /// recently used.
+    pub fn get(&mut self, parent_hash: B256, number: BlockNumber) -> Option<&PendingBlock> {
+        self.blocks.get(&(parent_hash, number)).map(|block| &*block)
+    }
+
+    /// Inserts a pending block, evicting the least recently used one if the cache is full.
+    pub fn insert(&mut self, pending_block: PendingBlock) {
+        let key = (pending_block.block.parent_hash, pending_block.block.number);
+        self.blocks.insert(key, pending_block);
+    }
+
+    /// Evicts the pending blocks that were realized or made obsolete by a newly committed block.
+    ///
+    /// Pending blocks are built on top of the latest block, so once a block is committed the
+    /// cached pending blocks at or below its number can't be requested anymore, and the next
+    /// pending request builds on top of the committed block instead. Returns true if one of the
+    /// evicted blocks was built on the parent of the committed block, i.e. was realized by it.
+    pub fn on_committed_block(&mut self, committed: &SealedHeader) -> bool {
+        let realized = self.blocks.peek(&(committed.parent_hash, committed.number)).is_some();
+        let obsolete = self
+            .blocks
+            .iter()
+            .filter(|((_, number), _)| *number <= committed.number)
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+        for key in obsolete {
+            self.blocks.remove(&key);
+        }
+        if self.actual_pending.as_ref().is_some_and(|block| block.number <= committed.number) {
+            self.actual_pending = None;
+        }
+        realized
+    }
+
+    /// Removes all locally built pending blocks, keeping the actual pending block.
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Returns an iterator over the cached pending blocks, most recently used first.
+    pub fn iter(&self) -> impl Iterator<Item = &PendingBlock> + '_ {
+        self.blocks.iter().map(|(_, block)| block)
+    }
+}
+
+impl Default for PendingBlockCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PENDING_BLOCK_CACHE_SIZE)
+    }
+}
+
+/// Awaits for new chain events and evicts the cached pending blocks that were realized or made
+/// obsolete by the tip of the committed chain.
+///
+/// See also [`PendingBlockCache::on_committed_block`].
+pub async fn pending_block_cache_new_blocks_task<St>(
+    pending_block: &Mutex<PendingBlockCache>,
+    mut events: St,
+) where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        pending_block.lock().await.on_committed_block(&event.tip().header);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlock};
+
+    fn pending_block(parent_hash: B256, number: BlockNumber) -> PendingBlock {
+        let header = Header { parent_hash, number, ..Default::default() }.seal_slow();
+        let block = SealedBlockWithSenders {
+            block: SealedBlock { header, ..Default::default() },
+            senders: vec![],
+        };
+        PendingBlock::new(block, ExecutionOutcome::default(), Instant::now())
+    }
+
+    fn pending_block_env(parent: &SealedHeader, number: u64) -> PendingBlockEnv {
+        PendingBlockEnv::new(
+            CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), Default::default()),
+            BlockEnv { number: U256::from(number), ..Default::default() },
+            PendingBlockEnvOrigin::DerivedFromLatest(parent.clone()),
+        )
+    }
+
+    #[test]
+    fn pending_block_validity() {
+        let latest = Header { number: 1, ..Default::default() }.seal_slow();
+        let env = pending_block_env(&latest, 2);
+        let block = pending_block(latest.hash(), 2);
+        let now = block.expires_at();
+
+        // fresh until the expiry
+        assert!(!block.is_expired(now));
+        assert!(block.is_valid_for(&env, now));
+
+        // expired
+        let later = now + std::time::Duration::from_millis(1);
+        assert!(block.is_expired(later));
+        assert!(!block.is_valid_for(&env, later));
+
+        // built on a different parent
+        let other = pending_block(B256::random(), 2);
+        assert!(!other.is_valid_for(&env, other.expires_at()));
+
+        // built for a different block number
+        assert!(!block.is_valid_for(&pending_block_env(&latest, 3), now));
+    }
+
+    #[test]
+    fn caches_pending_blocks_per_parent() {
+        let (first, second, third) = (B256::random(), B256::random(), B256::random());
+        let mut cache = PendingBlockCache::new(2);
+
+        cache.insert(pending_block(first, 1));
+        cache.insert(pending_block(second, 1));
+        assert!(cache.get(first, 1).is_some());
+        assert!(cache.get(second, 1).is_some());
+        assert!(cache.get(first, 2).is_none());
+
+        // exceeding the capacity evicts the least recently used block
+        cache.insert(pending_block(third, 1));
+        assert!(cache.get(first, 1).is_none());
+        assert!(cache.get(second, 1).is_some());
+        assert!(cache.get(third, 1).is_some());
+    }
+
+    #[test]
+    fn caches_actual_pending_block_by_hash() {
+        let mut cache = PendingBlockCache::default();
+        let first = pending_block(B256::random(), 1).block;
+        let second = pending_block(first.hash(), 2).block;
+
+        cache.set_actual_pending(first.clone());
+        assert_eq!(cache.actual_pending(first.hash()), Some(&first));
+        assert!(cache.actual_pending(second.hash()).is_none());
+
+        cache.set_actual_pending(second.clone());
+        assert!(cache.actual_pending(first.hash()).is_none());
+        assert_eq!(cache.actual_pending(second.hash()), Some(&second));
+    }
+
+    #[test]
+    fn committed_block_evicts_realized_pending_block() {
+        let latest = B256::random();
+        let mut cache = PendingBlockCache::new(4);
+        cache.insert(pending_block(latest, 2));
+        cache.insert(pending_block(B256::random(), 3));
+        cache.set_actual_pending(pending_block(latest, 2).block);
+
+        // a block on a different parent realizes no cached pending block
+        let unrelated = Header { parent_hash: B256::random(), number: 1, ..Default::default() };
+        assert!(!cache.on_committed_block(&unrelated.seal_slow()));
+        assert_eq!(cache.iter().count(), 2);
+
+        let committed = Header { parent_hash: latest, number: 2, ..Default::default() };
+        assert!(cache.on_committed_block(&committed.seal_slow()));
+        assert!(cache.get(latest, 2).is_none());
+        assert!(cache.actual_pending.is_none());
+        // pending blocks above the committed block are kept
+        assert_eq!(cache.iter().count(), 1);
+    }
+
+    #[test]
+    fn default_cache_holds_a_single_block() {
+        let (first, second) = (B256::random(), B256::random());
+        let mut cache = PendingBlockCache::default();
+
+        cache.insert(pending_block(first, 1));
+        cache.insert(pending_block(second, 1));
+        assert!(cache.get(first, 1).is_none());
+        assert!(cache.get(second, 1).is_some());
+    }
+}
@@ -7,7 +7,7 @@ use reth_rpc_types::{
 };
 use revm_primitives::calc_blob_gasprice;
 
-use super::{EthApiError, EthResult};
+use super::{logs_utils::enumerate_block_logs, EthApiError, EthResult};
 
 /// Receipt response builder.
 #[derive(Debug)]
@@ -52,26 +52,21 @@ impl ReceiptBuilder {
             blob_gas_used.and_then(|_| meta.excess_blob_gas.map(calc_blob_gasprice));
         let logs_bloom = receipt.bloom_slow();
 
-        // get number of logs in the block
-        let mut num_logs = 0;
-        for prev_receipt in all_receipts.iter().take(meta.index as usize) {
-            num_logs += prev_receipt.logs.len();
-        }
-
-        let mut logs = Vec::with_capacity(receipt.logs.len());
-        for (tx_log_idx, log) in receipt.logs.iter().enumerate() {
-            let rpclog = Log {
+        // the logs of the transaction with their index in the entire block
+        let logs = enumerate_block_logs(all_receipts)
+            .skip_while(|(tx_index, _, _)| *tx_index < meta.index)
+            .take_while(|(tx_index, _, _)| *tx_index == meta.index)
+            .map(|(_, log_index, log)| Log {
                 inner: log.clone(),
                 block_hash: Some(meta.block_hash),
                 block_number: Some(meta.block_number),
                 block_timestamp: Some(meta.timestamp),
                 transaction_hash: Some(meta.tx_hash),
                 transaction_index: Some(meta.index),
-                log_index: Some((num_logs + tx_log_idx) as u64),
+                log_index: Some(log_index),
                 removed: false,
-            };
-            logs.push(rpclog);
-        }
+            })
+            .collect();
 
         let rpc_receipt = reth_rpc_types::Receipt {
             status: receipt.success.into(),
@@ -124,3 +119,75 @@ impl ReceiptBuilder {
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::{
+        constants::eip4844::DATA_GAS_PER_BLOB, Transaction, TxEip1559, TxEip4844, TxLegacy, B256,
+    };
+    use reth_testing_utils::generators::{self, sign_tx_with_random_key_pair};
+
+    use super::*;
+
+    fn build_receipt(transaction: Transaction) -> TransactionReceipt<AnyReceiptEnvelope<Log>> {
+        let transaction = sign_tx_with_random_key_pair(&mut generators::rng(), transaction);
+        let meta = TransactionMeta {
+            tx_hash: transaction.hash,
+            base_fee: Some(10),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let receipt = Receipt { cumulative_gas_used: 21_000, success: true, ..Default::default() };
+        ReceiptBuilder::new(&transaction, meta, &receipt, &[receipt.clone()]).unwrap().build().inner
+    }
+
+    #[test]
+    fn legacy_receipt_pays_gas_price() {
+        let receipt = build_receipt(Transaction::Legacy(TxLegacy {
+            gas_price: 15,
+            gas_limit: 21_000,
+            ..Default::default()
+        }));
+        assert_eq!(receipt.effective_gas_price, 15);
+        assert_eq!(receipt.blob_gas_price, None);
+        assert_eq!(receipt.blob_gas_used, None);
+    }
+
+    #[test]
+    fn eip1559_receipt_pays_capped_tip() {
+        // base fee plus the full tip is below the max fee
+        let receipt = build_receipt(Transaction::Eip1559(TxEip1559 {
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 5,
+            gas_limit: 21_000,
+            ..Default::default()
+        }));
+        assert_eq!(receipt.effective_gas_price, 15);
+
+        // the max fee caps base fee plus tip
+        let receipt = build_receipt(Transaction::Eip1559(TxEip1559 {
+            max_fee_per_gas: 12,
+            max_priority_fee_per_gas: 5,
+            gas_limit: 21_000,
+            ..Default::default()
+        }));
+        assert_eq!(receipt.effective_gas_price, 12);
+        assert_eq!(receipt.blob_gas_price, None);
+    }
+
+    #[test]
+    fn eip4844_receipt_reports_blob_gas() {
+        let receipt = build_receipt(Transaction::Eip4844(TxEip4844 {
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 5,
+            max_fee_per_blob_gas: 10,
+            blob_versioned_hashes: vec![B256::ZERO, B256::ZERO],
+            gas_limit: 21_000,
+            ..Default::default()
+        }));
+        assert_eq!(receipt.effective_gas_price, 15);
+        // zero excess blob gas yields the minimum blob gas price
+        assert_eq!(receipt.blob_gas_price, Some(1));
+        assert_eq!(receipt.blob_gas_used, Some(2 * DATA_GAS_PER_BLOB as u128));
+    }
+}
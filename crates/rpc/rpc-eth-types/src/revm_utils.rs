@@ -37,6 +37,9 @@ pub fn get_precompiles(spec_id: SpecId) -> impl IntoIterator<Item = Address> {
 ///  - `disable_eip3607` is set to `true`
 ///  - `disable_base_fee` is set to `true`
 ///  - `nonce` is set to `None`
+///
+/// Requests with a gas limit above the given `gas_limit` cap are rejected, requests without a gas
+/// limit are capped by it.
 pub fn prepare_call_env<DB>(
     mut cfg: CfgEnvWithHandlerCfg,
     mut block: BlockEnv,
@@ -74,6 +77,12 @@ where
     }
 
     let request_gas = request.gas;
+    if let Some(gas) = request_gas.filter(|gas| *gas > gas_limit as u128) {
+        return Err(EthApiError::GasCapExceeded {
+            cap: gas_limit,
+            requested: gas.try_into().unwrap_or(u64::MAX),
+        })
+    }
     let mut env = build_call_evm_env(cfg, block, request)?;
     // set nonce to None so that the next nonce is used when transacting the call
     env.tx.nonce = None;
@@ -347,7 +356,7 @@ impl CallFees {
 }
 
 /// Applies the given block overrides to the env
-fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
+pub fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
     let BlockOverrides {
         number,
         difficulty,
@@ -382,6 +391,46 @@ fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
     }
 }
 
+/// Executes calls under the rules of the given hardfork instead of the ones of the block.
+///
+/// The block env is completed with the fields the hardfork requires: the difficulty is used as
+/// `prevrandao` after the merge and the excess blob gas defaults to zero from Cancun onwards,
+/// while it's removed before Cancun. Requests that use transaction features the hardfork doesn't
+/// support are rejected.
+pub fn apply_spec_id_override(
+    spec_id: SpecId,
+    cfg: &mut CfgEnvWithHandlerCfg,
+    block: &mut BlockEnv,
+    request: &TransactionRequest,
+) -> EthResult<()> {
+    if !spec_id.is_enabled_in(SpecId::LONDON) &&
+        (request.max_fee_per_gas.is_some() || request.max_priority_fee_per_gas.is_some())
+    {
+        return Err(EthApiError::InvalidParams(format!(
+            "EIP-1559 fees are not supported before London, got spec id {spec_id:?}"
+        )))
+    }
+    if !spec_id.is_enabled_in(SpecId::CANCUN) &&
+        (request.blob_versioned_hashes.is_some() || request.max_fee_per_blob_gas.is_some())
+    {
+        return Err(EthApiError::InvalidParams(format!(
+            "blob transactions are not supported before Cancun, got spec id {spec_id:?}"
+        )))
+    }
+
+    if spec_id.is_enabled_in(SpecId::MERGE) && block.prevrandao.is_none() {
+        block.prevrandao = Some(block.difficulty.into());
+    }
+    if !spec_id.is_enabled_in(SpecId::CANCUN) {
+        block.blob_excess_gas_and_price = None;
+    } else if block.blob_excess_gas_and_price.is_none() {
+        block.set_blob_excess_gas_and_price(0);
+    }
+
+    cfg.handler_cfg.spec_id = spec_id;
+    Ok(())
+}
+
 /// Applies the given state overrides (a set of [`AccountOverride`]) to the [`CacheDB`].
 pub fn apply_state_overrides<DB>(overrides: StateOverride, db: &mut CacheDB<DB>) -> EthResult<()>
 where
@@ -0,0 +1,22 @@
+//! Paginated storage dumps of an account.
+
+use reth_primitives::{B256, U256};
+
+/// A page of the storage slots of an account, ordered by the keccak256 hash of their key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageRangePage {
+    /// The storage slots of the page as `(hashed_key, value)` pairs.
+    pub storage: Vec<(B256, U256)>,
+    /// The hashed key of the first slot of the next page, or `None` if this is the last page.
+    pub next_key: Option<B256>,
+}
+
+impl StorageRangePage {
+    /// Creates a page from up to `max_results + 1` consecutive slots, where the additional slot
+    /// only serves as the cursor to the next page.
+    pub fn new(mut slots: Vec<(B256, U256)>, max_results: usize) -> Self {
+        let next_key = (slots.len() > max_results).then(|| slots[max_results].0);
+        slots.truncate(max_results);
+        Self { storage: slots, next_key }
+    }
+}
@@ -4,7 +4,9 @@
 
 use reth_primitives::{TransactionSignedEcRecovered, B256};
 use reth_rpc_types::{Transaction, TransactionInfo};
-use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
+use reth_rpc_types_compat::transaction::{
+    from_recovered_with_block_context, from_recovered_with_pending_block_context,
+};
 
 /// Represents from where a transaction was fetched.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -26,6 +28,17 @@ pub enum TransactionSource {
         /// base fee of the block.
         base_fee: Option<u64>,
     },
+    /// Transaction included in the locally built pending block
+    PendingBlock {
+        /// Transaction fetched from the pending block
+        transaction: TransactionSignedEcRecovered,
+        /// Index of the transaction in the pending block
+        index: u64,
+        /// Number of the pending block.
+        block_number: u64,
+        /// base fee of the pending block.
+        base_fee: Option<u64>,
+    },
 }
 
 // === impl TransactionSource ===
@@ -65,6 +78,19 @@ impl TransactionSource {
                     },
                 )
             }
+            Self::PendingBlock { transaction, index, block_number, base_fee } => {
+                let hash = transaction.hash();
+                (
+                    transaction,
+                    TransactionInfo {
+                        hash: Some(hash),
+                        index: Some(index),
+                        block_hash: None,
+                        block_number: Some(block_number),
+                        base_fee: base_fee.map(u128::from),
+                    },
+                )
+            }
         }
     }
 }
@@ -73,7 +99,8 @@ impl From<TransactionSource> for TransactionSignedEcRecovered {
     fn from(value: TransactionSource) -> Self {
         match value {
             TransactionSource::Pool(tx) => tx,
-            TransactionSource::Block { transaction, .. } => transaction,
+            TransactionSource::Block { transaction, .. } |
+            TransactionSource::PendingBlock { transaction, .. } => transaction,
         }
     }
 }
@@ -91,6 +118,14 @@ impl From<TransactionSource> for Transaction {
                     index as usize,
                 )
             }
+            TransactionSource::PendingBlock { transaction, index, block_number, base_fee } => {
+                from_recovered_with_pending_block_context(
+                    transaction,
+                    block_number,
+                    base_fee,
+                    index as usize,
+                )
+            }
         }
     }
 }
@@ -15,6 +15,21 @@ pub const DEFAULT_MAX_BLOCKS_PER_FILTER: u64 = 100_000;
 /// The default maximum of logs in a single response.
 pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 
+/// The default maximum number of blocks that can be simulated in a single `eth_simulateV1` request.
+pub const DEFAULT_MAX_SIMULATE_BLOCKS: u64 = 256;
+
+/// The default maximum number of storage slots that can be proven in a single `eth_getProof`
+/// request.
+pub const DEFAULT_MAX_PROOF_STORAGE_SLOTS: usize = 1024;
+
+/// The default maximum number of locally built pending blocks that are cached.
+pub const DEFAULT_PENDING_BLOCK_CACHE_SIZE: u32 = 1;
+
+/// The default maximum number of blocking tasks of the `eth` API that run concurrently.
+///
+/// This matches the default maximum number of threads in tokio's blocking pool.
+pub const DEFAULT_MAX_BLOCKING_TASKS: usize = 512;
+
 /// The default maximum number tracing requests we're allowing concurrently.
 /// Tracing is mostly CPU bound so we're limiting the number of concurrent requests to something
 /// lower that the number of cores, in order to minimize the impact on the rest of the system.
@@ -65,6 +80,16 @@ pub mod gas_oracle {
     /// The default minimum gas price, under which the sample will be ignored
     pub const DEFAULT_IGNORE_GAS_PRICE: U256 = U256::from_limbs([2u64, 0, 0, 0]);
 
+    /// The default minimum priority fee (or gas price before London Fork) to be recommended by
+    /// the gas price oracle, 1 gwei
+    pub const DEFAULT_MIN_SUGGESTED_PRIORITY_FEE: U256 =
+        U256::from_limbs([1_000_000_000u64, 0, 0, 0]);
+
+    /// The default minimum priority fee to be recommended by the gas price oracle on OP chains,
+    /// 0.001 gwei like op-geth, since their tips are orders of magnitude lower
+    pub const DEFAULT_OP_MIN_SUGGESTED_PRIORITY_FEE: U256 =
+        U256::from_limbs([1_000_000u64, 0, 0, 0]);
+
     /// The default gas limit for `eth_call` and adjacent calls.
     ///
     /// This is different from the default to regular 30M block gas limit
@@ -105,4 +130,10 @@ pub mod cache {
 
     /// Default number of concurrent database requests.
     pub const DEFAULT_CONCURRENT_DB_REQUESTS: usize = 512;
+
+    /// Default number of recently missing blocks and receipts that are remembered.
+    pub const DEFAULT_MISSING_ENTRIES_MAX_LEN: u32 = 1000;
+
+    /// Default time in milliseconds a missing block or receipts are remembered for.
+    pub const DEFAULT_MISSING_ENTRY_TTL_MS: u64 = 1000;
 }
@@ -1,6 +1,8 @@
 //! Compatibility functions for rpc `Block` type.
 
-use crate::transaction::from_recovered_with_block_context;
+use crate::transaction::{
+    from_recovered_with_block_context, from_recovered_with_pending_block_context,
+};
 use alloy_rlp::Encodable;
 use reth_primitives::{
     Block as PrimitiveBlock, BlockWithSenders, Header as PrimitiveHeader, Withdrawals, B256, U256,
@@ -25,6 +27,27 @@ pub fn from_block(
     }
 }
 
+/// Converts the given pending block into a [Block] response with the given
+/// [`BlockTransactionsKind`].
+///
+/// Full transactions are populated with the context of the pending block, see
+/// [`from_recovered_with_pending_block_context`].
+pub fn from_pending_block(
+    block: BlockWithSenders,
+    total_difficulty: U256,
+    kind: BlockTransactionsKind,
+    block_hash: Option<B256>,
+) -> Result<Block, BlockError> {
+    match kind {
+        BlockTransactionsKind::Hashes => {
+            Ok(from_block_with_tx_hashes(block, total_difficulty, block_hash))
+        }
+        BlockTransactionsKind::Full => {
+            Ok(from_block_with_full_transactions(block, total_difficulty, block_hash, true))
+        }
+    }
+}
+
 /// Create a new [Block] response from a [primitive block](reth_primitives::Block), using the
 /// total difficulty to populate its field in the rpc response.
 ///
@@ -53,10 +76,21 @@ pub fn from_block_with_tx_hashes(
 /// This will populate the `transactions` field with the _full_
 /// [Transaction](reth_rpc_types::Transaction) objects: [`BlockTransactions::Full`]
 pub fn from_block_full(
-    mut block: BlockWithSenders,
+    block: BlockWithSenders,
     total_difficulty: U256,
     block_hash: Option<B256>,
 ) -> Result<Block, BlockError> {
+    Ok(from_block_with_full_transactions(block, total_difficulty, block_hash, false))
+}
+
+/// Populates the `transactions` field with the _full_ transaction objects, using the pending block
+/// context if `pending` is true.
+fn from_block_with_full_transactions(
+    mut block: BlockWithSenders,
+    total_difficulty: U256,
+    block_hash: Option<B256>,
+    pending: bool,
+) -> Block {
     let block_hash = block_hash.unwrap_or_else(|| block.block.header.hash_slow());
     let block_number = block.block.number;
     let base_fee_per_gas = block.block.base_fee_per_gas;
@@ -71,23 +105,32 @@ pub fn from_block_full(
         .map(|(idx, (tx, sender))| {
             let signed_tx_ec_recovered = tx.with_signer(sender);
 
-            from_recovered_with_block_context(
-                signed_tx_ec_recovered,
-                block_hash,
-                block_number,
-                base_fee_per_gas,
-                idx,
-            )
+            if pending {
+                from_recovered_with_pending_block_context(
+                    signed_tx_ec_recovered,
+                    block_number,
+                    base_fee_per_gas,
+                    idx,
+                )
+            } else {
+                from_recovered_with_block_context(
+                    signed_tx_ec_recovered,
+                    block_hash,
+                    block_number,
+                    base_fee_per_gas,
+                    idx,
+                )
+            }
         })
         .collect::<Vec<_>>();
 
-    Ok(from_block_with_transactions(
+    from_block_with_transactions(
         block_length,
         block_hash,
         block.block,
         total_difficulty,
         BlockTransactions::Full(transactions),
-    ))
+    )
 }
 
 /// Converts from a [`reth_primitives::SealedHeader`] to a [`reth_rpc_types::Header`]
@@ -24,6 +24,19 @@ pub fn from_recovered_with_block_context(
     fill(tx, Some(block_hash), Some(block_number), base_fee, Some(tx_index))
 }
 
+/// Create a new rpc transaction result for a transaction included in the locally built pending
+/// block, using the given number, and tx index fields of the pending block.
+///
+/// The block hash is `None`, since the pending block is not sealed.
+pub fn from_recovered_with_pending_block_context(
+    tx: TransactionSignedEcRecovered,
+    block_number: BlockNumber,
+    base_fee: Option<u64>,
+    tx_index: usize,
+) -> Transaction {
+    fill(tx, None, Some(block_number), base_fee, Some(tx_index))
+}
+
 /// Create a new rpc transaction result for a _pending_ signed transaction, setting block
 /// environment related fields to `None`.
 pub fn from_recovered(tx: TransactionSignedEcRecovered) -> Transaction {
@@ -0,0 +1,121 @@
+//! Types for the `eth_simulateV1` endpoint
+
+use crate::{state::StateOverride, BlockOverrides, Log, ReceiptWithBloom, TransactionRequest};
+use alloy_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// The payload of `eth_simulateV1`: a chain of blocks whose calls are simulated on top of each
+/// other.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order.
+    #[serde(default)]
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to include the full receipt of every call in the response.
+    #[serde(default)]
+    pub return_full_receipts: bool,
+}
+
+/// A simulated block with optional block and state overrides and the calls it contains.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    /// Overrides of the block environment, e.g. the number or the timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// State overrides applied before the first call of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute, in order.
+    #[serde(default)]
+    pub calls: Vec<TransactionRequest>,
+}
+
+/// The result of a simulated block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// The block number.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub number: u64,
+    /// The block timestamp.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub timestamp: u64,
+    /// The gas limit of the block.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub gas_limit: u64,
+    /// The gas used by all calls of the block.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub gas_used: u64,
+    /// The base fee of the block.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub base_fee_per_gas: u128,
+    /// The results of the calls, in order.
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The result of a single simulated call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// The output of the call, or the revert data if it reverted.
+    pub return_data: Bytes,
+    /// The logs emitted by the call.
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    /// The gas used by the call.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub gas_used: u64,
+    /// Whether the call succeeded.
+    #[serde(with = "alloy_rpc_types::serde_helpers::quantity")]
+    pub status: bool,
+    /// The error if the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+    /// The receipt of the call, only included if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<ReceiptWithBloom<Log>>,
+}
+
+/// The error of a failed simulated call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulateError {
+    /// The JSON-RPC error code.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_deserialize_payload() {
+        let s = r#"{
+            "blockStateCalls": [{
+                "blockOverrides": { "number": "0x2a" },
+                "calls": [{
+                    "from": "0xc000000000000000000000000000000000000000",
+                    "to": "0xc100000000000000000000000000000000000000",
+                    "value": "0x3e8"
+                }]
+            }],
+            "returnFullReceipts": true
+        }"#;
+        let payload: SimulatePayload = serde_json::from_str(s).unwrap();
+        assert_eq!(payload.block_state_calls.len(), 1);
+        assert_eq!(payload.block_state_calls[0].calls.len(), 1);
+        assert!(payload.return_full_receipts);
+    }
+
+    #[test]
+    fn serializes_call_result() {
+        let result = SimCallResult { gas_used: 21000, status: true, ..Default::default() };
+        let value = serde_json::to_value(result).unwrap();
+        assert_eq!(value["gasUsed"], "0x5208");
+        assert_eq!(value["status"], "0x1");
+        assert!(value.get("error").is_none());
+    }
+}
@@ -275,6 +275,7 @@ where
             opts;
         let overrides = EvmOverrides::new(state_overrides, block_overrides.map(Box::new));
         let GethDebugTracingOptions { config, tracer, tracer_config, .. } = tracing_options;
+        let gas_limit = self.inner.eth_api.trace_gas_limit();
 
         let this = self.clone();
         if let Some(tracer) = tracer {
@@ -285,10 +286,16 @@ where
                         let inspector = self
                             .inner
                             .eth_api
-                            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                this.eth_api().inspect(db, env, &mut inspector)?;
-                                Ok(inspector)
-                            })
+                            .spawn_with_call_at_capped(
+                                call,
+                                at,
+                                overrides,
+                                gas_limit,
+                                move |db, env| {
+                                    this.eth_api().inspect(db, env, &mut inspector)?;
+                                    Ok(inspector)
+                                },
+                            )
                             .await?;
                         return Ok(FourByteFrame::from(inspector).into())
                     }
@@ -304,13 +311,20 @@ where
                         let frame = self
                             .inner
                             .eth_api
-                            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                let (res, _) = this.eth_api().inspect(db, env, &mut inspector)?;
-                                let frame = inspector
-                                    .into_geth_builder()
-                                    .geth_call_traces(call_config, res.result.gas_used());
-                                Ok(frame.into())
-                            })
+                            .spawn_with_call_at_capped(
+                                call,
+                                at,
+                                overrides,
+                                gas_limit,
+                                move |db, env| {
+                                    let (res, _) =
+                                        this.eth_api().inspect(db, env, &mut inspector)?;
+                                    let frame = inspector
+                                        .into_geth_builder()
+                                        .geth_call_traces(call_config, res.result.gas_used());
+                                    Ok(frame.into())
+                                },
+                            )
                             .await?;
                         return Ok(frame)
                     }
@@ -322,10 +336,15 @@ where
                             TracingInspectorConfig::from_geth_prestate_config(&prestate_config),
                         );
 
-                        let frame =
-                            self.inner
-                                .eth_api
-                                .spawn_with_call_at(call, at, overrides, move |db, env| {
+                        let frame = self
+                            .inner
+                            .eth_api
+                            .spawn_with_call_at_capped(
+                                call,
+                                at,
+                                overrides,
+                                gas_limit,
+                                move |db, env| {
                                     // wrapper is hack to get around 'higher-ranked lifetime error',
                                     // see <https://github.com/rust-lang/rust/issues/100013>
                                     let db = db.0;
@@ -336,8 +355,9 @@ where
                                         .into_geth_builder()
                                         .geth_prestate_traces(&res, prestate_config, db)?;
                                     Ok(frame)
-                                })
-                                .await?;
+                                },
+                            )
+                            .await?;
                         return Ok(frame.into())
                     }
                     GethDebugBuiltInTracerType::NoopTracer => Ok(NoopFrame::default().into()),
@@ -351,16 +371,22 @@ where
                         let frame = self
                             .inner
                             .eth_api
-                            .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                // wrapper is hack to get around 'higher-ranked lifetime error', see
-                                // <https://github.com/rust-lang/rust/issues/100013>
-                                let db = db.0;
+                            .spawn_with_call_at_capped(
+                                call,
+                                at,
+                                overrides,
+                                gas_limit,
+                                move |db, env| {
+                                    // wrapper is hack to get around 'higher-ranked lifetime error',
+                                    // see <https://github.com/rust-lang/rust/issues/100013>
+                                    let db = db.0;
 
-                                let (res, _) =
-                                    this.eth_api().inspect(&mut *db, env, &mut inspector)?;
-                                let frame = inspector.try_into_mux_frame(&res, db)?;
-                                Ok(frame.into())
-                            })
+                                    let (res, _) =
+                                        this.eth_api().inspect(&mut *db, env, &mut inspector)?;
+                                    let frame = inspector.try_into_mux_frame(&res, db)?;
+                                    Ok(frame.into())
+                                },
+                            )
                             .await?;
                         return Ok(frame)
                     }
@@ -373,16 +399,25 @@ where
                     let res = self
                         .inner
                         .eth_api
-                        .spawn_with_call_at(call, at, overrides, move |db, env| {
-                            // wrapper is hack to get around 'higher-ranked lifetime error', see
-                            // <https://github.com/rust-lang/rust/issues/100013>
-                            let db = db.0;
-
-                            let mut inspector = JsInspector::new(code, config)?;
-                            let (res, _) =
-                                this.eth_api().inspect(&mut *db, env.clone(), &mut inspector)?;
-                            Ok(inspector.json_result(res, &env, db)?)
-                        })
+                        .spawn_with_call_at_capped(
+                            call,
+                            at,
+                            overrides,
+                            gas_limit,
+                            move |db, env| {
+                                // wrapper is hack to get around 'higher-ranked lifetime error', see
+                                // <https://github.com/rust-lang/rust/issues/100013>
+                                let db = db.0;
+
+                                let mut inspector = JsInspector::new(code, config)?;
+                                let (res, _) = this.eth_api().inspect(
+                                    &mut *db,
+                                    env.clone(),
+                                    &mut inspector,
+                                )?;
+                                Ok(inspector.json_result(res, &env, db)?)
+                            },
+                        )
                         .await?;
 
                     Ok(GethTrace::JS(res))
@@ -398,7 +433,7 @@ where
         let (res, inspector) = self
             .inner
             .eth_api
-            .spawn_with_call_at(call, at, overrides, move |db, env| {
+            .spawn_with_call_at_capped(call, at, overrides, gas_limit, move |db, env| {
                 let (res, _) = this.eth_api().inspect(db, env, &mut inspector)?;
                 Ok((res, inspector))
             })
@@ -435,7 +470,7 @@ where
         let opts = opts.unwrap_or_default();
         let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
         let GethDebugTracingCallOptions { tracing_options, mut state_overrides, .. } = opts;
-        let gas_limit = self.inner.eth_api.call_gas_limit();
+        let gas_limit = self.inner.eth_api.trace_gas_limit();
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -714,6 +749,11 @@ where
         Err(internal_rpc_err("unimplemented"))
     }
 
+    /// Handler for `debug_getPendingBlockBuildError`
+    async fn pending_block_build_error(&self) -> RpcResult<Option<String>> {
+        Ok(self.inner.eth_api.pending_block_build_error().read().clone())
+    }
+
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
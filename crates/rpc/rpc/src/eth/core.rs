@@ -1,16 +1,32 @@
 //! Implementation of the [`jsonrpsee`] generated [`EthApiServer`](crate::EthApi) trait
 //! Handles RPC requests for the `eth_` namespace.
 
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use reth_primitives::{BlockNumberOrTag, U256};
+use reth_primitives::{Address, BlockNumberOrTag, U256};
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider};
 use reth_rpc_eth_api::{
     helpers::{EthSigner, SpawnBlocking},
-    RawTransactionForwarder,
+    RawTransactionForwarder, TxValidationHook,
+};
+use reth_rpc_eth_types::{
+    ChainInfoCache, EthApiError, EthCallCache, EthResult, EthStateCache, FeeHistoryCache,
+    FeeHistoryCacheInfo, GasCaps, GasOracle, GasPriceOracle, PendingBlockCache,
+};
+use reth_rpc_server_types::constants::{
+    DEFAULT_MAX_BLOCKING_TASKS, DEFAULT_MAX_PROOF_STORAGE_SLOTS,
+};
+use reth_rpc_types::engine::PayloadAttributes;
+use reth_tasks::{
+    pool::{BlockingTaskGuard, BlockingTaskPool},
+    TaskSpawner, TokioTaskExecutor,
 };
-use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, GasCap, GasPriceOracle, PendingBlock};
-use reth_tasks::{pool::BlockingTaskPool, TaskSpawner, TokioTaskExecutor};
 use tokio::sync::Mutex;
 
 use crate::eth::DevSigner;
@@ -36,6 +52,101 @@ impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConf
     pub fn set_eth_raw_transaction_forwarder(&self, forwarder: Arc<dyn RawTransactionForwarder>) {
         self.inner.raw_transaction_forwarder.write().replace(forwarder);
     }
+
+    /// Sets the hook that validates transactions submitted via `eth_sendRawTransaction` before
+    /// they're forwarded or added to the pool.
+    pub fn set_tx_validation_hook(&self, hook: Arc<dyn TxValidationHook>) {
+        self.inner.tx_validation_hook.write().replace(hook);
+    }
+
+    /// Sets the block number the current sync started from, reported as `startingBlock` by
+    /// `eth_syncing`.
+    ///
+    /// This should be called by the node whenever a new sync run begins, until then the block
+    /// number of the latest block at construction time is reported.
+    pub fn set_starting_block(&self, block_number: u64) {
+        self.inner.starting_block.store(block_number, Ordering::Relaxed);
+    }
+
+    /// Sets the block number the sync has progressed to, e.g. the checkpoint of the execution
+    /// stage, reported as `currentBlock` by `eth_syncing`.
+    ///
+    /// This is reported as is, so it can be below the best block after an unwind.
+    pub fn set_current_block(&self, block_number: u64) {
+        self.inner.current_block.store(block_number, Ordering::Relaxed);
+    }
+
+    /// Sets the block number the sync is heading towards, reported as `highestBlock` by
+    /// `eth_syncing`.
+    ///
+    /// The node sets this to the reached block once a sync run completes.
+    pub fn set_highest_block(&self, block_number: u64) {
+        self.inner.highest_block.store(block_number, Ordering::Relaxed);
+    }
+
+    /// Sets whether transactions submitted as private are included in the locally built pending
+    /// block. They are excluded by default.
+    pub fn set_include_private_in_pending(&self, include: bool) {
+        self.inner.include_private_in_pending.store(include, Ordering::Relaxed);
+    }
+
+    /// Sets the maximum number of distinct storage slots that can be proven in a single
+    /// `eth_getProof` request.
+    pub fn set_max_proof_storage_slots(&self, max_slots: usize) {
+        self.inner.max_proof_storage_slots.store(max_slots, Ordering::Relaxed);
+    }
+
+    /// Sets the fee recipient of the blocks built by the node, reported by
+    /// [`EthApiSpec::fee_recipient`](reth_rpc_eth_api::helpers::EthApiSpec::fee_recipient).
+    ///
+    /// The node doesn't configure a fee recipient, so this is only set by embedders that build
+    /// blocks with their own payload builder.
+    pub fn set_fee_recipient(&self, fee_recipient: Option<Address>) {
+        *self.inner.fee_recipient.write() = fee_recipient;
+    }
+
+    /// Sets the payload attributes the locally built pending block is built with, instead of
+    /// deriving them from the latest block. `None` restores the derived pending block.
+    ///
+    /// The cached pending blocks are discarded, so the next pending request builds a new block.
+    ///
+    /// The node derives the pending block from the latest block, so this is only set by
+    /// embedders, e.g. with the attributes of their own payload builder.
+    pub async fn set_pending_block_attributes(&self, attributes: Option<PayloadAttributes>) {
+        *self.inner.pending_block_attributes.write() = attributes;
+        self.inner.pending_block.lock().await.clear();
+    }
+
+    /// Sets the maximum number of blocking tasks, like `eth_call` or tracing, that run
+    /// concurrently. Further tasks are queued until a running task completes.
+    ///
+    /// This only applies to tasks spawned after the call.
+    pub fn set_max_blocking_tasks(&self, max_blocking_tasks: usize) {
+        *self.inner.blocking_task_guard.write() = BlockingTaskGuard::new(max_blocking_tasks);
+    }
+
+    /// Sets how long a request waits for the result of a blocking task, like `eth_call` or
+    /// tracing, before it fails with a timeout error. `None`, the default, waits indefinitely.
+    ///
+    /// The timed out task itself isn't aborted, see
+    /// [`SpawnBlocking::blocking_task_timeout`].
+    pub fn set_blocking_task_timeout(&self, timeout: Option<Duration>) {
+        *self.inner.blocking_task_timeout.write() = timeout;
+    }
+
+    /// Replaces the gas oracle that suggests the priority fee for `eth_maxPriorityFeePerGas` and
+    /// `eth_gasPrice`, e.g. with one that is backed by an external feed.
+    ///
+    /// The built-in [`GasPriceOracle`] is still used for the `eth_feeHistory` limits.
+    pub fn set_gas_oracle(&self, gas_oracle: Arc<dyn GasOracle>) {
+        self.inner.custom_gas_oracle.write().replace(gas_oracle);
+    }
+
+    /// Sets the maximum number of `eth_call` outputs that are cached, replacing the cached
+    /// outputs. The cache is disabled by default, which is the same as a size of zero.
+    pub fn set_call_cache_size(&self, max_len: u32) {
+        *self.inner.call_cache.lock() = EthCallCache::new(max_len);
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
@@ -50,7 +161,9 @@ where
         network: Network,
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
-        gas_cap: impl Into<GasCap>,
+        gas_cap: impl Into<GasCaps>,
+        max_simulate_blocks: u64,
+        pending_block_cache_size: u32,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
         evm_config: EvmConfig,
@@ -62,7 +175,9 @@ where
             network,
             eth_cache,
             gas_oracle,
-            gas_cap.into().into(),
+            gas_cap,
+            max_simulate_blocks,
+            pending_block_cache_size,
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
@@ -79,7 +194,9 @@ where
         network: Network,
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
-        gas_cap: u64,
+        gas_cap: impl Into<GasCaps>,
+        max_simulate_blocks: u64,
+        pending_block_cache_size: u32,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
@@ -101,14 +218,30 @@ where
             signers: parking_lot::RwLock::new(Default::default()),
             eth_cache,
             gas_oracle,
-            gas_cap,
-            starting_block: U256::from(latest_block),
+            custom_gas_oracle: Default::default(),
+            gas_caps: gas_cap.into(),
+            max_simulate_blocks,
+            starting_block: AtomicU64::new(latest_block),
+            current_block: AtomicU64::new(latest_block),
+            highest_block: AtomicU64::new(latest_block),
+            chain_info: Default::default(),
             task_spawner,
-            pending_block: Default::default(),
+            pending_block: Mutex::new(PendingBlockCache::new(pending_block_cache_size)),
+            pending_block_build_error: Default::default(),
+            call_cache: Default::default(),
+            include_private_in_pending: AtomicBool::new(false),
+            max_proof_storage_slots: AtomicUsize::new(DEFAULT_MAX_PROOF_STORAGE_SLOTS),
+            pending_block_attributes: Default::default(),
+            fee_recipient: Default::default(),
             blocking_task_pool,
+            blocking_task_guard: parking_lot::RwLock::new(BlockingTaskGuard::new(
+                DEFAULT_MAX_BLOCKING_TASKS,
+            )),
+            blocking_task_timeout: Default::default(),
             fee_history_cache,
             evm_config,
             raw_transaction_forwarder: parking_lot::RwLock::new(raw_transaction_forwarder),
+            tx_validation_hook: Default::default(),
         };
 
         Self { inner: Arc::new(inner) }
@@ -124,9 +257,20 @@ where
         &self.inner.gas_oracle
     }
 
-    /// Returns the configured gas limit cap for `eth_call` and tracing related calls
-    pub fn gas_cap(&self) -> u64 {
-        self.inner.gas_cap
+    /// Returns a handle to the cached pending blocks.
+    pub fn pending_block_cache(&self) -> &Mutex<PendingBlockCache> {
+        self.inner.pending_block()
+    }
+
+    /// Returns a handle to the cached info about the tip of the canonical chain.
+    pub fn chain_info_cache(&self) -> &ChainInfoCache {
+        self.inner.chain_info()
+    }
+
+    /// Returns the configured gas limit caps for `eth_call`, `eth_estimateGas` and tracing related
+    /// calls
+    pub fn gas_caps(&self) -> GasCaps {
+        self.inner.gas_caps
     }
 
     /// Returns the inner `Provider`
@@ -148,6 +292,30 @@ where
     pub fn fee_history_cache(&self) -> &FeeHistoryCache {
         &self.inner.fee_history_cache
     }
+
+    /// Returns the range of blocks `eth_feeHistory` can serve from memory and how the fee history
+    /// cache is configured.
+    pub fn fee_history_cache_info(&self) -> FeeHistoryCacheInfo {
+        self.inner.fee_history_cache.info()
+    }
+
+    /// Checks that the genesis block in the database is the genesis of the configured chain.
+    ///
+    /// The chain id is read from the chain spec, so if the node is pointed at the datadir of a
+    /// different chain, responses would mix up both chains. A database without a genesis block
+    /// passes the check.
+    pub fn check_genesis(&self) -> EthResult<()> {
+        let chain_spec = self.provider().chain_spec();
+        let expected = chain_spec.genesis_hash();
+        match self.provider().block_hash(0)? {
+            Some(stored) if stored != expected => Err(EthApiError::GenesisHashMismatch {
+                chain_id: chain_spec.chain().id(),
+                expected,
+                stored,
+            }),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> std::fmt::Debug
@@ -178,6 +346,16 @@ where
     fn tracing_task_pool(&self) -> &reth_tasks::pool::BlockingTaskPool {
         self.inner.blocking_task_pool()
     }
+
+    #[inline]
+    fn blocking_task_guard(&self) -> Option<BlockingTaskGuard> {
+        Some(self.inner.blocking_task_guard())
+    }
+
+    #[inline]
+    fn blocking_task_timeout(&self) -> Option<Duration> {
+        self.inner.blocking_task_timeout()
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig> {
@@ -187,6 +365,12 @@ impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConf
         let mut signers = self.inner.signers.write();
         *signers = DevSigner::random_signers(20);
     }
+
+    /// Replaces the configured signers with the given ones.
+    pub fn with_signers(self, signers: Vec<Box<dyn EthSigner>>) -> Self {
+        *self.inner.signers.write() = signers;
+        self
+    }
 }
 
 /// Container type `EthApi`
@@ -204,22 +388,51 @@ pub struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     eth_cache: EthStateCache,
     /// The async gas oracle frontend for gas price suggestions
     gas_oracle: GasPriceOracle<Provider>,
-    /// Maximum gas limit for `eth_call` and call tracing RPC methods.
-    gas_cap: u64,
-    /// The block number at which the node started
-    starting_block: U256,
+    /// Replaces the gas oracle for priority fee suggestions if set.
+    custom_gas_oracle: parking_lot::RwLock<Option<Arc<dyn GasOracle>>>,
+    /// Maximum gas limits for `eth_call`, `eth_estimateGas` and call tracing RPC methods.
+    gas_caps: GasCaps,
+    /// Maximum number of blocks for `eth_simulateV1`.
+    max_simulate_blocks: u64,
+    /// The block number at which the current sync started, or the latest block at construction
+    /// time if no sync was reported.
+    starting_block: AtomicU64,
+    /// The block number the current sync has progressed to.
+    current_block: AtomicU64,
+    /// The block number the current sync is targeting.
+    highest_block: AtomicU64,
+    /// Cached info about the tip of the canonical chain.
+    chain_info: ChainInfoCache,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
-    /// Cached pending block if any
-    pending_block: Mutex<Option<PendingBlock>>,
+    /// Cached pending blocks
+    pending_block: Mutex<PendingBlockCache>,
+    /// The reason the most recent local pending block build failed, if it did.
+    pending_block_build_error: parking_lot::RwLock<Option<String>>,
+    /// Whether private transactions are included in the locally built pending block.
+    include_private_in_pending: AtomicBool,
+    /// Maximum number of distinct storage slots per `eth_getProof` request.
+    max_proof_storage_slots: AtomicUsize,
+    /// Externally supplied payload attributes the local pending block is built with.
+    pending_block_attributes: parking_lot::RwLock<Option<PayloadAttributes>>,
+    /// The configured fee recipient of the blocks built by the node.
+    fee_recipient: parking_lot::RwLock<Option<Address>>,
+    /// Cached outputs of `eth_call` requests on top of historical blocks.
+    call_cache: parking_lot::Mutex<EthCallCache>,
     /// A pool dedicated to CPU heavy blocking tasks.
     blocking_task_pool: BlockingTaskPool,
+    /// Limits the number of blocking tasks running concurrently.
+    blocking_task_guard: parking_lot::RwLock<BlockingTaskGuard>,
+    /// How long a request waits for the result of a blocking task.
+    blocking_task_timeout: parking_lot::RwLock<Option<Duration>>,
     /// Cache for block fees history
     fee_history_cache: FeeHistoryCache,
     /// The type that defines how to configure the EVM
     evm_config: EvmConfig,
     /// Allows forwarding received raw transactions
     raw_transaction_forwarder: parking_lot::RwLock<Option<Arc<dyn RawTransactionForwarder>>>,
+    /// Validates received raw transactions before they're submitted
+    tx_validation_hook: parking_lot::RwLock<Option<Arc<dyn TxValidationHook>>>,
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, EvmConfig> {
@@ -235,12 +448,54 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         &self.eth_cache
     }
 
-    /// Returns a handle to the pending block.
+    /// Returns a handle to the cached pending blocks.
     #[inline]
-    pub const fn pending_block(&self) -> &Mutex<Option<PendingBlock>> {
+    pub const fn pending_block(&self) -> &Mutex<PendingBlockCache> {
         &self.pending_block
     }
 
+    /// Returns a handle to the cached info about the tip of the canonical chain.
+    #[inline]
+    pub const fn chain_info(&self) -> &ChainInfoCache {
+        &self.chain_info
+    }
+
+    /// Returns a handle to the reason the most recent local pending block build failed.
+    #[inline]
+    pub const fn pending_block_build_error(&self) -> &parking_lot::RwLock<Option<String>> {
+        &self.pending_block_build_error
+    }
+
+    /// Returns true if private transactions are included in the locally built pending block.
+    #[inline]
+    pub fn include_private_in_pending(&self) -> bool {
+        self.include_private_in_pending.load(Ordering::Relaxed)
+    }
+
+    /// Returns the maximum number of distinct storage slots per `eth_getProof` request.
+    #[inline]
+    pub fn max_proof_storage_slots(&self) -> usize {
+        self.max_proof_storage_slots.load(Ordering::Relaxed)
+    }
+
+    /// Returns the payload attributes the locally built pending block is built with, if set.
+    #[inline]
+    pub fn pending_block_attributes(&self) -> Option<PayloadAttributes> {
+        self.pending_block_attributes.read().clone()
+    }
+
+    /// Returns the configured fee recipient of the blocks built by the node, if set.
+    #[inline]
+    pub fn fee_recipient(&self) -> Option<Address> {
+        *self.fee_recipient.read()
+    }
+
+    /// Returns a handle to the cached outputs of `eth_call` requests.
+    #[inline]
+    pub const fn call_cache(&self) -> &parking_lot::Mutex<EthCallCache> {
+        &self.call_cache
+    }
+
     /// Returns a handle to the task spawner.
     #[inline]
     pub const fn task_spawner(&self) -> &dyn TaskSpawner {
@@ -253,6 +508,18 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         &self.blocking_task_pool
     }
 
+    /// Returns the guard that limits the number of blocking tasks running concurrently.
+    #[inline]
+    pub fn blocking_task_guard(&self) -> BlockingTaskGuard {
+        self.blocking_task_guard.read().clone()
+    }
+
+    /// Returns how long a request waits for the result of a blocking task, if bounded.
+    #[inline]
+    pub fn blocking_task_timeout(&self) -> Option<Duration> {
+        *self.blocking_task_timeout.read()
+    }
+
     /// Returns a handle to the EVM config.
     #[inline]
     pub const fn evm_config(&self) -> &EvmConfig {
@@ -271,10 +538,22 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         self.raw_transaction_forwarder.read().clone()
     }
 
-    /// Returns the gas cap.
+    /// Returns the hook that validates received raw transactions, if set.
     #[inline]
-    pub const fn gas_cap(&self) -> u64 {
-        self.gas_cap
+    pub fn tx_validation_hook(&self) -> Option<Arc<dyn TxValidationHook>> {
+        self.tx_validation_hook.read().clone()
+    }
+
+    /// Returns the gas caps.
+    #[inline]
+    pub const fn gas_caps(&self) -> GasCaps {
+        self.gas_caps
+    }
+
+    /// Returns the maximum number of blocks for `eth_simulateV1`.
+    #[inline]
+    pub const fn max_simulate_blocks(&self) -> u64 {
+        self.max_simulate_blocks
     }
 
     /// Returns a handle to the gas oracle.
@@ -283,6 +562,12 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         &self.gas_oracle
     }
 
+    /// Returns the gas oracle that replaces the built-in one for priority fee suggestions, if set.
+    #[inline]
+    pub fn custom_gas_oracle(&self) -> Option<Arc<dyn GasOracle>> {
+        self.custom_gas_oracle.read().clone()
+    }
+
     /// Returns a handle to the fee history cache.
     #[inline]
     pub const fn fee_history_cache(&self) -> &FeeHistoryCache {
@@ -297,66 +582,59 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
 
     /// Returns the starting block.
     #[inline]
-    pub const fn starting_block(&self) -> U256 {
-        self.starting_block
+    pub fn starting_block(&self) -> U256 {
+        U256::from(self.starting_block.load(Ordering::Relaxed))
+    }
+
+    /// Returns the block number the current sync has progressed to.
+    #[inline]
+    pub fn current_block(&self) -> U256 {
+        U256::from(self.current_block.load(Ordering::Relaxed))
+    }
+
+    /// Returns the block number the current sync is targeting.
+    #[inline]
+    pub fn highest_block(&self) -> U256 {
+        U256::from(self.highest_block.load(Ordering::Relaxed))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use futures::FutureExt;
     use jsonrpsee_types::error::INVALID_PARAMS_CODE;
     use reth_chainspec::BaseFeeParams;
     use reth_evm_ethereum::EthEvmConfig;
     use reth_network_api::noop::NoopNetwork;
     use reth_primitives::{
-        constants::ETHEREUM_BLOCK_GAS_LIMIT, Block, BlockNumberOrTag, Header, TransactionSigned,
-        B256, U64,
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, Block, BlockNumberOrTag, Header, Receipt,
+        SealedBlockWithSenders, TransactionSigned, TxType, B256, U64,
     };
     use reth_provider::{
         test_utils::{MockEthProvider, NoopProvider},
-        BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+        ChainSpecProvider, ExecutionOutcome,
     };
-    use reth_rpc_eth_api::EthApiServer;
-    use reth_rpc_eth_types::{
-        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    use reth_rpc_eth_api::{
+        helpers::{EthApiSpec, LoadPendingBlock, SpawnBlocking},
+        EthApiServer,
     };
-    use reth_rpc_types::FeeHistory;
-    use reth_tasks::pool::BlockingTaskPool;
+    use reth_rpc_eth_types::{EthApiError, EthResult, GasPriceOracleConfig, PendingBlock};
+    use reth_rpc_types::{error::EthRpcErrorCode, FeeHistory};
     use reth_testing_utils::{generators, generators::Rng};
-    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
-
-    use crate::EthApi;
-
-    fn build_test_eth_api<
-        P: BlockReaderIdExt
-            + BlockReader
-            + ChainSpecProvider
-            + EvmEnvProvider
-            + StateProviderFactory
-            + Unpin
-            + Clone
-            + 'static,
-    >(
-        provider: P,
-    ) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig> {
-        let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
-        let fee_history_cache =
-            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
-
-        EthApi::new(
-            provider.clone(),
-            testing_pool(),
-            NoopNetwork::default(),
-            cache.clone(),
-            GasPriceOracle::new(provider, Default::default(), cache),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            fee_history_cache,
-            evm_config,
-            None,
-        )
-    }
+    use reth_transaction_pool::test_utils::TestPool;
+
+    use crate::{
+        eth::{test_utils::TestEthApiBuilder, DevSigner},
+        EthApi,
+    };
 
     // Function to prepare the EthApi with mock data
     fn prepare_eth_api(
@@ -437,7 +715,7 @@ mod tests {
             last_header.base_fee_per_gas.unwrap_or_default() as u128,
         ));
 
-        let eth_api = build_test_eth_api(mock_provider);
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
 
         (eth_api, base_fees_per_gas, gas_used_ratios)
     }
@@ -446,7 +724,7 @@ mod tests {
     #[tokio::test]
     async fn test_fee_history_empty() {
         let response = <EthApi<_, _, _, _> as EthApiServer>::fee_history(
-            &build_test_eth_api(NoopProvider::default()),
+            &TestEthApiBuilder::new(NoopProvider::default()).build(),
             U64::from(1),
             BlockNumberOrTag::Latest,
             None,
@@ -562,6 +840,185 @@ mod tests {
         );
     }
 
+    /// Block tags are resolved before the range is computed
+    #[tokio::test]
+    async fn test_fee_history_block_tags() {
+        let block_count = 10;
+        let newest_block = 9;
+
+        let (eth_api, base_fees_per_gas, _) =
+            prepare_eth_api(newest_block, None, block_count, MockEthProvider::default());
+
+        // `earliest` only covers the genesis block
+        let fee_history = eth_api
+            .fee_history(U64::from(block_count), BlockNumberOrTag::Earliest, None)
+            .await
+            .unwrap();
+        assert_eq!(fee_history.oldest_block, 0);
+        assert_eq!(fee_history.gas_used_ratio.len(), 1);
+
+        // the mock provider has no finalized or safe block
+        for tag in [BlockNumberOrTag::Finalized, BlockNumberOrTag::Safe] {
+            let err = <EthApi<_, _, _, _> as EthApiServer>::fee_history(
+                &eth_api,
+                U64::from(block_count),
+                tag,
+                None,
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(err.code(), EthRpcErrorCode::UnknownBlock.code());
+        }
+
+        // `pending` ends at the locally built pending block, which is empty
+        let fee_history = eth_api
+            .fee_history(U64::from(block_count), BlockNumberOrTag::Pending, None)
+            .await
+            .unwrap();
+        assert_eq!(fee_history.oldest_block, 1);
+        assert_eq!(fee_history.gas_used_ratio.len() as u64, block_count);
+        assert_eq!(
+            fee_history.base_fee_per_gas[..block_count as usize - 1],
+            base_fees_per_gas[1..block_count as usize]
+        );
+        assert_eq!(*fee_history.gas_used_ratio.last().unwrap(), 0.);
+    }
+
+    /// The locally built pending block is the newest entry of a `pending` fee history
+    #[tokio::test]
+    async fn test_fee_history_pending_block() {
+        let (eth_api, _, _) = prepare_eth_api(9, None, 10, MockEthProvider::default());
+
+        let transaction = |max_priority_fee_per_gas| TransactionSigned {
+            transaction: reth_primitives::Transaction::Eip1559(reth_primitives::TxEip1559 {
+                max_priority_fee_per_gas,
+                max_fee_per_gas: 100,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let receipt = |cumulative_gas_used| Receipt {
+            tx_type: TxType::Eip1559,
+            success: true,
+            cumulative_gas_used,
+            ..Default::default()
+        };
+
+        // the pool is empty, so a pending block with transactions is cached before it is built
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let number = pending.block_env.number.to::<u64>();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            gas_used: 42_000,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        };
+        let block = SealedBlockWithSenders {
+            block: Block {
+                header,
+                body: vec![transaction(2), transaction(1)],
+                ..Default::default()
+            }
+            .seal_slow(),
+            senders: vec![],
+        };
+        let receipts = vec![vec![Some(receipt(21_000)), Some(receipt(42_000))]];
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            block.clone(),
+            ExecutionOutcome::new(Default::default(), receipts.into(), number, vec![]),
+            Instant::now() + Duration::from_secs(1),
+        ));
+
+        let fee_history = eth_api
+            .fee_history(U64::from(1), BlockNumberOrTag::Pending, Some(vec![0.0, 100.0]))
+            .await
+            .unwrap();
+        assert_eq!(fee_history.oldest_block, number);
+        assert_eq!(
+            fee_history.base_fee_per_gas,
+            vec![7, block.header.next_block_base_fee(BaseFeeParams::ethereum()).unwrap() as u128]
+        );
+        assert_eq!(fee_history.gas_used_ratio, vec![42_000. / ETHEREUM_BLOCK_GAS_LIMIT as f64]);
+        assert_eq!(fee_history.reward, Some(vec![vec![1, 2]]));
+
+        // the pending block isn't persisted to the fee history cache
+        assert!(eth_api
+            .fee_history_cache()
+            .get_history(number, number, false)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    /// Requesting more blocks than configured should be rejected
+    #[tokio::test]
+    async fn test_fee_history_exceeds_max_blocks() {
+        let block_count = 10;
+        let newest_block = 1337;
+        let mock_provider = MockEthProvider::default();
+
+        let _ = prepare_eth_api(newest_block, None, block_count, mock_provider.clone());
+        let eth_api = TestEthApiBuilder::new(mock_provider)
+            .gpo_config(GasPriceOracleConfig {
+                max_fee_history_blocks: Some(5),
+                ..Default::default()
+            })
+            .build();
+
+        let response = <EthApi<_, _, _, _> as EthApiServer>::fee_history(
+            &eth_api,
+            U64::from(block_count),
+            newest_block.into(),
+            None,
+        )
+        .await;
+        let error_object = response.unwrap_err();
+        assert_eq!(error_object.code(), INVALID_PARAMS_CODE);
+
+        let fee_history =
+            eth_api.fee_history(U64::from(5), newest_block.into(), None).await.unwrap();
+        assert_eq!(fee_history.gas_used_ratio.len(), 5);
+    }
+
+    /// Requests reaching past genesis return the blocks from genesis onwards
+    #[tokio::test]
+    async fn test_fee_history_clamped_at_genesis() {
+        let newest_block = 4;
+
+        let (eth_api, base_fees_per_gas, gas_used_ratios) =
+            prepare_eth_api(newest_block, None, newest_block + 1, MockEthProvider::default());
+
+        let fee_history =
+            eth_api.fee_history(U64::from(100), newest_block.into(), None).await.unwrap();
+        assert_eq!(fee_history.oldest_block, 0);
+        assert_eq!(fee_history.gas_used_ratio, gas_used_ratios);
+        assert_eq!(fee_history.base_fee_per_gas, base_fees_per_gas);
+        assert_eq!(fee_history.base_fee_per_gas.len(), fee_history.gas_used_ratio.len() + 1);
+    }
+
+    /// Requests for more blocks than the header history are clamped to the newest blocks
+    #[tokio::test]
+    async fn test_fee_history_clamped_at_max_header_history() {
+        let block_count = 10;
+        let newest_block = 1337;
+        let mock_provider = MockEthProvider::default();
+
+        let (_, base_fees_per_gas, gas_used_ratios) =
+            prepare_eth_api(newest_block, None, block_count, mock_provider.clone());
+        let eth_api = TestEthApiBuilder::new(mock_provider)
+            .gpo_config(GasPriceOracleConfig { max_header_history: 4, ..Default::default() })
+            .build();
+
+        let fee_history =
+            eth_api.fee_history(U64::from(block_count), newest_block.into(), None).await.unwrap();
+        assert_eq!(fee_history.oldest_block, newest_block - 3);
+        assert_eq!(fee_history.gas_used_ratio, gas_used_ratios[6..]);
+        assert_eq!(fee_history.base_fee_per_gas, base_fees_per_gas[6..]);
+        assert_eq!(fee_history.base_fee_per_gas.len(), 5);
+    }
+
     /// Requesting all blocks should be ok
     #[tokio::test]
     async fn test_fee_history_all_blocks() {
@@ -598,4 +1055,147 @@ mod tests {
             "all: no percentiles were requested, so there should be no rewards result"
         );
     }
+
+    #[tokio::test]
+    async fn test_fee_history_omits_rewards_without_percentiles() {
+        let mock_provider = MockEthProvider::default();
+        for number in 0..=3 {
+            let header = Header {
+                number,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(7),
+                ..Default::default()
+            };
+            let hash = header.hash_slow();
+            mock_provider.add_header(hash, header.clone());
+            mock_provider.add_block(hash, Block { header, ..Default::default() });
+        }
+        let eth_api = TestEthApiBuilder::new(mock_provider.clone()).build();
+        eth_api.fee_history_cache().backfill(&mock_provider, 3).await.unwrap();
+
+        for percentiles in [None, Some(vec![])] {
+            let fee_history =
+                eth_api.fee_history(U64::from(3), 3.into(), percentiles).await.unwrap();
+            assert!(fee_history.reward.is_none());
+            assert!(serde_json::to_value(&fee_history).unwrap().get("reward").is_none());
+        }
+        // the rewards of the cached blocks weren't computed
+        let entries = eth_api.fee_history_cache().get_history(1, 3, false).await.unwrap().unwrap();
+        assert!(entries.iter().all(|entry| entry.has_pending_rewards()));
+
+        // empty blocks have a zero row if percentiles are requested
+        let fee_history =
+            eth_api.fee_history(U64::from(3), 3.into(), Some(vec![50.])).await.unwrap();
+        assert_eq!(fee_history.reward, Some(vec![vec![0]; 3]));
+    }
+
+    #[tokio::test]
+    async fn blocking_task_panic_is_reported() {
+        let eth_api = TestEthApiBuilder::new(NoopProvider::default()).build();
+
+        let err = eth_api
+            .spawn_blocking_io(|_| -> EthResult<()> { panic!("io task failed") })
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, EthApiError::BlockingTaskPanicked(ref msg) if msg == "io task failed")
+        );
+
+        let block = 42;
+        let err = eth_api
+            .spawn_tracing(move |_| -> EthResult<()> { panic!("tracing block {block} failed") })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "blocking task panicked: tracing block 42 failed");
+    }
+
+    #[tokio::test]
+    async fn test_with_signers() {
+        let signers = DevSigner::random_signers(2);
+        let expected = signers.iter().flat_map(|signer| signer.accounts()).collect::<Vec<_>>();
+        assert_eq!(expected.len(), 2);
+
+        let eth_api = TestEthApiBuilder::new(NoopProvider::default()).build().with_signers(signers);
+        assert_eq!(EthApiSpec::accounts(&eth_api), expected);
+    }
+
+    #[tokio::test]
+    async fn genesis_mismatch_is_detected() {
+        let mock_provider = MockEthProvider::default();
+        let eth_api = TestEthApiBuilder::new(mock_provider.clone()).build();
+        // an empty database has nothing to compare against
+        assert!(eth_api.check_genesis().is_ok());
+
+        // the genesis of a different chain
+        let stored = B256::random();
+        mock_provider.add_block(stored, Block::default());
+        let err = eth_api.check_genesis().unwrap_err();
+        let expected = mock_provider.chain_spec().genesis_hash();
+        assert!(matches!(
+            err,
+            EthApiError::GenesisHashMismatch { chain_id: 1, expected: e, stored: s }
+                if e == expected && s == stored
+        ));
+    }
+
+    #[tokio::test]
+    async fn matching_genesis_passes() {
+        let mock_provider = MockEthProvider::default();
+        let genesis_hash = mock_provider.chain_spec().genesis_hash();
+        mock_provider.add_block(genesis_hash, Block::default());
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+        assert!(eth_api.check_genesis().is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocking_tasks_are_limited() {
+        let eth_api = TestEthApiBuilder::new(NoopProvider::default()).build();
+        eth_api.set_max_blocking_tasks(1);
+
+        let running = Arc::new(AtomicU64::new(0));
+        let max_running = Arc::new(AtomicU64::new(0));
+        let task = |running: Arc<AtomicU64>, max_running: Arc<AtomicU64>| {
+            move |_| -> EthResult<()> {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            }
+        };
+
+        let io = (0..4)
+            .map(|_| eth_api.spawn_blocking_io(task(running.clone(), max_running.clone())).boxed());
+        let tracing = (0..4)
+            .map(|_| eth_api.spawn_tracing(task(running.clone(), max_running.clone())).boxed());
+        for res in futures::future::join_all(io.chain(tracing)).await {
+            res.unwrap();
+        }
+
+        // all tasks ran one after another
+        assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocking_task_timeout() {
+        let eth_api = TestEthApiBuilder::new(NoopProvider::default()).build();
+        let slow_task = |_| -> EthResult<()> {
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        };
+
+        let timeout = Duration::from_millis(20);
+        eth_api.set_blocking_task_timeout(Some(timeout));
+        // tasks that complete in time aren't affected
+        assert!(eth_api.spawn_tracing(|_| Ok(())).await.is_ok());
+
+        let err = eth_api.spawn_blocking_io(slow_task).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(t) if t == timeout));
+        let err = eth_api.spawn_tracing(slow_task).await.unwrap_err();
+        assert!(matches!(err, EthApiError::ExecutionTimedOut(t) if t == timeout));
+
+        eth_api.set_blocking_task_timeout(None);
+        assert!(eth_api.spawn_blocking_io(slow_task).await.is_ok());
+    }
 }
@@ -10,13 +10,14 @@ use std::{
 };
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
 use reth_chainspec::ChainInfo;
 use reth_primitives::{IntoRecoveredTransaction, TxHash};
 use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
 use reth_rpc_eth_api::EthFilterApiServer;
 use reth_rpc_eth_types::{
-    logs_utils::{self, append_matching_block_logs},
+    logs_utils::{self, BlockLogsAppender},
     EthApiError, EthFilterError, EthStateCache, EthSubscriptionIdProvider,
 };
 use reth_rpc_server_types::ToRpcResult;
@@ -61,8 +62,12 @@ where
         config: EthFilterConfig,
         task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
-        let EthFilterConfig { max_blocks_per_filter, max_logs_per_response, stale_filter_ttl } =
-            config;
+        let EthFilterConfig {
+            max_blocks_per_filter,
+            max_logs_per_response,
+            max_logs_query_duration,
+            stale_filter_ttl,
+        } = config;
         let inner = EthFilterInner {
             provider,
             active_filters: Default::default(),
@@ -75,6 +80,7 @@ where
             // if not set, use the max value, which is effectively no limit
             max_blocks_per_filter: max_blocks_per_filter.unwrap_or(u64::MAX),
             max_logs_per_response: max_logs_per_response.unwrap_or(usize::MAX),
+            max_logs_query_duration,
         };
 
         let eth_filter = Self { inner: Arc::new(inner) };
@@ -328,6 +334,8 @@ struct EthFilterInner<Provider, Pool> {
     max_blocks_per_filter: u64,
     /// Maximum number of logs that can be returned in a response
     max_logs_per_response: usize,
+    /// Maximum time a range query for logs may take
+    max_logs_query_duration: Option<Duration>,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// maximum number of headers to read at once for range filter
@@ -419,6 +427,10 @@ where
     /// Returns an error if:
     ///  - underlying database error
     ///  - amount of matches exceeds configured limit
+    ///  - the query takes longer than the configured time budget
+    ///
+    /// The limits are only enforced for ranges of multiple blocks, and the errors include the range
+    /// of blocks whose logs were collected completely, so the query can be resumed from there.
     async fn get_logs_in_block_range(
         &self,
         filter: &Filter,
@@ -437,8 +449,12 @@ where
             return Err(EthFilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
         }
 
+        let started = Instant::now();
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
+        // the limits are only enforced if the range has multiple blocks, so we always return all
+        // logs of a single block
+        let is_multi_block_range = from_block != to_block;
 
         if (to_block == best_number) && (from_block == best_number) {
             // only one block to check and it's the current best block which we can fetch directly
@@ -470,12 +486,15 @@ where
         for (from, to) in
             BlockRangeInclusiveIter::new(from_block..=to_block, self.max_headers_range)
         {
+            if is_multi_block_range {
+                self.ensure_time_budget(started, from_block, from)?;
+            }
             let headers = self.provider.headers_range(from..=to)?;
 
+            let mut matching_blocks = Vec::new();
             for (idx, header) in headers.iter().enumerate() {
                 // only if filter matches
-                if FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
-                    FilteredParams::matches_topics(header.logs_bloom, &topics_filter)
+                if logs_utils::log_matches_bloom(header.logs_bloom, &address_filter, &topics_filter)
                 {
                     // these are consecutive headers, so we can use the parent hash of the next
                     // block to get the current header's hash
@@ -486,33 +505,91 @@ where
                             .block_hash(header.number)?
                             .ok_or(ProviderError::HeaderNotFound(header.number.into()))?,
                     };
+                    matching_blocks
+                        .push((BlockNumHash::new(header.number, block_hash), header.timestamp));
+                }
+            }
 
-                    if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await? {
-                        append_matching_block_logs(
-                            &mut all_logs,
-                            &self.provider,
-                            &filter_params,
-                            BlockNumHash::new(header.number, block_hash),
-                            &receipts,
-                            false,
-                            header.timestamp,
-                        )?;
-
-                        // size check but only if range is multiple blocks, so we always return all
-                        // logs of a single block
-                        let is_multi_block_range = from_block != to_block;
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(EthFilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
-                        }
+            // stream the receipts of the matching blocks, so a query that exceeds the max results
+            // stops without loading the receipts of the remaining blocks
+            let block_hashes = matching_blocks.iter().map(|(block, _)| block.hash).collect();
+            let mut receipts = std::pin::pin!(self.eth_cache.receipts_stream(block_hashes));
+            let mut matching_blocks = matching_blocks.into_iter();
+            let mut appender: Option<BlockLogsAppender<'_, _>> = None;
+
+            while let Some(receipt) = receipts.next().await {
+                let receipt = receipt?;
+                if appender
+                    .as_ref()
+                    .is_some_and(|appender| appender.block_num_hash().hash != receipt.block_hash)
+                {
+                    appender = None;
+                }
+                if appender.is_none() {
+                    // blocks without receipts are skipped by the stream, the others are streamed in
+                    // block order
+                    let Some((block, timestamp)) =
+                        matching_blocks.find(|(block, _)| block.hash == receipt.block_hash)
+                    else {
+                        return Err(EthFilterError::InternalError)
+                    };
+                    if is_multi_block_range {
+                        self.ensure_time_budget(started, from_block, block.number)?;
                     }
+                    appender = Some(BlockLogsAppender::new(
+                        &self.provider,
+                        &filter_params,
+                        block,
+                        false,
+                        timestamp,
+                    ));
+                }
+                let appender = appender.as_mut().expect("appender is set");
+                appender.append_receipt_logs(&mut all_logs, receipt.index, receipt.receipt())?;
+
+                if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
+                    return Err(EthFilterError::QueryExceedsMaxResults {
+                        max_logs: self.max_logs_per_response,
+                        from_block,
+                        to_block: last_completed_block(
+                            from_block,
+                            appender.block_num_hash().number,
+                        ),
+                    })
                 }
             }
         }
 
         Ok(all_logs)
     }
+
+    /// Returns an error if a query that started at the given instant exceeded the configured time
+    /// budget.
+    ///
+    /// `next_block` is the first block whose logs were not collected completely.
+    fn ensure_time_budget(
+        &self,
+        started: Instant,
+        from_block: u64,
+        next_block: u64,
+    ) -> Result<(), EthFilterError> {
+        match self.max_logs_query_duration {
+            Some(budget) if started.elapsed() > budget => {
+                Err(EthFilterError::QueryExceedsTimeBudget {
+                    budget,
+                    from_block,
+                    to_block: last_completed_block(from_block, next_block),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the last block before `next_block` whose logs were collected completely by a query
+/// that started at `from_block`, or `None` if no block was completed.
+fn last_completed_block(from_block: u64, next_block: u64) -> Option<u64> {
+    (next_block > from_block).then(|| next_block - 1)
 }
 
 /// Config for the filter
@@ -526,6 +603,10 @@ pub struct EthFilterConfig {
     ///
     /// If `None` then no limit is enforced.
     pub max_logs_per_response: Option<usize>,
+    /// Maximum time an `eth_getLogs` call for a range of blocks may take.
+    ///
+    /// If `None` then no limit is enforced.
+    pub max_logs_query_duration: Option<Duration>,
     /// How long a filter remains valid after the last poll.
     ///
     /// A filter is considered stale if it has not been polled for longer than this duration and
@@ -547,6 +628,12 @@ impl EthFilterConfig {
         self
     }
 
+    /// Sets the maximum time an `eth_getLogs` call for a range of blocks may take.
+    pub const fn max_logs_query_duration(mut self, duration: Duration) -> Self {
+        self.max_logs_query_duration = Some(duration);
+        self
+    }
+
     /// Sets how long a filter remains valid after the last poll before it will be removed.
     pub const fn stale_filter_ttl(mut self, duration: Duration) -> Self {
         self.stale_filter_ttl = duration;
@@ -559,6 +646,7 @@ impl Default for EthFilterConfig {
         Self {
             max_blocks_per_filter: None,
             max_logs_per_response: None,
+            max_logs_query_duration: None,
             // 5min
             stale_filter_ttl: Duration::from_secs(5 * 60),
         }
@@ -710,6 +798,101 @@ impl Iterator for BlockRangeInclusiveIter {
 mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Block, Header, Receipt, TransactionSigned, B256};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    #[tokio::test]
+    async fn logs_rejects_range_above_max_blocks() {
+        let provider = MockEthProvider::default();
+        for number in 0..=10 {
+            provider.add_header(B256::random(), Header { number, ..Default::default() });
+        }
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        let eth_filter = EthFilter::new(
+            provider,
+            testing_pool(),
+            cache,
+            EthFilterConfig::default().max_blocks_per_filter(5),
+            Box::<TokioTaskExecutor>::default(),
+        );
+
+        let filter = Filter::new().from_block(0).to_block(10);
+        let err = eth_filter.inner.logs_for_filter(filter).await.unwrap_err();
+        assert!(matches!(err, EthFilterError::QueryExceedsMaxBlocks(5)));
+    }
+
+    /// Returns a provider with a chain of blocks `0..=4` that have a single transaction with a
+    /// log each.
+    fn provider_with_logs() -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..=4 {
+            let block = Block {
+                header: Header { number, parent_hash, ..Default::default() },
+                body: vec![TransactionSigned::default()],
+                ..Default::default()
+            };
+            let hash = block.header.hash_slow();
+            provider.add_block(hash, block);
+            let receipt = Receipt { logs: vec![Default::default()], ..Default::default() };
+            provider.add_receipts(hash, vec![receipt]);
+            parent_hash = hash;
+        }
+        provider
+    }
+
+    fn eth_filter(
+        provider: MockEthProvider,
+        config: EthFilterConfig,
+    ) -> EthFilter<MockEthProvider, TestPool> {
+        let cache =
+            EthStateCache::spawn(provider.clone(), Default::default(), EthEvmConfig::default());
+        EthFilter::new(provider, testing_pool(), cache, config, Box::<TokioTaskExecutor>::default())
+    }
+
+    #[tokio::test]
+    async fn logs_exceeding_max_results_are_resumable() {
+        let eth_filter =
+            eth_filter(provider_with_logs(), EthFilterConfig::default().max_logs_per_response(2));
+
+        let filter = Filter::new().from_block(0).to_block(4);
+        let err = eth_filter.inner.logs_for_filter(filter).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EthFilterError::QueryExceedsMaxResults {
+                max_logs: 2,
+                from_block: 0,
+                to_block: Some(1)
+            }
+        ));
+        assert_eq!(err.to_string(), "query exceeds max results 2, retry with the range 0-1");
+
+        // the logs of the suggested range fit into a response
+        let filter = Filter::new().from_block(0).to_block(1);
+        assert_eq!(eth_filter.inner.logs_for_filter(filter).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn logs_exceeding_time_budget_are_resumable() {
+        let config = EthFilterConfig::default().max_logs_query_duration(Duration::ZERO);
+        let eth_filter = eth_filter(provider_with_logs(), config);
+
+        let filter = Filter::new().from_block(2).to_block(4);
+        let err = eth_filter.inner.logs_for_filter(filter).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EthFilterError::QueryExceedsTimeBudget { from_block: 2, to_block: None, .. }
+        ));
+        assert_eq!(err.to_string(), "query exceeds time budget 0ns");
+
+        // the logs of a single block are always returned
+        let filter = Filter::new().from_block(2).to_block(2);
+        assert_eq!(eth_filter.inner.logs_for_filter(filter).await.unwrap().len(), 1);
+    }
 
     #[test]
     fn test_block_range_iter() {
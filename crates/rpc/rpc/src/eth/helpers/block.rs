@@ -32,3 +32,119 @@ where
         self.inner.cache()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use reth_primitives::{Block, BlockNumberOrTag, Header, SealedBlockWithSenders, B256, U256};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_eth_api::EthApiServer;
+    use reth_rpc_eth_types::PendingBlock;
+    use reth_testing_utils::generators::{self, random_signed_tx};
+
+    use super::*;
+    use crate::eth::test_utils::TestEthApiBuilder;
+
+    /// The block at which the merge activated on mainnet.
+    const PARIS_BLOCK: u64 = 15_537_394;
+
+    fn add_block(provider: &MockEthProvider, number: u64, ommers: Vec<Header>) -> B256 {
+        let header = Header { number, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.add_header(hash, header.clone());
+        provider.add_block(hash, Block { header, ommers, ..Default::default() });
+        hash
+    }
+
+    #[tokio::test]
+    async fn uncles_of_pre_merge_block() {
+        let provider = MockEthProvider::default();
+        let ommer = Header { number: 99, gas_limit: 1, ..Default::default() };
+        let hash = add_block(&provider, 100, vec![ommer.clone()]);
+        let eth_api = TestEthApiBuilder::new(provider).build();
+
+        let count = eth_api.block_uncles_count_by_hash(hash).await.unwrap();
+        assert_eq!(count, Some(U256::from(1)));
+        let count = eth_api.block_uncles_count_by_number(BlockNumberOrTag::Number(100)).await;
+        assert_eq!(count.unwrap(), Some(U256::from(1)));
+
+        let uncle = eth_api.uncle_by_block_hash_and_index(hash, 0.into()).await.unwrap().unwrap();
+        assert_eq!(uncle.header.hash, Some(ommer.hash_slow()));
+        assert!(eth_api.uncle_by_block_hash_and_index(hash, 1.into()).await.unwrap().is_none());
+
+        let block = eth_api.block_by_hash(hash, false).await.unwrap().unwrap();
+        assert_eq!(block.uncles, vec![ommer.hash_slow()]);
+    }
+
+    #[tokio::test]
+    async fn no_uncles_after_merge() {
+        let provider = MockEthProvider::default();
+        let hash = add_block(&provider, PARIS_BLOCK + 1, vec![]);
+        let eth_api = TestEthApiBuilder::new(provider).build();
+
+        let count = eth_api.block_uncles_count_by_hash(hash).await.unwrap();
+        assert_eq!(count, Some(U256::ZERO));
+        let number = BlockNumberOrTag::Number(PARIS_BLOCK + 1);
+        assert_eq!(eth_api.block_uncles_count_by_number(number).await.unwrap(), Some(U256::ZERO));
+
+        assert!(eth_api.uncle_by_block_hash_and_index(hash, 0.into()).await.unwrap().is_none());
+        let uncle = eth_api.uncle_by_block_number_and_index(number, 0.into()).await.unwrap();
+        assert!(uncle.is_none());
+
+        let block = eth_api.block_by_hash(hash, false).await.unwrap().unwrap();
+        assert!(block.uncles.is_empty());
+
+        // unknown blocks have no uncle count
+        assert_eq!(eth_api.block_uncles_count_by_hash(B256::random()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn block_transaction_counts() {
+        let provider = MockEthProvider::default();
+        let mut rng = generators::rng();
+        let header = Header { number: 1, ..Default::default() };
+        let hash = header.hash_slow();
+        let body = vec![random_signed_tx(&mut rng), random_signed_tx(&mut rng)];
+        provider.add_header(hash, header.clone());
+        provider.add_block(hash, Block { header, body, ..Default::default() });
+        let eth_api = TestEthApiBuilder::new(provider).build();
+
+        let count = eth_api.block_transaction_count_by_hash(hash).await.unwrap();
+        assert_eq!(count, Some(U256::from(2)));
+        let number = BlockNumberOrTag::Number(1);
+        let count = eth_api.block_transaction_count_by_number(number).await.unwrap();
+        assert_eq!(count, Some(U256::from(2)));
+
+        // the pending count is served by the locally built pending block
+        let transactions = vec![
+            random_signed_tx(&mut rng),
+            random_signed_tx(&mut rng),
+            random_signed_tx(&mut rng),
+        ];
+        let senders = transactions.iter().map(|tx| tx.recover_signer().unwrap()).collect();
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number: pending.block_env.number.to(),
+            ..Default::default()
+        };
+        let block = SealedBlockWithSenders {
+            block: Block { header, body: transactions, ..Default::default() }.seal_slow(),
+            senders,
+        };
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            block,
+            Default::default(),
+            Instant::now() + Duration::from_secs(1),
+        ));
+        let count = eth_api.block_transaction_count_by_number(BlockNumberOrTag::Pending).await;
+        assert_eq!(count.unwrap(), Some(U256::from(3)));
+
+        // unknown blocks have no transaction count
+        let count = eth_api.block_transaction_count_by_hash(B256::random()).await.unwrap();
+        assert_eq!(count, None);
+        let count = eth_api.block_transaction_count_by_number(BlockNumberOrTag::Number(2)).await;
+        assert_eq!(count.unwrap(), None);
+    }
+}
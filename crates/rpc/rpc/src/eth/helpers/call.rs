@@ -2,12 +2,23 @@
 
 use reth_evm::ConfigureEvm;
 use reth_rpc_eth_api::helpers::{Call, EthCall, LoadPendingBlock, LoadState, SpawnBlocking};
+use reth_rpc_eth_types::EthCallCache;
 
 use crate::EthApi;
 
-impl<Provider, Pool, Network, EvmConfig> EthCall for EthApi<Provider, Pool, Network, EvmConfig> where
-    Self: Call + LoadPendingBlock
+impl<Provider, Pool, Network, EvmConfig> EthCall for EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Self: Call + LoadPendingBlock,
 {
+    #[inline]
+    fn max_simulate_blocks(&self) -> u64 {
+        self.inner.max_simulate_blocks()
+    }
+
+    #[inline]
+    fn call_cache(&self) -> Option<&parking_lot::Mutex<EthCallCache>> {
+        Some(self.inner.call_cache())
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> Call for EthApi<Provider, Pool, Network, EvmConfig>
@@ -17,7 +28,17 @@ where
 {
     #[inline]
     fn call_gas_limit(&self) -> u64 {
-        self.inner.gas_cap()
+        self.inner.gas_caps().call
+    }
+
+    #[inline]
+    fn estimate_gas_limit(&self) -> u64 {
+        self.inner.gas_caps().estimate
+    }
+
+    #[inline]
+    fn trace_gas_limit(&self) -> u64 {
+        self.inner.gas_caps().trace
     }
 
     #[inline]
@@ -25,3 +46,562 @@ where
         self.inner.evm_config()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        bytes, constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex, revm_primitives::SpecId,
+        AccessList, AccessListItem, Address, Block, BlockId, Bytes, Header, TxKind, B256, U256,
+        U64,
+    };
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        ChainSpecProvider,
+    };
+    use reth_rpc_eth_types::{
+        fee_history::next_block_base_fee, EthApiError, GasCaps, GasEstimateBound,
+        RpcInvalidTransactionError,
+    };
+    use reth_rpc_server_types::constants::DEFAULT_MAX_SIMULATE_BLOCKS;
+    use reth_rpc_types::{
+        state::{AccountOverride, EvmOverrides},
+        trace::{
+            geth::{
+                GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingCallOptions,
+                GethDebugTracingOptions, PreStateFrame, PreStateMode,
+            },
+            tracerequest::TraceCallRequest,
+        },
+        BlockOverrides, SimBlock, SimulatePayload, TransactionRequest,
+    };
+    use reth_tasks::pool::BlockingTaskGuard;
+    use reth_transaction_pool::test_utils::TestPool;
+
+    use super::*;
+    use crate::{eth::test_utils::TestEthApiBuilder, DebugApi, TraceApi};
+
+    /// Builds an API over a single block and the given contract.
+    fn eth_api_with_contract(
+        contract: Address,
+        code: Bytes,
+        gas_cap: impl Into<GasCaps>,
+    ) -> EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig> {
+        let provider = MockEthProvider::default();
+        provider.add_header(B256::random(), Header::default());
+        provider.add_account(contract, ExtendedAccount::new(0, U256::ZERO).with_bytecode(code));
+
+        TestEthApiBuilder::new(provider).gas_cap(gas_cap).build()
+    }
+
+    fn slot(slot: u64) -> B256 {
+        U256::from(slot).into()
+    }
+
+    fn transfer(from: Address, to: Address, value: u64) -> TransactionRequest {
+        TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(to)),
+            value: Some(U256::from(value)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn create_access_list_reads_storage() {
+        let contract = Address::random();
+        // reads storage slots `1` and `2` and the balance of the identity precompile
+        let code = bytes!("600154506002545060043150");
+        let eth_api = eth_api_with_contract(contract, code, 1_000_000);
+
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            // a precompile in the request is removed from the list
+            access_list: Some(AccessList(vec![AccessListItem {
+                address: Address::with_last_byte(4),
+                storage_keys: Vec::new(),
+            }])),
+            ..Default::default()
+        };
+        let result = eth_api.create_access_list_at(request, None).await.unwrap();
+
+        assert_eq!(
+            result.access_list,
+            AccessList(vec![AccessListItem {
+                address: contract,
+                storage_keys: vec![slot(1), slot(2)]
+            }])
+        );
+        assert!(result.gas_used > U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn create_access_list_reports_reverted_state() {
+        let contract = Address::random();
+        // reads storage slot `1` and reverts
+        let code = bytes!("6001545060006000fd");
+        let eth_api = eth_api_with_contract(contract, code, 1_000_000);
+
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            ..Default::default()
+        };
+        let result = eth_api.create_access_list_at(request, None).await.unwrap();
+
+        assert_eq!(
+            result.access_list,
+            AccessList(vec![AccessListItem { address: contract, storage_keys: vec![slot(1)] }])
+        );
+        assert!(result.gas_used > U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn create_access_list_respects_gas_cap() {
+        let contract = Address::random();
+        // reads storage slots `1` and `2` and the balance of the identity precompile
+        let code = bytes!("600154506002545060043150");
+        let eth_api = eth_api_with_contract(contract, code, 22_000);
+
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            ..Default::default()
+        };
+        let err = eth_api.create_access_list_at(request, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidTransaction(_)));
+    }
+
+    #[tokio::test]
+    async fn call_respects_method_gas_caps() {
+        let contract = Address::random();
+        // reads storage slots `1` and `2` and the balance of the identity precompile
+        let code = bytes!("600154506002545060043150");
+        let eth_api = eth_api_with_contract(
+            contract,
+            code,
+            GasCaps { call: 100_000, estimate: 100_000, trace: 1_000_000 },
+        );
+
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            gas: Some(500_000),
+            ..Default::default()
+        };
+
+        // above the call cap
+        let err = eth_api
+            .transact_call_at(request.clone(), BlockId::latest(), EvmOverrides::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::GasCapExceeded { cap: 100_000, requested: 500_000 }));
+
+        // above the estimate cap
+        let err = EthCall::estimate_gas_at(&eth_api, request.clone(), BlockId::latest(), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::GasCapExceeded { cap: 100_000, requested: 500_000 }));
+
+        // below the trace cap
+        let trace_api =
+            TraceApi::new(eth_api.provider().clone(), eth_api.clone(), BlockingTaskGuard::new(1));
+        let traces =
+            trace_api.trace_call(TraceCallRequest::new(request).with_trace()).await.unwrap();
+        assert!(traces.trace.first().is_some_and(|trace| trace.error.is_none()));
+    }
+
+    #[tokio::test]
+    async fn call_reports_halt_reason() {
+        let call = |code| async move {
+            let contract = Address::random();
+            let eth_api = eth_api_with_contract(contract, code, 1_000_000);
+            let request = TransactionRequest {
+                from: Some(Address::random()),
+                to: Some(TxKind::Call(contract)),
+                ..Default::default()
+            };
+            EthCall::call(&eth_api, request, Some(BlockId::latest()), EvmOverrides::default())
+                .await
+                .unwrap_err()
+        };
+
+        // recursively jumps to the start, pushing one item per iteration
+        let err = call(bytes!("5b6000600056")).await;
+        assert!(matches!(
+            err,
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::StackOverflow)
+        ));
+        assert_eq!(err.to_string(), "stack limit reached");
+
+        // jumps into the push data
+        let err = call(bytes!("6001600056")).await;
+        assert!(matches!(
+            err,
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::InvalidJump)
+        ));
+    }
+
+    #[tokio::test]
+    async fn debug_trace_call_builtin_tracers() {
+        let inner = Address::random();
+        let outer = Address::random();
+        // calls `inner` with all remaining gas
+        let code =
+            [&hex!("6000600060006000600073")[..], inner.as_slice(), &hex!("5af100")].concat();
+        let eth_api = eth_api_with_contract(
+            outer,
+            code.into(),
+            GasCaps { call: 100_000, estimate: 100_000, trace: 1_000_000 },
+        );
+        eth_api
+            .provider()
+            .add_account(inner, ExtendedAccount::new(0, U256::ZERO).with_bytecode(bytes!("00")));
+        let debug_api =
+            DebugApi::new(eth_api.provider().clone(), eth_api.clone(), BlockingTaskGuard::new(1));
+
+        let from = Address::random();
+        let request = TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Call(outer)),
+            ..Default::default()
+        };
+        let opts = |tracer| GethDebugTracingCallOptions {
+            tracing_options: GethDebugTracingOptions::default()
+                .with_tracer(GethDebugTracerType::BuiltInTracer(tracer)),
+            ..Default::default()
+        };
+
+        let frame = debug_api
+            .debug_trace_call(request.clone(), None, opts(GethDebugBuiltInTracerType::CallTracer))
+            .await
+            .unwrap()
+            .try_into_call_frame()
+            .unwrap();
+        assert_eq!((frame.from, frame.to, frame.typ.as_str()), (from, Some(outer), "CALL"));
+        // the call is limited by the trace cap rather than the call cap
+        assert_eq!(frame.gas, U256::from(1_000_000));
+        assert!(frame.error.is_none());
+        assert_eq!(frame.calls.len(), 1);
+        assert_eq!((frame.calls[0].from, frame.calls[0].to), (outer, Some(inner)));
+        assert_eq!(frame.calls[0].typ, "CALL");
+
+        let frame = debug_api
+            .debug_trace_call(request, None, opts(GethDebugBuiltInTracerType::PreStateTracer))
+            .await
+            .unwrap()
+            .try_into_pre_state_frame()
+            .unwrap();
+        let PreStateFrame::Default(PreStateMode(accounts)) = frame else {
+            panic!("expected the default prestate mode")
+        };
+        assert!([from, outer, inner].iter().all(|address| accounts.contains_key(address)));
+    }
+
+    #[tokio::test]
+    async fn call_outputs_are_cached_for_historical_blocks() {
+        let contract = Address::random();
+        // returns storage slot `0`
+        let code = bytes!("6000546000526020" "6000f3");
+        let eth_api = eth_api_with_contract(contract, code.clone(), 30_000_000);
+        eth_api.set_call_cache_size(8);
+
+        let block = Header { number: 1, ..Default::default() };
+        let block_hash = block.hash_slow();
+        eth_api.provider().add_block(block_hash, Block { header: block, ..Default::default() });
+        let set_slot = |value: u64| {
+            let account = ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(code.clone())
+                .extend_storage([(B256::ZERO, U256::from(value))]);
+            eth_api.provider().add_account(contract, account);
+        };
+        let request = TransactionRequest { to: Some(TxKind::Call(contract)), ..Default::default() };
+        let word = |value: u64| Bytes::from(B256::from(U256::from(value)));
+        let call = |at: BlockId| {
+            EthCall::call(&eth_api, request.clone(), Some(at), EvmOverrides::default())
+        };
+
+        set_slot(1);
+        assert_eq!(call(block_hash.into()).await.unwrap(), word(1));
+        assert_eq!(eth_api.inner.call_cache().lock().len(), 1);
+
+        // the mock serves the same state for every block, so only the cache returns the old value
+        set_slot(2);
+        assert_eq!(call(block_hash.into()).await.unwrap(), word(1));
+        assert_eq!(call(BlockId::latest()).await.unwrap(), word(2));
+        assert_eq!(eth_api.inner.call_cache().lock().len(), 1);
+
+        // disabling the cache executes every call
+        eth_api.set_call_cache_size(0);
+        assert_eq!(call(block_hash.into()).await.unwrap(), word(2));
+        assert!(eth_api.inner.call_cache().lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn call_with_spec_id_override() {
+        let contract = Address::random();
+        // returns the blob base fee, the `BLOBBASEFEE` opcode was introduced in Cancun
+        let code = bytes!("4a6000526020" "6000f3");
+        let eth_api = eth_api_with_contract(contract, code, 30_000_000);
+        let request = TransactionRequest { to: Some(TxKind::Call(contract)), ..Default::default() };
+
+        let output = eth_api
+            .call_with_spec_id(request.clone(), None, EvmOverrides::default(), SpecId::CANCUN)
+            .await
+            .unwrap();
+        assert_eq!(output, Bytes::from(B256::from(U256::from(1))));
+
+        let err = eth_api
+            .call_with_spec_id(request.clone(), None, EvmOverrides::default(), SpecId::SHANGHAI)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidTransaction(_)), "{err:?}");
+
+        // blob transactions can't be executed before Cancun
+        let request =
+            TransactionRequest { blob_versioned_hashes: Some(vec![B256::ZERO]), ..request };
+        let err = eth_api
+            .call_with_spec_id(request, None, EvmOverrides::default(), SpecId::SHANGHAI)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_reports_gas_cap() {
+        let contract = Address::random();
+        // loops until it runs out of gas
+        let code = bytes!("5b600056");
+        let eth_api = eth_api_with_contract(contract, code, 100_000);
+
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            ..Default::default()
+        };
+        let (cfg, mut block_env, _) = eth_api.evm_env_at(BlockId::latest()).await.unwrap();
+
+        // the block would allow for more gas than the cap
+        block_env.gas_limit = U256::from(30_000_000);
+        let state = eth_api.latest_state().unwrap();
+        let err = eth_api
+            .estimate_gas_detailed_with(
+                cfg.clone(),
+                block_env.clone(),
+                request.clone(),
+                state,
+                None,
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::GasCapReached(100_000))
+        ));
+
+        // the block gas limit is below the cap
+        block_env.gas_limit = U256::from(50_000);
+        let state = eth_api.latest_state().unwrap();
+        let err =
+            eth_api.estimate_gas_detailed_with(cfg, block_env, request, state, None).unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::BasicOutOfGas(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_below_cap() {
+        let contract = Address::random();
+        // reads storage slots `1` and `2` and the balance of the identity precompile
+        let code = bytes!("600154506002545060043150");
+        let eth_api = eth_api_with_contract(contract, code, 1_000_000);
+
+        let request = TransactionRequest {
+            from: Some(Address::random()),
+            to: Some(TxKind::Call(contract)),
+            ..Default::default()
+        };
+        let (cfg, mut block_env, _) = eth_api.evm_env_at(BlockId::latest()).await.unwrap();
+
+        block_env.gas_limit = U256::from(30_000_000);
+        let state = eth_api.latest_state().unwrap();
+        let estimate = eth_api
+            .estimate_gas_detailed_with(
+                cfg.clone(),
+                block_env.clone(),
+                request.clone(),
+                state,
+                None,
+            )
+            .unwrap();
+        assert!(estimate.is_capped());
+        assert_eq!(estimate.highest, 1_000_000);
+        assert_eq!(estimate.floor, 21_000);
+        assert!(estimate.gas > estimate.floor && estimate.gas < estimate.highest);
+
+        block_env.gas_limit = U256::from(500_000);
+        let state = eth_api.latest_state().unwrap();
+        let bounded =
+            eth_api.estimate_gas_detailed_with(cfg, block_env, request, state, None).unwrap();
+        assert_eq!(bounded.bound, GasEstimateBound::BlockGasLimit);
+        assert_eq!(bounded.highest, 500_000);
+        assert_eq!(bounded.gas, estimate.gas);
+    }
+
+    #[tokio::test]
+    async fn simulate_v1_chains_blocks() {
+        let contract = Address::random();
+        let sender = Address::random();
+        let recipient = Address::with_last_byte(0xaa);
+        // returns the balance of `recipient`
+        let code = bytes!("7300000000000000000000000000000000000000aa3160005260206000f3");
+        let eth_api = eth_api_with_contract(contract, code, 1_000_000);
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                SimBlock {
+                    state_overrides: Some(HashMap::from([(
+                        sender,
+                        AccountOverride {
+                            balance: Some(U256::from(1_000_000)),
+                            ..Default::default()
+                        },
+                    )])),
+                    calls: vec![transfer(sender, recipient, 1_000)],
+                    ..Default::default()
+                },
+                SimBlock {
+                    calls: vec![TransactionRequest {
+                        to: Some(TxKind::Call(contract)),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+            return_full_receipts: true,
+        };
+        let blocks = eth_api.simulate_v1(payload, None).await.unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].number, 1);
+        assert_eq!(blocks[1].number, 2);
+        assert_eq!(
+            blocks[1].timestamp,
+            blocks[0].timestamp + eth_api.provider().chain_spec().block_time.as_secs()
+        );
+
+        let transfer = &blocks[0].calls[0];
+        assert!(transfer.status);
+        assert_eq!(transfer.gas_used, 21_000);
+        assert_eq!(blocks[0].gas_used, 21_000);
+        assert!(transfer.receipt.is_some());
+
+        // the balance read sees the transfer of the previous block
+        let read = &blocks[1].calls[0];
+        assert!(read.status);
+        assert_eq!(read.return_data, Bytes::from(U256::from(1_000).to_be_bytes::<32>()));
+    }
+
+    #[tokio::test]
+    async fn simulate_v1_validates_blocks() {
+        let contract = Address::random();
+        let eth_api = eth_api_with_contract(contract, Bytes::new(), 1_000_000);
+
+        // block numbers must increase
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                SimBlock {
+                    block_overrides: Some(BlockOverrides {
+                        number: Some(U256::from(10)),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                SimBlock {
+                    block_overrides: Some(BlockOverrides {
+                        number: Some(U256::from(5)),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let err = eth_api.simulate_v1(payload, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+
+        // the number of blocks is limited
+        let payload = SimulatePayload {
+            block_state_calls: vec![SimBlock::default(); DEFAULT_MAX_SIMULATE_BLOCKS as usize + 1],
+            ..Default::default()
+        };
+        let err = eth_api.simulate_v1(payload, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+    }
+
+    #[tokio::test]
+    async fn simulate_v1_limits_gas() {
+        let sender = Address::random();
+        let recipient = Address::random();
+        let eth_api = eth_api_with_contract(Address::random(), Bytes::new(), 50_000);
+        let call = TransactionRequest { gas: Some(30_000), ..transfer(sender, recipient, 0) };
+
+        // the calls of a block can't exceed the block gas limit
+        let payload = SimulatePayload {
+            block_state_calls: vec![SimBlock {
+                block_overrides: Some(BlockOverrides {
+                    gas_limit: Some(U64::from(40_000)),
+                    ..Default::default()
+                }),
+                calls: vec![call.clone(), call.clone()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let err = eth_api.simulate_v1(payload, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::InvalidParams(_)));
+
+        // all calls of a request share the gas budget, also across blocks
+        let block = SimBlock { calls: vec![call], ..Default::default() };
+        let payload =
+            SimulatePayload { block_state_calls: vec![block.clone(), block], ..Default::default() };
+        let err = eth_api.simulate_v1(payload, None).await.unwrap_err();
+        assert!(matches!(err, EthApiError::GasCapExceeded { cap: 29_000, requested: 30_000 }));
+    }
+
+    #[tokio::test]
+    async fn simulate_v1_derives_base_fee() {
+        let sender = Address::random();
+        let eth_api = eth_api_with_contract(Address::random(), Bytes::new(), 1_000_000);
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![
+                SimBlock {
+                    block_overrides: Some(BlockOverrides {
+                        gas_limit: Some(U64::from(ETHEREUM_BLOCK_GAS_LIMIT)),
+                        base_fee: Some(U256::from(1_000_000_000)),
+                        ..Default::default()
+                    }),
+                    calls: vec![transfer(sender, Address::random(), 0)],
+                    ..Default::default()
+                },
+                SimBlock::default(),
+            ],
+            ..Default::default()
+        };
+        let blocks = eth_api.simulate_v1(payload, None).await.unwrap();
+
+        // the base fee of the second block follows from the gas used by the first one
+        let base_fee_params = eth_api.provider().chain_spec().base_fee_params_at_timestamp(0);
+        assert_eq!(blocks[0].base_fee_per_gas, 1_000_000_000);
+        assert_eq!(
+            blocks[1].base_fee_per_gas,
+            next_block_base_fee(21_000, ETHEREUM_BLOCK_GAS_LIMIT, 1_000_000_000, base_fee_params)
+                as u128
+        );
+    }
+}
@@ -1,9 +1,11 @@
 //! Contains RPC handler implementations for fee history.
 
+use futures::Future;
+use reth_primitives::U256;
 use reth_provider::{BlockIdReader, BlockReaderIdExt, ChainSpecProvider, HeaderProvider};
 
 use reth_rpc_eth_api::helpers::{EthFees, LoadBlock, LoadFee};
-use reth_rpc_eth_types::{EthStateCache, FeeHistoryCache, GasPriceOracle};
+use reth_rpc_eth_types::{EthResult, EthStateCache, FeeHistoryCache, GasPriceOracle};
 
 use crate::EthApi;
 
@@ -36,4 +38,160 @@ where
     fn fee_history_cache(&self) -> &FeeHistoryCache {
         self.inner.fee_history_cache()
     }
+
+    /// Returns the suggestion of the custom gas oracle if one is set, see
+    /// [`EthApi::set_gas_oracle`].
+    fn suggested_priority_fee(&self) -> impl Future<Output = EthResult<U256>> + Send
+    where
+        Self: 'static,
+    {
+        let custom_gas_oracle = self.inner.custom_gas_oracle();
+        async move {
+            match custom_gas_oracle {
+                Some(gas_oracle) => gas_oracle.suggest_tip_cap().await,
+                None => self.gas_oracle().suggest_tip_cap().await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::future::BoxFuture;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        Block, BlockNumberOrTag, Header, Receipt, Transaction, TxEip1559, TxLegacy, B256,
+    };
+    use reth_provider::{test_utils::MockEthProvider, BlockHashReader};
+    use reth_rpc_eth_types::{GasOracle, GasPriceOracleConfig};
+    use reth_testing_utils::generators::{self, sign_tx_with_random_key_pair};
+    use reth_transaction_pool::test_utils::TestPool;
+
+    use super::*;
+    use crate::eth::test_utils::TestEthApiBuilder;
+
+    /// The block at which London activated on mainnet.
+    const LONDON_BLOCK: u64 = 12_965_000;
+
+    /// Builds an API over three blocks starting at `first_block`, each with a single transaction
+    /// paying 10, 20 and 30 wei and using 21000 gas.
+    async fn eth_api_with_chain(
+        first_block: u64,
+        base_fee_per_gas: Option<u64>,
+        transaction: impl Fn(u128) -> Transaction,
+    ) -> EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig> {
+        let provider = MockEthProvider::default();
+        let mut rng = generators::rng();
+        let mut parent_hash = B256::ZERO;
+        for (fee, number) in [10, 20, 30].into_iter().zip(first_block..) {
+            let tx = sign_tx_with_random_key_pair(&mut rng, transaction(fee));
+            let header = Header {
+                number,
+                parent_hash,
+                base_fee_per_gas,
+                gas_used: 21_000,
+                ..Default::default()
+            };
+            let block = Block { header, body: vec![tx], ..Default::default() }.seal_slow();
+            provider.add_header(block.hash(), block.header.header().clone());
+            provider.add_block(block.hash(), block.clone().unseal());
+            let receipt =
+                Receipt { success: true, cumulative_gas_used: 21_000, ..Default::default() };
+            provider.add_receipts(block.hash(), vec![receipt]);
+            parent_hash = block.hash();
+        }
+
+        // only sample the blocks of the chain, and don't raise the small test tips
+        let oracle_config = GasPriceOracleConfig {
+            blocks: 3,
+            min_suggested_priority_fee: Some(U256::ZERO),
+            ..Default::default()
+        };
+        TestEthApiBuilder::new(provider).gpo_config(oracle_config).build()
+    }
+
+    #[tokio::test]
+    async fn gas_price_adds_base_fee_after_london() {
+        let eth_api = eth_api_with_chain(LONDON_BLOCK, Some(7), |tip| {
+            Transaction::Eip1559(TxEip1559 {
+                max_fee_per_gas: 1_000,
+                max_priority_fee_per_gas: tip,
+                gas_limit: 21_000,
+                ..Default::default()
+            })
+        })
+        .await;
+
+        // the 60th percentile of the tips plus the base fee of the latest block
+        assert_eq!(EthFees::suggested_priority_fee(&eth_api).await.unwrap(), U256::from(20));
+        assert_eq!(EthFees::gas_price(&eth_api).await.unwrap(), U256::from(27));
+    }
+
+    #[tokio::test]
+    async fn gas_price_is_legacy_sample_before_london() {
+        let eth_api = eth_api_with_chain(1, None, |gas_price| {
+            Transaction::Legacy(TxLegacy { gas_price, gas_limit: 21_000, ..Default::default() })
+        })
+        .await;
+
+        // the 60th percentile of the legacy gas prices
+        assert_eq!(EthFees::gas_price(&eth_api).await.unwrap(), U256::from(20));
+    }
+
+    #[tokio::test]
+    async fn gas_price_from_custom_gas_oracle() {
+        struct FixedGasOracle(U256);
+
+        impl GasOracle for FixedGasOracle {
+            fn suggest_tip_cap(&self) -> BoxFuture<'_, EthResult<U256>> {
+                Box::pin(async move { Ok(self.0) })
+            }
+        }
+
+        let eth_api = eth_api_with_chain(LONDON_BLOCK, Some(7), |tip| {
+            Transaction::Eip1559(TxEip1559 {
+                max_fee_per_gas: 1_000,
+                max_priority_fee_per_gas: tip,
+                gas_limit: 21_000,
+                ..Default::default()
+            })
+        })
+        .await;
+        eth_api.set_gas_oracle(Arc::new(FixedGasOracle(U256::from(42))));
+
+        assert_eq!(EthFees::suggested_priority_fee(&eth_api).await.unwrap(), U256::from(42));
+        assert_eq!(EthFees::gas_price(&eth_api).await.unwrap(), U256::from(42 + 7));
+    }
+
+    #[tokio::test]
+    async fn fee_history_memoizes_block_tips() {
+        let eth_api = eth_api_with_chain(LONDON_BLOCK, Some(7), |tip| {
+            Transaction::Eip1559(TxEip1559 {
+                max_fee_per_gas: 1_000,
+                max_priority_fee_per_gas: tip,
+                gas_limit: 21_000,
+                ..Default::default()
+            })
+        })
+        .await;
+        let newest_block = BlockNumberOrTag::Number(LONDON_BLOCK + 2);
+        let hash = eth_api.provider().block_hash(LONDON_BLOCK + 2).unwrap().unwrap();
+        assert!(eth_api.fee_history_cache().memoized_block_tips(hash).is_none());
+
+        // the fee history cache holds no entries, so the rewards are computed from the block
+        let history =
+            EthFees::fee_history(&eth_api, 1, newest_block, Some(vec![50.])).await.unwrap();
+        assert_eq!(history.reward, Some(vec![vec![30]]));
+        let tips = eth_api.fee_history_cache().memoized_block_tips(hash).unwrap();
+
+        // a request for other percentiles of the same block reuses the memoized tips
+        let history =
+            EthFees::fee_history(&eth_api, 1, newest_block, Some(vec![10., 90.])).await.unwrap();
+        assert_eq!(history.reward, Some(vec![vec![30, 30]]));
+        let memoized = eth_api.fee_history_cache().memoized_block_tips(hash).unwrap();
+        assert!(Arc::ptr_eq(&tips, &memoized));
+    }
 }
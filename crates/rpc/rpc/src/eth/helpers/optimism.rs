@@ -16,7 +16,9 @@ use revm::L1BlockInfo;
 use revm_primitives::{BlockEnv, ExecutionResult};
 
 use reth_rpc_eth_api::helpers::{LoadPendingBlock, LoadReceipt, SpawnBlocking};
-use reth_rpc_eth_types::{EthApiError, EthResult, EthStateCache, PendingBlock, ReceiptBuilder};
+use reth_rpc_eth_types::{
+    EthApiError, EthResult, EthStateCache, PendingBlockCache, ReceiptBuilder,
+};
 use reth_rpc_server_types::result::internal_rpc_err;
 
 use crate::EthApi;
@@ -164,10 +166,15 @@ where
     }
 
     #[inline]
-    fn pending_block(&self) -> &tokio::sync::Mutex<Option<PendingBlock>> {
+    fn pending_block(&self) -> &tokio::sync::Mutex<PendingBlockCache> {
         self.inner.pending_block()
     }
 
+    #[inline]
+    fn pending_block_build_error(&self) -> &parking_lot::RwLock<Option<String>> {
+        self.inner.pending_block_build_error()
+    }
+
     #[inline]
     fn evm_config(&self) -> &impl reth_evm::ConfigureEvm {
         self.inner.evm_config()
@@ -3,7 +3,8 @@
 use reth_evm::ConfigureEvm;
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
 use reth_rpc_eth_api::helpers::{LoadPendingBlock, SpawnBlocking};
-use reth_rpc_eth_types::PendingBlock;
+use reth_rpc_eth_types::PendingBlockCache;
+use reth_rpc_types::engine::PayloadAttributes;
 use reth_transaction_pool::TransactionPool;
 
 use crate::EthApi;
@@ -29,12 +30,513 @@ where
     }
 
     #[inline]
-    fn pending_block(&self) -> &tokio::sync::Mutex<Option<PendingBlock>> {
+    fn pending_block(&self) -> &tokio::sync::Mutex<PendingBlockCache> {
         self.inner.pending_block()
     }
 
+    #[inline]
+    fn pending_block_build_error(&self) -> &parking_lot::RwLock<Option<String>> {
+        self.inner.pending_block_build_error()
+    }
+
+    #[inline]
+    fn include_private_in_pending(&self) -> bool {
+        self.inner.include_private_in_pending()
+    }
+
+    #[inline]
+    fn pending_block_attributes(&self) -> Option<PayloadAttributes> {
+        self.inner.pending_block_attributes()
+    }
+
     #[inline]
     fn evm_config(&self) -> &impl ConfigureEvm {
         self.inner.evm_config()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use reth_chainspec::{ChainSpec, MAINNET};
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT,
+        proofs::calculate_withdrawals_root,
+        revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg, SpecId},
+        Address, Block, BlockId, Bytes, Header, SealedBlockWithSenders, Withdrawal, Withdrawals,
+        B256, U256,
+    };
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        CanonStateNotification, Chain, ExecutionOutcome, StateProvider,
+    };
+    use reth_revm::db::BundleState;
+    use reth_rpc_eth_api::helpers::EthBlocks;
+    use reth_rpc_eth_types::{
+        pending_block::pending_block_cache_new_blocks_task, PendingBlock, PendingBlockEnv,
+        PendingBlockEnvOrigin, SkippedTransactionReason,
+    };
+    use reth_rpc_types::BlockTransactions;
+    use reth_testing_utils::generators::{self, random_signed_tx};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        ChangedAccount, PoolTransaction, TransactionOrigin, TransactionPoolExt,
+    };
+
+    use revm_primitives::AccountInfo;
+
+    use super::*;
+    use crate::eth::test_utils::TestEthApiBuilder;
+
+    #[tokio::test]
+    async fn records_pending_block_build_error() {
+        let mock_provider = MockEthProvider::default();
+        let header = Header { number: 1, ..Default::default() };
+        let hash = header.hash_slow();
+        mock_provider.add_header(hash, header.clone());
+        mock_provider.add_block(hash, Block { header, ..Default::default() });
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+        assert!(eth_api.pending_block_build_error().read().is_none());
+
+        // cancun is active at this timestamp on mainnet, so building the pending block fails
+        // without a parent beacon block root
+        let attributes = PayloadAttributes {
+            timestamp: 1_710_338_135,
+            prev_randao: B256::random(),
+            suggested_fee_recipient: Address::random(),
+            withdrawals: Some(vec![]),
+            parent_beacon_block_root: None,
+        };
+        eth_api.set_pending_block_attributes(Some(attributes)).await;
+        let pending = eth_api.local_pending_block().await.unwrap();
+        assert!(pending.is_none());
+        assert!(eth_api.pending_block_build_error().read().is_some());
+    }
+
+    #[tokio::test]
+    async fn derived_pending_block_uses_chain_block_time() {
+        let mock_provider = MockEthProvider {
+            chain_spec: Arc::new(ChainSpec {
+                block_time: Duration::from_secs(2),
+                ..(**MAINNET).clone()
+            }),
+            ..Default::default()
+        };
+        let header = Header { number: 1, timestamp: 100, ..Default::default() };
+        let hash = header.hash_slow();
+        mock_provider.add_header(hash, header.clone());
+        mock_provider.add_block(hash, Block { header, ..Default::default() });
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(pending.origin.header().number, 2);
+        assert_eq!(pending.origin.header().timestamp, 102);
+    }
+
+    #[tokio::test]
+    async fn pending_block_env_derived_from_genesis() {
+        let mock_provider = MockEthProvider::default();
+        // only the genesis header is known, there's no latest block yet
+        let genesis = mock_provider.chain_spec.sealed_genesis_header();
+        mock_provider.add_header(genesis.hash(), genesis.header().clone());
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(pending.origin.header().number, 1);
+        assert_eq!(pending.origin.state_block_id(), BlockId::Hash(genesis.hash().into()));
+    }
+
+    #[tokio::test]
+    async fn caches_actual_pending_block() {
+        let mock_provider = MockEthProvider::default();
+        let eth_api = TestEthApiBuilder::new(mock_provider.clone()).build();
+
+        let pending_block = |number, senders| SealedBlockWithSenders {
+            block: Block { header: Header { number, ..Default::default() }, ..Default::default() }
+                .seal_slow(),
+            senders,
+        };
+
+        let first = pending_block(1, vec![]);
+        mock_provider.set_pending_block(Some(first.clone()));
+        assert_eq!(eth_api.local_pending_block().await.unwrap(), Some(first.clone()));
+
+        // a block with the same hash is served from memory instead of being fetched again
+        mock_provider.set_pending_block(Some(pending_block(1, vec![Address::random()])));
+        assert_eq!(eth_api.local_pending_block().await.unwrap(), Some(first));
+
+        // a new pending block hash invalidates the cached block
+        let second = pending_block(2, vec![]);
+        mock_provider.set_pending_block(Some(second.clone()));
+        assert_eq!(eth_api.local_pending_block().await.unwrap(), Some(second));
+    }
+
+    #[tokio::test]
+    async fn pending_block_with_full_transactions() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        mock_provider.add_header(latest_hash, latest.clone());
+        mock_provider.add_block(latest_hash, Block { header: latest, ..Default::default() });
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        // the block the pending block builder produced from two pool transactions
+        let mut rng = generators::rng();
+        let transactions = vec![random_signed_tx(&mut rng), random_signed_tx(&mut rng)];
+        let senders = transactions.iter().map(|tx| tx.recover_signer().unwrap()).collect();
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let number = pending.block_env.number.to();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number,
+            ..Default::default()
+        };
+        let block = SealedBlockWithSenders {
+            block: Block { header, body: transactions.clone(), ..Default::default() }.seal_slow(),
+            senders,
+        };
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            block,
+            Default::default(),
+            Instant::now() + Duration::from_secs(1),
+        ));
+
+        let block = eth_api.rpc_block(BlockId::pending(), true).await.unwrap().unwrap();
+        let BlockTransactions::Full(txs) = block.inner.transactions else {
+            panic!("expected full transactions")
+        };
+        assert_eq!(txs.len(), 2);
+        for (idx, (tx, signed)) in txs.iter().zip(&transactions).enumerate() {
+            assert_eq!(tx.hash, signed.hash());
+            assert_eq!(tx.transaction_index, Some(idx as u64));
+            assert_eq!(tx.block_hash, None);
+            assert_eq!(tx.block_number, Some(number));
+        }
+    }
+
+    #[tokio::test]
+    async fn private_transactions_in_pending_block() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, ..Default::default() }.seal_slow();
+        mock_provider.add_header(latest.hash(), latest.header().clone());
+
+        // a private transaction the sender can pay for
+        let pool = testing_pool();
+        let tx = MockTransaction::eip1559().with_gas_limit(21_000);
+        mock_provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(u64::MAX)));
+        pool.add_transaction(TransactionOrigin::Private, tx.clone()).await.unwrap();
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).pool(pool).build();
+
+        // the mock chain isn't merged, so the env with all hardforks enabled is configured here
+        let pending_env = || {
+            PendingBlockEnv::new(
+                CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::LATEST),
+                BlockEnv {
+                    number: U256::from(2),
+                    gas_limit: U256::from(ETHEREUM_BLOCK_GAS_LIMIT),
+                    ..Default::default()
+                },
+                PendingBlockEnvOrigin::DerivedFromLatest(latest.clone()),
+            )
+        };
+
+        let (block, _) = eth_api.build_block(pending_env()).unwrap();
+        assert!(block.body.is_empty());
+
+        eth_api.set_include_private_in_pending(true);
+        let (block, _) = eth_api.build_block(pending_env()).unwrap();
+        assert_eq!(block.body.len(), 1);
+        assert_eq!(block.body[0].hash(), *tx.hash());
+        assert_eq!(block.senders, vec![tx.sender()]);
+    }
+
+    #[tokio::test]
+    async fn build_report_lists_skipped_transactions() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, ..Default::default() }.seal_slow();
+        mock_provider.add_header(latest.hash(), latest.header().clone());
+        let pool = testing_pool();
+        let funded = ExtendedAccount::new(0, U256::from(u64::MAX));
+
+        // a transaction that is included
+        let included = MockTransaction::eip1559().with_gas_limit(21_000);
+        mock_provider.add_account(included.sender(), funded.clone());
+
+        // a transaction that is included, but reverts
+        let reverted = MockTransaction::eip1559().with_gas_limit(50_000);
+        mock_provider.add_account(reverted.sender(), funded.clone());
+        let revert_code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd]);
+        mock_provider.add_account(
+            reverted.to().unwrap(),
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(revert_code),
+        );
+
+        // a transaction that exceeds the block gas limit
+        let oversized = MockTransaction::eip1559().with_gas_limit(2_000_000);
+        mock_provider.add_account(oversized.sender(), funded.clone());
+
+        // a transaction whose sender can't pay for its value
+        let unfunded = MockTransaction::eip1559().with_gas_limit(21_000).with_value(U256::from(1));
+        mock_provider.add_account(unfunded.sender(), ExtendedAccount::new(0, U256::ZERO));
+
+        // a transaction the pool considers executable, but whose nonce is ahead of the state
+        let gapped = MockTransaction::eip1559().with_gas_limit(21_000).with_nonce(5);
+        mock_provider.add_account(gapped.sender(), ExtendedAccount::new(3, U256::from(u64::MAX)));
+
+        for tx in [&included, &reverted, &oversized, &unfunded, &gapped] {
+            pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+        }
+        pool.update_accounts(vec![ChangedAccount {
+            address: gapped.sender(),
+            nonce: 5,
+            balance: U256::MAX,
+        }]);
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).pool(pool).build();
+
+        let pending_env = PendingBlockEnv::new(
+            CfgEnvWithHandlerCfg::new_with_spec_id(Default::default(), SpecId::LATEST),
+            BlockEnv {
+                number: U256::from(2),
+                gas_limit: U256::from(1_000_000),
+                ..Default::default()
+            },
+            PendingBlockEnvOrigin::DerivedFromLatest(latest),
+        );
+        let (block, _, report) = eth_api.build_block_with_report(pending_env).unwrap();
+
+        let mut included_hashes = vec![*included.hash(), *reverted.hash()];
+        included_hashes.sort();
+        let mut block_hashes = block.body.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        block_hashes.sort();
+        assert_eq!(block_hashes, included_hashes);
+        let mut report_hashes = report.included.clone();
+        report_hashes.sort();
+        assert_eq!(report_hashes, included_hashes);
+        assert_eq!(report.reverted, vec![*reverted.hash()]);
+
+        assert_eq!(report.skipped.len(), 3);
+        assert_eq!(
+            report.skip_reason(oversized.hash()),
+            Some(&SkippedTransactionReason::GasLimitReached)
+        );
+        assert_eq!(
+            report.skip_reason(unfunded.hash()),
+            Some(&SkippedTransactionReason::InsufficientFunds)
+        );
+        assert_eq!(report.skip_reason(gapped.hash()), Some(&SkippedTransactionReason::NonceGap));
+    }
+
+    #[tokio::test]
+    async fn committed_block_evicts_realized_pending_block() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        mock_provider.add_header(latest_hash, latest.clone());
+        mock_provider.add_block(latest_hash, Block { header: latest, ..Default::default() });
+
+        let eth_api = TestEthApiBuilder::new(mock_provider.clone()).build();
+
+        // the block the pending block builder produced on top of the latest block
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number: pending.block_env.number.to(),
+            ..Default::default()
+        };
+        let built = SealedBlockWithSenders {
+            block: Block { header, ..Default::default() }.seal_slow(),
+            senders: vec![],
+        };
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            built.clone(),
+            Default::default(),
+            Instant::now() + Duration::from_secs(1),
+        ));
+        assert_eq!(eth_api.local_pending_block().await.unwrap(), Some(built));
+
+        // the realized block is committed before the cached pending block expires
+        let header = Header { parent_hash: latest_hash, number: 2, ..Default::default() };
+        let committed = SealedBlockWithSenders {
+            block: Block { header: header.clone(), ..Default::default() }.seal_slow(),
+            senders: vec![],
+        };
+        mock_provider.add_header(committed.hash(), header);
+        mock_provider.add_block(committed.hash(), committed.block.clone().unseal());
+        let new = Arc::new(Chain::new(vec![committed.clone()], Default::default(), None));
+        pending_block_cache_new_blocks_task(
+            eth_api.pending_block_cache(),
+            futures::stream::iter([CanonStateNotification::Commit { new }]),
+        )
+        .await;
+
+        // the realized pending block is gone, and the next one builds on the committed block
+        assert_eq!(eth_api.pending_block().lock().await.iter().count(), 0);
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        assert_eq!(pending.origin.build_target_hash(), committed.hash());
+    }
+
+    #[tokio::test]
+    async fn pending_state_overlays_pending_block() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        mock_provider.add_header(latest_hash, latest.clone());
+        mock_provider.add_block(latest_hash, Block { header: latest, ..Default::default() });
+
+        let (modified, untouched) = (Address::random(), Address::random());
+        mock_provider.add_account(modified, ExtendedAccount::new(1, U256::from(1)));
+        mock_provider.add_account(untouched, ExtendedAccount::new(2, U256::from(2)));
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        // the pending block is built from the empty pool, so it doesn't modify any account
+        let state = eth_api.pending_state_provider().await.unwrap();
+        assert_eq!(state.account_balance(modified).unwrap(), Some(U256::from(1)));
+        assert_eq!(eth_api.pending_block().lock().await.iter().count(), 1);
+
+        // a pending block that updates only one of the accounts replaces the built one
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let number = pending.block_env.number.to();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number,
+            ..Default::default()
+        };
+        let bundle = BundleState::builder(number..=number)
+            .state_present_account_info(
+                modified,
+                AccountInfo { nonce: 2, balance: U256::from(1000), ..Default::default() },
+            )
+            .build();
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            SealedBlockWithSenders {
+                block: Block { header, ..Default::default() }.seal_slow(),
+                senders: vec![],
+            },
+            ExecutionOutcome::new(bundle, Default::default(), number, vec![]),
+            Instant::now() + Duration::from_secs(1),
+        ));
+
+        let state = eth_api.pending_state_provider().await.unwrap();
+        assert_eq!(state.account_balance(modified).unwrap(), Some(U256::from(1000)));
+        assert_eq!(state.account_nonce(modified).unwrap(), Some(2));
+        assert_eq!(state.account_balance(untouched).unwrap(), Some(U256::from(2)));
+        assert_eq!(state.account_nonce(untouched).unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn try_pending_block_does_not_build() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        mock_provider.add_header(latest_hash, latest.clone());
+        mock_provider.add_block(latest_hash, Block { header: latest, ..Default::default() });
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        // nothing is cached and no build is attempted
+        assert!(eth_api.try_pending_block().unwrap().is_none());
+        assert!(eth_api.pending_block_build_error().read().is_none());
+
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let number = pending.block_env.number.to();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number,
+            ..Default::default()
+        };
+        let block = SealedBlockWithSenders {
+            block: Block { header, ..Default::default() }.seal_slow(),
+            senders: vec![],
+        };
+        let pending_block =
+            |expires_at| PendingBlock::new(block.clone(), ExecutionOutcome::default(), expires_at);
+
+        eth_api
+            .pending_block()
+            .lock()
+            .await
+            .insert(pending_block(Instant::now() + Duration::from_secs(1)));
+        assert_eq!(eth_api.try_pending_block().unwrap(), Some(block.clone()));
+
+        // a block that is currently being built isn't waited for
+        let lock = eth_api.pending_block().lock().await;
+        assert!(eth_api.try_pending_block().unwrap().is_none());
+        drop(lock);
+
+        // expired blocks aren't served
+        eth_api
+            .pending_block()
+            .lock()
+            .await
+            .insert(pending_block(Instant::now() - Duration::from_secs(1)));
+        assert!(eth_api.try_pending_block().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn pending_block_from_attributes() {
+        let mock_provider = MockEthProvider::default();
+        let latest = Header { number: 1, timestamp: 100, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        mock_provider.add_header(latest_hash, latest.clone());
+        mock_provider.add_block(latest_hash, Block { header: latest, ..Default::default() });
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        let withdrawal =
+            Withdrawal { index: 0, validator_index: 1, address: Address::random(), amount: 2 };
+        let attributes = PayloadAttributes {
+            // shanghai is active at this timestamp on mainnet
+            timestamp: 1_700_000_000,
+            prev_randao: B256::random(),
+            suggested_fee_recipient: Address::random(),
+            withdrawals: Some(vec![withdrawal]),
+            parent_beacon_block_root: None,
+        };
+        eth_api.set_pending_block_attributes(Some(attributes.clone())).await;
+
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let header = pending.origin.header().clone();
+        assert!(matches!(pending.origin, PendingBlockEnvOrigin::FromAttributes { .. }));
+        assert_eq!(header.number, 2);
+        assert_eq!(header.timestamp, attributes.timestamp);
+        assert_eq!(header.beneficiary, attributes.suggested_fee_recipient);
+        assert_eq!(pending.origin.build_target_hash(), latest_hash);
+
+        // the mock chain isn't merged, so the post-merge block env is configured from the header
+        let block_env = BlockEnv {
+            number: U256::from(header.number),
+            coinbase: header.beneficiary,
+            timestamp: U256::from(header.timestamp),
+            prevrandao: Some(header.mix_hash),
+            gas_limit: U256::from(ETHEREUM_BLOCK_GAS_LIMIT),
+            ..Default::default()
+        };
+        let (block, execution_outcome) = eth_api
+            .build_block(PendingBlockEnv::new(pending.cfg, block_env, pending.origin))
+            .unwrap();
+        assert_eq!(block.beneficiary, attributes.suggested_fee_recipient);
+        assert_eq!(block.timestamp, attributes.timestamp);
+        assert_eq!(block.mix_hash, attributes.prev_randao);
+        assert_eq!(block.withdrawals, Some(Withdrawals::new(vec![withdrawal])));
+        assert_eq!(block.withdrawals_root, Some(calculate_withdrawals_root(&[withdrawal])));
+        // withdrawal amounts are denominated in gwei
+        let recipient = execution_outcome.account(&withdrawal.address).flatten().unwrap();
+        assert_eq!(recipient.balance, U256::from(2_000_000_000u64));
+
+        // without attributes the pending block is derived from the latest block again
+        eth_api.set_pending_block_attributes(None).await;
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        assert!(matches!(pending.origin, PendingBlockEnvOrigin::DerivedFromLatest(_)));
+    }
+}
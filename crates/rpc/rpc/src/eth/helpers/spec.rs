@@ -1,10 +1,13 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use reth_chainspec::ChainInfo;
 use reth_errors::{RethError, RethResult};
 use reth_evm::ConfigureEvm;
-use reth_network_api::NetworkInfo;
-use reth_primitives::{Address, U256, U64};
+use reth_network_api::{NetworkInfo, PeersInfo};
+use reth_primitives::{Address, U64};
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
 use reth_rpc_eth_api::helpers::EthApiSpec;
+use reth_rpc_eth_types::{HealthStatus, DEFAULT_MAX_BLOCKS_BEHIND};
 use reth_rpc_types::{SyncInfo, SyncStatus};
 use reth_transaction_pool::TransactionPool;
 
@@ -15,7 +18,7 @@ where
     Pool: TransactionPool + 'static,
     Provider:
         BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
-    Network: NetworkInfo + 'static,
+    Network: NetworkInfo + PeersInfo + 'static,
     EvmConfig: ConfigureEvm,
 {
     /// Returns the current ethereum protocol version.
@@ -27,13 +30,33 @@ where
     }
 
     /// Returns the chain id
+    ///
+    /// This is read from the chain spec, so it doesn't depend on the network being launched.
     fn chain_id(&self) -> U64 {
-        U64::from(self.network().chain_id())
+        U64::from(self.provider().chain_spec().chain().id())
     }
 
     /// Returns the current info for the chain
+    ///
+    /// Unless the node is syncing, this is served from the
+    /// [`ChainInfoCache`](reth_rpc_eth_types::ChainInfoCache) that is updated on every canonical
+    /// chain change. While syncing, blocks can become canonical without a
+    /// notification, so the provider is asked and the cache is cleared.
     fn chain_info(&self) -> RethResult<ChainInfo> {
-        Ok(self.provider().chain_info()?)
+        let syncing = self.is_syncing();
+        if !syncing {
+            if let Some(info) = self.inner.chain_info().get() {
+                return Ok(info)
+            }
+        }
+
+        let info = self.provider().chain_info()?;
+        if syncing {
+            self.inner.chain_info().clear();
+        } else {
+            self.inner.chain_info().set_if_empty(info);
+        }
+        Ok(info)
     }
 
     fn accounts(&self) -> Vec<Address> {
@@ -47,13 +70,10 @@ where
     /// Returns the [`SyncStatus`] of the network
     fn sync_status(&self) -> RethResult<SyncStatus> {
         let status = if self.is_syncing() {
-            let current_block = U256::from(
-                self.provider().chain_info().map(|info| info.best_number).unwrap_or_default(),
-            );
             SyncStatus::Info(SyncInfo {
                 starting_block: self.inner.starting_block(),
-                current_block,
-                highest_block: current_block,
+                current_block: self.inner.current_block(),
+                highest_block: self.inner.highest_block(),
                 warp_chunks_amount: None,
                 warp_chunks_processed: None,
             })
@@ -62,4 +82,251 @@ where
         };
         Ok(status)
     }
+
+    /// Returns the [`HealthStatus`] of the node.
+    ///
+    /// The highest block seen on the network is the target of the current sync, or the best
+    /// block if no sync target has been reported.
+    fn health(&self) -> RethResult<HealthStatus> {
+        let ChainInfo { best_hash, best_number: best_block } = self.chain_info()?;
+        let highest_block = best_block.max(self.inner.highest_block().to());
+        let latest_block_age = self.provider().header(&best_hash)?.map(|header| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            now.saturating_sub(Duration::from_secs(header.timestamp))
+        });
+        Ok(HealthStatus {
+            peers: self.network().num_connected_peers(),
+            best_block,
+            highest_block,
+            latest_block_age,
+            max_blocks_behind: DEFAULT_MAX_BLOCKS_BEHIND,
+        })
+    }
+
+    /// Returns the configured fee recipient, or the suggested fee recipient of the supplied
+    /// pending block attributes.
+    fn fee_recipient(&self) -> Option<Address> {
+        self.inner.fee_recipient().or_else(|| {
+            self.inner
+                .pending_block_attributes()
+                .map(|attributes| attributes.suggested_fee_recipient)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc};
+
+    use reth_chainspec::SEPOLIA;
+    use reth_network_api::{noop::NoopNetwork, NetworkError, NetworkStatus};
+    use reth_network_peers::{Enr, NodeRecord};
+    use reth_primitives::{Header, SealedBlock, SealedBlockWithSenders, B256, U256};
+    use reth_provider::{test_utils::MockEthProvider, CanonStateNotification, Chain};
+    use reth_rpc_eth_types::chain_info::chain_info_cache_new_blocks_task;
+    use reth_rpc_types::engine::PayloadAttributes;
+    use secp256k1::SecretKey;
+
+    use super::*;
+    use crate::eth::test_utils::TestEthApiBuilder;
+
+    /// A network that is always syncing.
+    #[derive(Debug, Default)]
+    struct SyncingNetwork(NoopNetwork);
+
+    impl NetworkInfo for SyncingNetwork {
+        fn local_addr(&self) -> SocketAddr {
+            self.0.local_addr()
+        }
+
+        async fn network_status(&self) -> Result<NetworkStatus, NetworkError> {
+            self.0.network_status().await
+        }
+
+        fn chain_id(&self) -> u64 {
+            self.0.chain_id()
+        }
+
+        fn is_syncing(&self) -> bool {
+            true
+        }
+
+        fn is_initially_syncing(&self) -> bool {
+            true
+        }
+    }
+
+    impl PeersInfo for SyncingNetwork {
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+
+        fn local_node_record(&self) -> NodeRecord {
+            self.0.local_node_record()
+        }
+
+        fn local_enr(&self) -> Enr<SecretKey> {
+            self.0.local_enr()
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_status_reports_sync_origin() {
+        let provider = MockEthProvider::default();
+        provider.add_header(B256::random(), Header { number: 100, ..Default::default() });
+        let eth_api = TestEthApiBuilder::new(provider).network(SyncingNetwork::default()).build();
+
+        // the node was launched at block 100, but the sync started at block 42
+        eth_api.set_starting_block(42);
+
+        let SyncStatus::Info(info) = eth_api.sync_status().unwrap() else {
+            panic!("expected sync info")
+        };
+        assert_eq!(info.starting_block, U256::from(42));
+        assert_eq!(info.current_block, U256::from(100));
+    }
+
+    #[tokio::test]
+    async fn sync_status_reports_sync_progress() {
+        let provider = MockEthProvider::default();
+        provider.add_header(B256::random(), Header { number: 100, ..Default::default() });
+        let eth_api = TestEthApiBuilder::new(provider).network(SyncingNetwork::default()).build();
+
+        // no target reported yet
+        let SyncStatus::Info(info) = eth_api.sync_status().unwrap() else {
+            panic!("expected sync info")
+        };
+        assert_eq!(info.current_block, U256::from(100));
+        assert_eq!(info.highest_block, U256::from(100));
+
+        // execution is ahead of the best block, the target is further ahead
+        eth_api.set_current_block(150);
+        eth_api.set_highest_block(1_000);
+
+        let SyncStatus::Info(info) = eth_api.sync_status().unwrap() else {
+            panic!("expected sync info")
+        };
+        assert_eq!(info.starting_block, U256::from(100));
+        assert_eq!(info.current_block, U256::from(150));
+        assert_eq!(info.highest_block, U256::from(1_000));
+
+        // the pipeline unwound below the best block
+        eth_api.set_current_block(80);
+
+        let SyncStatus::Info(info) = eth_api.sync_status().unwrap() else {
+            panic!("expected sync info")
+        };
+        assert_eq!(info.current_block, U256::from(80));
+        assert_eq!(info.highest_block, U256::from(1_000));
+    }
+
+    #[tokio::test]
+    async fn chain_id_from_chain_spec() {
+        let provider = MockEthProvider { chain_spec: SEPOLIA.clone(), ..Default::default() };
+        let eth_api = TestEthApiBuilder::new(provider).network(SyncingNetwork::default()).build();
+
+        // the no-op network always reports mainnet
+        assert_eq!(eth_api.network().chain_id(), 1);
+        assert_eq!(eth_api.chain_id(), U64::from(SEPOLIA.chain().id()));
+    }
+
+    #[tokio::test]
+    async fn health_reports_node_behind_as_unhealthy() {
+        let provider = MockEthProvider::default();
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() - 60;
+        provider
+            .add_header(B256::random(), Header { number: 100, timestamp, ..Default::default() });
+        let eth_api = TestEthApiBuilder::new(provider).network(SyncingNetwork::default()).build();
+
+        // no sync target reported, the node is at the tip
+        let health = eth_api.health().unwrap();
+        assert!(health.has_peers());
+        assert!(health.is_healthy());
+        assert!(health.latest_block_age.unwrap() >= Duration::from_secs(60));
+
+        // peers report a chain far ahead of the node
+        eth_api.set_highest_block(1_000);
+
+        let health = eth_api.health().unwrap();
+        assert_eq!(health.best_block, 100);
+        assert_eq!(health.highest_block, 1_000);
+        assert_eq!(health.blocks_behind(), 900);
+        assert!(!health.is_synced());
+        assert!(!health.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn chain_info_follows_canonical_chain() {
+        let provider = MockEthProvider::default();
+        let latest = Header { number: 100, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        provider.add_header(latest_hash, latest);
+        let eth_api = TestEthApiBuilder::new(provider).build();
+
+        // the first request reads the provider and fills the cache
+        assert_eq!(eth_api.chain_info().unwrap().best_number, 100);
+        assert_eq!(
+            eth_api.chain_info_cache().get(),
+            Some(ChainInfo { best_hash: latest_hash, best_number: 100 })
+        );
+
+        // the committed block isn't known to the provider, so it must be served from the cache
+        let committed = SealedBlockWithSenders {
+            block: SealedBlock {
+                header: Header { parent_hash: latest_hash, number: 101, ..Default::default() }
+                    .seal_slow(),
+                ..Default::default()
+            },
+            senders: vec![],
+        };
+        let new = Arc::new(Chain::new(vec![committed.clone()], Default::default(), None));
+        chain_info_cache_new_blocks_task(
+            eth_api.chain_info_cache(),
+            futures::stream::iter([CanonStateNotification::Commit { new }]),
+        )
+        .await;
+
+        assert_eq!(
+            eth_api.chain_info().unwrap(),
+            ChainInfo { best_hash: committed.hash(), best_number: 101 }
+        );
+        let block_number = reth_rpc_eth_api::EthApiServer::block_number(&eth_api).unwrap();
+        assert_eq!(block_number, U256::from(101));
+    }
+
+    #[tokio::test]
+    async fn chain_info_is_read_from_provider_while_syncing() {
+        let provider = MockEthProvider::default();
+        provider.add_header(B256::random(), Header { number: 100, ..Default::default() });
+        let eth_api = TestEthApiBuilder::new(provider).network(SyncingNetwork::default()).build();
+
+        // blocks synced by the pipeline aren't notified, so the cached tip may be outdated
+        eth_api.chain_info_cache().set(ChainInfo { best_hash: B256::random(), best_number: 5 });
+        assert_eq!(eth_api.chain_info().unwrap().best_number, 100);
+        assert_eq!(eth_api.chain_info_cache().get(), None);
+    }
+
+    #[tokio::test]
+    async fn reports_configured_fee_recipient() {
+        let eth_api = TestEthApiBuilder::new(MockEthProvider::default())
+            .network(SyncingNetwork::default())
+            .build();
+        assert_eq!(eth_api.fee_recipient(), None);
+
+        // the suggested fee recipient of the pending block attributes is reported if no fee
+        // recipient is configured
+        let attributes = PayloadAttributes {
+            timestamp: 0,
+            prev_randao: B256::ZERO,
+            suggested_fee_recipient: Address::random(),
+            withdrawals: None,
+            parent_beacon_block_root: None,
+        };
+        eth_api.set_pending_block_attributes(Some(attributes.clone())).await;
+        assert_eq!(eth_api.fee_recipient(), Some(attributes.suggested_fee_recipient));
+
+        let fee_recipient = Address::random();
+        eth_api.set_fee_recipient(Some(fee_recipient));
+        assert_eq!(eth_api.fee_recipient(), Some(fee_recipient));
+    }
 }
@@ -8,9 +8,14 @@ use reth_rpc_eth_types::EthStateCache;
 
 use crate::EthApi;
 
-impl<Provider, Pool, Network, EvmConfig> EthState for EthApi<Provider, Pool, Network, EvmConfig> where
-    Self: LoadState + SpawnBlocking
+impl<Provider, Pool, Network, EvmConfig> EthState for EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Self: LoadState + SpawnBlocking,
 {
+    #[inline]
+    fn max_proof_storage_slots(&self) -> usize {
+        self.inner.max_proof_storage_slots()
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> LoadState for EthApi<Provider, Pool, Network, EvmConfig>
@@ -36,41 +41,30 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, sync::Arc};
 
-    use reth_evm_ethereum::EthEvmConfig;
+    use reth_chainspec::ChainSpecBuilder;
     use reth_primitives::{
-        constants::ETHEREUM_BLOCK_GAS_LIMIT, Address, StorageKey, StorageValue, U256,
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, keccak256, Address, Block, BlockId, Bytes, Header,
+        StorageKey, StorageValue, TxKind, B256, U256,
     };
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
-    use reth_rpc_eth_api::helpers::EthState;
-    use reth_rpc_eth_types::{
-        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    use reth_rpc_eth_api::helpers::{EthCall, EthState, LoadPendingBlock};
+    use reth_rpc_eth_types::{EthApiError, RpcInvalidTransactionError, StorageRangePage};
+    use reth_rpc_types::{serde_helpers::JsonStorageKey, state::EvmOverrides, TransactionRequest};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        PoolTransaction, TransactionOrigin, TransactionPool,
     };
-    use reth_tasks::pool::BlockingTaskPool;
-    use reth_transaction_pool::test_utils::testing_pool;
+    use revm_primitives::AccountInfo;
 
-    use super::*;
+    use crate::eth::test_utils::TestEthApiBuilder;
 
     #[tokio::test]
     async fn test_storage() {
         // === Noop ===
         let pool = testing_pool();
-        let evm_config = EthEvmConfig::default();
-
-        let cache = EthStateCache::spawn(NoopProvider::default(), Default::default(), evm_config);
-        let eth_api = EthApi::new(
-            NoopProvider::default(),
-            pool.clone(),
-            (),
-            cache.clone(),
-            GasPriceOracle::new(NoopProvider::default(), Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
-            evm_config,
-            None,
-        );
+        let eth_api = TestEthApiBuilder::new(NoopProvider::default()).pool(pool.clone()).build();
         let address = Address::random();
         let storage = eth_api.storage_at(address, U256::ZERO.into(), None).await.unwrap();
         assert_eq!(storage, U256::ZERO.to_be_bytes());
@@ -83,22 +77,187 @@ mod tests {
         let account = ExtendedAccount::new(0, U256::ZERO).extend_storage(storage);
         mock_provider.add_account(address, account);
 
-        let cache = EthStateCache::spawn(mock_provider.clone(), Default::default(), evm_config);
-        let eth_api = EthApi::new(
-            mock_provider.clone(),
-            pool,
-            (),
-            cache.clone(),
-            GasPriceOracle::new(mock_provider, Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
-            evm_config,
-            None,
-        );
+        let eth_api = TestEthApiBuilder::new(mock_provider).pool(pool).build();
 
         let storage_key: U256 = storage_key.into();
         let storage = eth_api.storage_at(address, storage_key.into(), None).await.unwrap();
         assert_eq!(storage, storage_value.to_be_bytes());
     }
+
+    #[tokio::test]
+    async fn test_storage_range_pages() {
+        let mock_provider = MockEthProvider::default();
+        let address = Address::random();
+        let storage = (1..=5u64)
+            .map(|slot| (StorageKey::from(U256::from(slot)), StorageValue::from(slot)))
+            .collect::<HashMap<_, _>>();
+        let mut expected =
+            storage.iter().map(|(key, value)| (keccak256(key), *value)).collect::<Vec<_>>();
+        expected.sort_unstable();
+        let account = ExtendedAccount::new(0, U256::ZERO).extend_storage(storage);
+        mock_provider.add_account(address, account);
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        let first =
+            eth_api.storage_range_at(BlockId::latest(), address, B256::ZERO, 3).await.unwrap();
+        assert_eq!(first.storage, expected[..3]);
+        assert_eq!(first.next_key, Some(expected[3].0));
+
+        let second = eth_api
+            .storage_range_at(BlockId::latest(), address, first.next_key.unwrap(), 3)
+            .await
+            .unwrap();
+        assert_eq!(second.storage, expected[3..]);
+        assert_eq!(second.next_key, None);
+
+        // accounts without storage have an empty page
+        let page = eth_api
+            .storage_range_at(BlockId::latest(), Address::random(), B256::ZERO, 3)
+            .await
+            .unwrap();
+        assert_eq!(page, StorageRangePage::default());
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts() {
+        let mock_provider = MockEthProvider::default();
+        let historical = Header { number: 1, ..Default::default() };
+        let historical_hash = historical.hash_slow();
+        mock_provider
+            .add_block(historical_hash, Block { header: historical, ..Default::default() });
+
+        let contract = Address::random();
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        mock_provider.add_account(
+            contract,
+            ExtendedAccount::new(1, U256::from(7)).with_bytecode(code.clone()),
+        );
+        let eoa = Address::random();
+        mock_provider.add_account(eoa, ExtendedAccount::new(3, U256::from(42)));
+        let missing = Address::random();
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        let accounts = eth_api
+            .get_accounts(vec![missing, contract, eoa], Some(BlockId::hash(historical_hash)))
+            .await
+            .unwrap();
+        assert_eq!(accounts.len(), 3);
+        assert_eq!(accounts[0], AccountInfo::default());
+        assert_eq!(accounts[1].balance, U256::from(7));
+        assert_eq!(accounts[1].nonce, 1);
+        assert_eq!(accounts[1].code_hash, keccak256(&code));
+        assert_eq!(accounts[1].code.as_ref().unwrap().original_bytes(), code);
+        assert_eq!(accounts[2].balance, U256::from(42));
+        assert_eq!(accounts[2].nonce, 3);
+        assert!(accounts[2].is_empty_code_hash());
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_storage_slots() {
+        let mock_provider = MockEthProvider::default();
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+        eth_api.set_max_proof_storage_slots(2);
+        let address = Address::random();
+        let [first, second, third] = [(); 3].map(|_| B256::random());
+
+        // repeated slots are proven once and don't count towards the limit
+        let keys = [first, second, first, second].map(JsonStorageKey).to_vec();
+        let proof = eth_api.get_proof(address, keys, None).unwrap().await.unwrap();
+        assert_eq!(
+            proof.storage_proof.iter().map(|proof| proof.key.0).collect::<Vec<_>>(),
+            [first, second]
+        );
+
+        let keys = [first, second, third].map(JsonStorageKey).to_vec();
+        let err = eth_api.get_proof(address, keys, None).err().unwrap();
+        assert!(matches!(err, EthApiError::ProofStorageSlotsExceeded { max: 2, requested: 3 }));
+    }
+
+    #[tokio::test]
+    async fn test_pending_balance_includes_pool_transfer() {
+        let mock_provider = MockEthProvider {
+            chain_spec: Arc::new(ChainSpecBuilder::mainnet().paris_activated().build()),
+            ..Default::default()
+        };
+        let latest =
+            Header { number: 1, gas_limit: ETHEREUM_BLOCK_GAS_LIMIT, ..Default::default() };
+        let latest_hash = latest.hash_slow();
+        mock_provider.add_header(latest_hash, latest.clone());
+        mock_provider.add_block(latest_hash, Block { header: latest, ..Default::default() });
+
+        // a transfer the sender can pay for
+        let pool = testing_pool();
+        let transfer = MockTransaction::legacy()
+            .with_gas_limit(21_000)
+            .with_gas_price(10)
+            .with_value(U256::from(1000));
+        let (sender, recipient) = (transfer.sender(), transfer.to().unwrap());
+        mock_provider.add_account(sender, ExtendedAccount::new(0, U256::from(1_000_000)));
+        pool.add_transaction(TransactionOrigin::External, transfer.clone()).await.unwrap();
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).pool(pool).build();
+
+        // requesting the pending state builds the pending block, which includes the transfer
+        let pending = eth_api.balance(recipient, Some(BlockId::pending())).await.unwrap();
+        let latest = eth_api.balance(recipient, Some(BlockId::latest())).await.unwrap();
+        assert_eq!(pending, U256::from(1000));
+        assert_eq!(latest, U256::ZERO);
+        let pending = eth_api.balance(sender, Some(BlockId::pending())).await.unwrap();
+        let latest = eth_api.balance(sender, Some(BlockId::latest())).await.unwrap();
+        assert_eq!(pending, U256::from(1_000_000 - 1000 - 21_000 * 10));
+        assert_eq!(latest, U256::from(1_000_000));
+
+        let pending = eth_api.local_pending_block().await.unwrap().unwrap();
+        assert_eq!(pending.body.len(), 1);
+        assert_eq!(pending.body[0].hash(), *transfer.hash());
+
+        // calls see the pending state as well, so the recipient can forward the received value
+        let forward = TransactionRequest {
+            from: Some(recipient),
+            to: Some(TxKind::Call(Address::random())),
+            value: Some(U256::from(1000)),
+            ..Default::default()
+        };
+        let overrides = EvmOverrides::default();
+        EthCall::call(&eth_api, forward.clone(), Some(BlockId::pending()), overrides.clone())
+            .await
+            .unwrap();
+        let err =
+            EthCall::call(&eth_api, forward, Some(BlockId::latest()), overrides).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::InvalidTransaction(RpcInvalidTransactionError::InsufficientFunds)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pending_transaction_count_from_pool() {
+        let mock_provider = MockEthProvider::default();
+        let tx = MockTransaction::eip1559().with_nonce(3);
+        mock_provider.add_account(tx.sender(), ExtendedAccount::new(3, U256::ZERO));
+
+        let pool = testing_pool();
+        let eth_api = TestEthApiBuilder::new(mock_provider).pool(pool.clone()).build();
+
+        // the two queued transactions advance the on-chain nonce
+        pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+        pool.add_transaction(TransactionOrigin::External, tx.next()).await.unwrap();
+        let count = EthState::transaction_count(&eth_api, tx.sender(), Some(BlockId::pending()))
+            .await
+            .unwrap();
+        assert_eq!(count, U256::from(5));
+        let count = EthState::transaction_count(&eth_api, tx.sender(), Some(BlockId::latest()))
+            .await
+            .unwrap();
+        assert_eq!(count, U256::from(3));
+
+        // a transaction after a nonce gap is not counted
+        pool.add_transaction(TransactionOrigin::External, tx.skip(2)).await.unwrap();
+        let count = EthState::transaction_count(&eth_api, tx.sender(), Some(BlockId::pending()))
+            .await
+            .unwrap();
+        assert_eq!(count, U256::from(5));
+    }
 }
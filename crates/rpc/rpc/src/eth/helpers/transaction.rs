@@ -2,8 +2,8 @@
 
 use reth_provider::{BlockReaderIdExt, TransactionsProvider};
 use reth_rpc_eth_api::{
-    helpers::{EthSigner, EthTransactions, LoadTransaction, SpawnBlocking},
-    RawTransactionForwarder,
+    helpers::{EthSigner, EthTransactions, LoadPendingBlock, LoadTransaction, SpawnBlocking},
+    RawTransactionForwarder, TxValidationHook,
 };
 use reth_rpc_eth_types::EthStateCache;
 use reth_transaction_pool::TransactionPool;
@@ -27,6 +27,11 @@ where
         self.inner.raw_tx_forwarder()
     }
 
+    #[inline]
+    fn tx_validation_hook(&self) -> Option<std::sync::Arc<dyn TxValidationHook>> {
+        self.inner.tx_validation_hook()
+    }
+
     #[inline]
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn EthSigner>>> {
         self.inner.signers()
@@ -36,7 +41,7 @@ where
 impl<Provider, Pool, Network, EvmConfig> LoadTransaction
     for EthApi<Provider, Pool, Network, EvmConfig>
 where
-    Self: SpawnBlocking,
+    Self: SpawnBlocking + LoadPendingBlock,
     Provider: TransactionsProvider,
     Pool: TransactionPool,
 {
@@ -60,42 +65,37 @@ where
 
 #[cfg(test)]
 mod tests {
-    use reth_evm_ethereum::EthEvmConfig;
-    use reth_network_api::noop::NoopNetwork;
-    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex, Bytes};
-    use reth_provider::test_utils::NoopProvider;
-    use reth_rpc_eth_api::helpers::EthTransactions;
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use reth_primitives::{
+        hex_literal::hex, Address, Block, BlockNumberOrTag, Bytes, Header,
+        PooledTransactionsElementEcRecovered, SealedBlock, SealedBlockWithSenders,
+        TransactionSigned,
+    };
+    use reth_provider::{
+        test_utils::{MockEthProvider, NoopProvider},
+        ExecutionOutcome,
+    };
+    use reth_rpc_eth_api::helpers::{EthTransactions, LoadPendingBlock};
     use reth_rpc_eth_types::{
-        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+        utils::recover_raw_transaction, EthApiError, PendingBlock, TransactionSource,
     };
-    use reth_tasks::pool::BlockingTaskPool;
+    use reth_rpc_types::{Index, Transaction};
     use reth_transaction_pool::{test_utils::testing_pool, TransactionPool};
 
     use super::*;
+    use crate::eth::test_utils::TestEthApiBuilder;
 
     #[tokio::test]
     async fn send_raw_transaction() {
         let noop_provider = NoopProvider::default();
-        let noop_network_provider = NoopNetwork::default();
 
         let pool = testing_pool();
 
-        let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(noop_provider, Default::default(), evm_config);
-        let fee_history_cache =
-            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
-        let eth_api = EthApi::new(
-            noop_provider,
-            pool.clone(),
-            noop_network_provider,
-            cache.clone(),
-            GasPriceOracle::new(noop_provider, Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            fee_history_cache,
-            evm_config,
-            None,
-        );
+        let eth_api = TestEthApiBuilder::new(noop_provider).pool(pool.clone()).build();
 
         // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
         let tx_1 = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
@@ -122,4 +122,198 @@ mod tests {
         assert!(pool.get(&tx_1_result).is_some(), "tx1 not found in the pool");
         assert!(pool.get(&tx_2_result).is_some(), "tx2 not found in the pool");
     }
+
+    #[derive(Debug)]
+    struct BannedSender(Address);
+
+    impl TxValidationHook for BannedSender {
+        fn validate_transaction(
+            &self,
+            transaction: &PooledTransactionsElementEcRecovered,
+        ) -> Result<(), String> {
+            if transaction.signer() == self.0 {
+                return Err(format!("sender {} is banned", self.0))
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn send_raw_transaction_rejected_by_hook() {
+        let noop_provider = NoopProvider::default();
+        let pool = testing_pool();
+
+        let eth_api = TestEthApiBuilder::new(noop_provider).pool(pool.clone()).build();
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let tx = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let sender = recover_raw_transaction(tx.clone()).unwrap().signer();
+        eth_api.set_tx_validation_hook(Arc::new(BannedSender(sender)));
+
+        let err = eth_api.send_raw_transaction(tx.clone()).await.unwrap_err();
+        assert!(matches!(err, EthApiError::TransactionRejected(_)), "{err:?}");
+        assert_eq!(pool.len(), 0);
+
+        // other senders are still accepted
+        eth_api.set_tx_validation_hook(Arc::new(BannedSender(Address::ZERO)));
+        eth_api.send_raw_transaction(tx).await.unwrap();
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn transaction_by_hash_in_pending_block() {
+        let noop_provider = NoopProvider::default();
+        let pool = testing_pool();
+
+        let eth_api = TestEthApiBuilder::new(noop_provider).pool(pool.clone()).build();
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let raw = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let hash = eth_api.send_raw_transaction(raw.clone()).await.unwrap();
+        let tx = TransactionSigned::decode_enveloped(&mut raw.as_ref())
+            .unwrap()
+            .into_ecrecovered()
+            .unwrap();
+
+        // the pool transaction is not part of a block yet
+        let source = EthTransactions::transaction_by_hash(&eth_api, hash).await.unwrap().unwrap();
+        assert!(matches!(source, TransactionSource::Pool(_)));
+
+        // include it in the pending block
+        let block = SealedBlockWithSenders {
+            block: SealedBlock {
+                header: Header { number: 1, base_fee_per_gas: Some(7), ..Default::default() }
+                    .seal_slow(),
+                body: vec![tx.clone().into_signed()],
+                ..Default::default()
+            },
+            senders: vec![tx.signer()],
+        };
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            block,
+            ExecutionOutcome::default(),
+            Instant::now() + Duration::from_secs(1),
+        ));
+
+        let source = EthTransactions::transaction_by_hash(&eth_api, hash).await.unwrap().unwrap();
+        assert_eq!(
+            source,
+            TransactionSource::PendingBlock {
+                transaction: tx,
+                index: 0,
+                block_number: 1,
+                base_fee: Some(7)
+            }
+        );
+
+        let transaction = Transaction::from(source);
+        assert_eq!(transaction.block_hash, None);
+        assert_eq!(transaction.block_number, Some(1));
+        assert_eq!(transaction.transaction_index, Some(0));
+    }
+
+    #[tokio::test]
+    async fn raw_transaction_by_hash_roundtrip() {
+        let noop_provider = NoopProvider::default();
+        let pool = testing_pool();
+
+        let eth_api = TestEthApiBuilder::new(noop_provider).pool(pool.clone()).build();
+
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let raw = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let tx = recover_raw_transaction(raw.clone()).unwrap().into_ecrecovered_transaction();
+        let hash = tx.hash();
+        assert_eq!(eth_api.raw_transaction_by_hash(hash).await.unwrap(), None);
+
+        // the transaction is found once it's included in the pending block
+        let block = SealedBlockWithSenders {
+            block: SealedBlock {
+                header: Header { number: 1, ..Default::default() }.seal_slow(),
+                body: vec![tx.clone().into_signed()],
+                ..Default::default()
+            },
+            senders: vec![tx.signer()],
+        };
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            block,
+            ExecutionOutcome::default(),
+            Instant::now() + Duration::from_secs(1),
+        ));
+        assert_eq!(eth_api.raw_transaction_by_hash(hash).await.unwrap(), Some(raw));
+    }
+
+    #[tokio::test]
+    async fn raw_transaction_by_block_and_index() {
+        // https://etherscan.io/tx/0xa694b71e6c128a2ed8e2e0f6770bddbe52e3bb8f10e8472f9a79ab81497a8b5d
+        let raw = Bytes::from(hex!("02f871018303579880850555633d1b82520894eee27662c2b8eba3cd936a23f039f3189633e4c887ad591c62bdaeb180c080a07ea72c68abfb8fca1bd964f0f99132ed9280261bdca3e549546c0205e800f7d0a05b4ef3039e9c9b9babc179a1878fb825b5aaf5aed2fa8744854150157b08d6f3"));
+        let tx = recover_raw_transaction(raw.clone()).unwrap().into_ecrecovered_transaction();
+
+        let mock_provider = MockEthProvider::default();
+        let block = Block {
+            header: Header { number: 1, ..Default::default() },
+            body: vec![tx.clone().into_signed()],
+            ..Default::default()
+        }
+        .seal_slow();
+        mock_provider.add_header(block.hash(), block.header.header().clone());
+        mock_provider.add_block(block.hash(), block.clone().unseal());
+
+        let eth_api = TestEthApiBuilder::new(mock_provider).build();
+
+        // by block hash and by block number
+        let by_hash = eth_api
+            .raw_transaction_by_block_and_tx_index(block.hash().into(), Index::from(0))
+            .await
+            .unwrap();
+        assert_eq!(by_hash, Some(raw.clone()));
+        let by_number = eth_api
+            .raw_transaction_by_block_and_tx_index(
+                BlockNumberOrTag::Number(1).into(),
+                Index::from(0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(by_number, Some(raw.clone()));
+
+        // out of range indices and unknown blocks resolve to `null`
+        let out_of_range = eth_api
+            .raw_transaction_by_block_and_tx_index(block.hash().into(), Index::from(1))
+            .await
+            .unwrap();
+        assert_eq!(out_of_range, None);
+        let unknown = eth_api
+            .raw_transaction_by_block_and_tx_index(
+                BlockNumberOrTag::Number(2).into(),
+                Index::from(0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unknown, None);
+
+        // the pool is empty, so a pending block with the transaction is cached here
+        let pending = eth_api.pending_block_env_and_cfg().unwrap();
+        let header = Header {
+            parent_hash: pending.origin.build_target_hash(),
+            number: pending.block_env.number.to(),
+            ..Default::default()
+        };
+        let pending = SealedBlockWithSenders {
+            block: SealedBlock {
+                header: header.seal_slow(),
+                body: vec![tx.clone().into_signed()],
+                ..Default::default()
+            },
+            senders: vec![tx.signer()],
+        };
+        eth_api.pending_block().lock().await.insert(PendingBlock::new(
+            pending,
+            ExecutionOutcome::default(),
+            Instant::now() + Duration::from_secs(1),
+        ));
+        let pending = eth_api
+            .raw_transaction_by_block_and_tx_index(BlockNumberOrTag::Pending.into(), Index::from(0))
+            .await
+            .unwrap();
+        assert_eq!(pending, Some(raw));
+    }
 }
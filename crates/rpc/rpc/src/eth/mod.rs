@@ -6,6 +6,9 @@ pub mod filter;
 pub mod helpers;
 pub mod pubsub;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 /// Implementation of `eth` namespace API.
 pub use bundle::EthBundle;
 pub use core::EthApi;
@@ -14,4 +17,4 @@ pub use pubsub::EthPubSub;
 
 pub use helpers::signer::DevSigner;
 
-pub use reth_rpc_eth_api::RawTransactionForwarder;
+pub use reth_rpc_eth_api::{RawTransactionForwarder, TxValidationHook};
@@ -17,7 +17,7 @@ use reth_rpc_types::{
         Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
-    FilteredParams, Header, Log,
+    FilteredParams, Header, Log, Transaction,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
@@ -131,13 +131,9 @@ where
                 match params {
                     Params::Bool(true) => {
                         // full transaction objects requested
-                        let stream = pubsub.full_pending_transaction_stream().map(|tx| {
-                            EthSubscriptionResult::FullTransaction(Box::new(
-                                reth_rpc_types_compat::transaction::from_recovered(
-                                    tx.transaction.to_recovered_transaction(),
-                                ),
-                            ))
-                        });
+                        let stream = pubsub
+                            .full_pending_transaction_stream()
+                            .map(|tx| EthSubscriptionResult::FullTransaction(Box::new(tx)));
                         return pipe_from_stream(accepted_sink, stream).await
                     }
                     Params::Bool(false) | Params::None => {
@@ -295,11 +291,19 @@ where
         ReceiverStream::new(self.pool.pending_transactions_listener())
     }
 
-    /// Returns a stream that yields all transactions emitted by the txpool.
-    fn full_pending_transaction_stream(
-        &self,
-    ) -> impl Stream<Item = NewTransactionEvent<<Pool as TransactionPool>::Transaction>> {
-        self.pool.new_pending_pool_transactions_listener()
+    /// Returns a stream that yields all transactions emitted by the txpool as full RPC
+    /// transaction objects.
+    ///
+    /// Pooled transactions already carry their recovered sender, so the signature is not
+    /// recovered again for every emitted transaction.
+    fn full_pending_transaction_stream(&self) -> impl Stream<Item = Transaction> {
+        self.pool.new_pending_pool_transactions_listener().map(
+            |NewTransactionEvent { transaction, .. }| {
+                reth_rpc_types_compat::transaction::from_recovered(
+                    transaction.transaction.to_recovered_transaction(),
+                )
+            },
+        )
     }
 }
 
@@ -338,3 +342,204 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        Address, Block, Bytes, Header as PrimitiveHeader, Receipt, Receipts,
+        SealedBlockWithSenders, Signature, Transaction as PrimitiveTransaction, TransactionSigned,
+        TxLegacy,
+    };
+    use reth_provider::{
+        test_utils::{NoopProvider, TestCanonStateSubscriptions},
+        Chain, ExecutionOutcome,
+    };
+    use reth_rpc_types::Filter;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        PoolTransaction, TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn subscribe_full_pending_transactions() {
+        let pool = testing_pool();
+        let module = EthPubSub::new(
+            NoopProvider::default(),
+            pool.clone(),
+            TestCanonStateSubscriptions::default(),
+            NoopNetwork::default(),
+        )
+        .into_rpc();
+
+        let mut sub = module
+            .subscribe_unbounded("eth_subscribe", ("newPendingTransactions", true))
+            .await
+            .unwrap();
+
+        let tx = MockTransaction::eip1559();
+        pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+
+        let (full, _) = sub.next::<Transaction>().await.unwrap().unwrap();
+        assert_eq!(full.hash, *tx.hash());
+        assert_eq!(full.from, tx.sender());
+        assert_eq!(full.nonce, tx.nonce());
+    }
+
+    /// Returns a chain with a single block at the given number whose transactions emitted the
+    /// given number of logs.
+    fn chain_with_logs(number: u64, extra_data: u8, logs_per_tx: &[usize]) -> Arc<Chain> {
+        let body = (0..logs_per_tx.len() as u64)
+            .map(|nonce| {
+                TransactionSigned::from_transaction_and_signature(
+                    PrimitiveTransaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+                    Signature::default(),
+                )
+            })
+            .collect();
+        let header =
+            PrimitiveHeader { number, extra_data: vec![extra_data].into(), ..Default::default() };
+        let block = SealedBlockWithSenders {
+            block: Block { header, body, ..Default::default() }.seal_slow(),
+            senders: vec![Address::ZERO; logs_per_tx.len()],
+        };
+        let receipts = logs_per_tx
+            .iter()
+            .map(|&logs| {
+                Some(Receipt { logs: vec![Default::default(); logs], ..Default::default() })
+            })
+            .collect::<Vec<_>>();
+        let outcome = ExecutionOutcome::new(
+            Default::default(),
+            Receipts::from_iter([receipts]),
+            number,
+            vec![],
+        );
+        Arc::new(Chain::new([block], outcome, None))
+    }
+
+    #[tokio::test]
+    async fn log_stream_marks_reverted_logs_as_removed() {
+        let chain_events = TestCanonStateSubscriptions::default();
+        let pubsub = EthPubSub::new(
+            NoopProvider::default(),
+            testing_pool(),
+            chain_events.clone(),
+            NoopNetwork::default(),
+        );
+        let mut logs = Box::pin(pubsub.inner.log_stream(FilteredParams::default()));
+
+        let old = chain_with_logs(1, 0, &[2]);
+        let new = chain_with_logs(1, 1, &[1, 1]);
+        let old_hash = old.tip().hash();
+        let new_hash = new.tip().hash();
+        let new_tx_hashes = new.tip().body.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+
+        chain_events.add_next_commit(old.clone());
+        chain_events.add_next_reorg(old, new);
+
+        // the canonical logs of the old block
+        for log_index in 0..2 {
+            let log = logs.next().await.unwrap();
+            assert!(!log.removed);
+            assert_eq!(log.block_hash, Some(old_hash));
+            assert_eq!((log.transaction_index, log.log_index), (Some(0), Some(log_index)));
+        }
+
+        // the old block's logs are reverted before the new block's logs are delivered
+        for log_index in 0..2 {
+            let log = logs.next().await.unwrap();
+            assert!(log.removed);
+            assert_eq!(log.block_hash, Some(old_hash));
+            assert_eq!((log.transaction_index, log.log_index), (Some(0), Some(log_index)));
+        }
+        for (tx_index, tx_hash) in new_tx_hashes.into_iter().enumerate() {
+            let log = logs.next().await.unwrap();
+            assert!(!log.removed);
+            assert_eq!((log.block_hash, log.block_number), (Some(new_hash), Some(1)));
+            assert_eq!(log.transaction_hash, Some(tx_hash));
+            assert_eq!(log.transaction_index, Some(tx_index as u64));
+            assert_eq!(log.log_index, Some(tx_index as u64));
+        }
+    }
+
+    #[tokio::test]
+    async fn log_stream_replays_filtered_logs_of_reverted_blocks() {
+        let chain_events = TestCanonStateSubscriptions::default();
+        let pubsub = EthPubSub::new(
+            NoopProvider::default(),
+            testing_pool(),
+            chain_events.clone(),
+            NoopNetwork::default(),
+        );
+        let watched = Address::with_last_byte(1);
+        let filter = Filter::new().address(watched);
+        let mut logs = Box::pin(pubsub.inner.log_stream(FilteredParams::new(Some(filter))));
+
+        // two blocks that each emitted a watched and an unrelated log
+        let block = |number, extra_data: u8, addresses: &[Address]| {
+            let body = (0..addresses.len() as u64)
+                .map(|nonce| {
+                    TransactionSigned::from_transaction_and_signature(
+                        PrimitiveTransaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+                        Signature::default(),
+                    )
+                })
+                .collect();
+            let header = PrimitiveHeader {
+                number,
+                extra_data: vec![extra_data].into(),
+                ..Default::default()
+            };
+            let block = SealedBlockWithSenders {
+                block: Block { header, body, ..Default::default() }.seal_slow(),
+                senders: vec![Address::ZERO; addresses.len()],
+            };
+            let receipts = addresses
+                .iter()
+                .map(|&address| {
+                    let log = reth_primitives::Log::new_unchecked(address, vec![], Bytes::new());
+                    Some(Receipt { logs: vec![log], ..Default::default() })
+                })
+                .collect::<Vec<_>>();
+            (block, receipts)
+        };
+        let chain = |blocks: Vec<(SealedBlockWithSenders, Vec<Option<Receipt>>)>| {
+            let first = blocks[0].0.number;
+            let (blocks, receipts): (Vec<_>, Vec<_>) = blocks.into_iter().unzip();
+            let outcome = ExecutionOutcome::new(
+                Default::default(),
+                Receipts::from_iter(receipts),
+                first,
+                vec![],
+            );
+            Arc::new(Chain::new(blocks, outcome, None))
+        };
+        let old = chain(vec![
+            block(1, 0, &[watched, Address::ZERO]),
+            block(2, 0, &[Address::ZERO, watched]),
+        ]);
+        let new = chain(vec![block(1, 1, &[watched])]);
+        let old_hashes = old.blocks_iter().map(|block| block.hash()).collect::<Vec<_>>();
+        let new_hash = new.tip().hash();
+
+        chain_events.add_next_commit(old.clone());
+        chain_events.add_next_reorg(old, new);
+
+        for removed in [false, true] {
+            for (block_hash, tx_index) in old_hashes.iter().zip([0, 1]) {
+                let log = logs.next().await.unwrap();
+                assert_eq!(log.removed, removed);
+                assert_eq!(log.inner.address, watched);
+                assert_eq!(
+                    (log.block_hash, log.transaction_index),
+                    (Some(*block_hash), Some(tx_index))
+                );
+            }
+        }
+        let log = logs.next().await.unwrap();
+        assert!(!log.removed);
+        assert_eq!(log.block_hash, Some(new_hash));
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers for testing the `eth` namespace API.
+
+use reth_evm_ethereum::EthEvmConfig;
+use reth_network_api::noop::NoopNetwork;
+use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
+use reth_provider::{
+    BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+};
+use reth_rpc_eth_types::{
+    EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasCaps, GasPriceOracle,
+    GasPriceOracleConfig,
+};
+use reth_rpc_server_types::constants::{
+    DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PENDING_BLOCK_CACHE_SIZE,
+};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+use crate::EthApi;
+
+/// Builds an [`EthApi`] for tests.
+///
+/// Uses a [`testing_pool`], a [`NoopNetwork`], the block gas limit as gas cap and the default gas
+/// price oracle and caches, unless configured otherwise.
+#[derive(Debug)]
+pub(crate) struct TestEthApiBuilder<Provider, Network = NoopNetwork> {
+    provider: Provider,
+    pool: TestPool,
+    network: Network,
+    gas_cap: GasCaps,
+    gpo_config: GasPriceOracleConfig,
+}
+
+impl<Provider> TestEthApiBuilder<Provider> {
+    /// Creates a new builder for an [`EthApi`] over the given provider.
+    pub(crate) fn new(provider: Provider) -> Self {
+        Self {
+            provider,
+            pool: testing_pool(),
+            network: NoopNetwork::default(),
+            gas_cap: ETHEREUM_BLOCK_GAS_LIMIT.into(),
+            gpo_config: Default::default(),
+        }
+    }
+}
+
+impl<Provider, Network> TestEthApiBuilder<Provider, Network> {
+    /// Sets the transaction pool, e.g. to add transactions after the [`EthApi`] is built.
+    pub(crate) fn pool(mut self, pool: TestPool) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Sets the network.
+    pub(crate) fn network<N>(self, network: N) -> TestEthApiBuilder<Provider, N> {
+        let Self { provider, pool, gas_cap, gpo_config, .. } = self;
+        TestEthApiBuilder { provider, pool, network, gas_cap, gpo_config }
+    }
+
+    /// Sets the gas cap of calls.
+    pub(crate) fn gas_cap(mut self, gas_cap: impl Into<GasCaps>) -> Self {
+        self.gas_cap = gas_cap.into();
+        self
+    }
+
+    /// Sets the config of the gas price oracle.
+    pub(crate) const fn gpo_config(mut self, gpo_config: GasPriceOracleConfig) -> Self {
+        self.gpo_config = gpo_config;
+        self
+    }
+
+    /// Builds the [`EthApi`].
+    pub(crate) fn build(self) -> EthApi<Provider, TestPool, Network, EthEvmConfig>
+    where
+        Provider: BlockReaderIdExt
+            + BlockReader
+            + ChainSpecProvider
+            + EvmEnvProvider
+            + StateProviderFactory
+            + Unpin
+            + Clone
+            + 'static,
+    {
+        let Self { provider, pool, network, gas_cap, gpo_config } = self;
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let fee_history_cache =
+            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
+
+        EthApi::new(
+            provider.clone(),
+            pool,
+            network,
+            cache.clone(),
+            GasPriceOracle::new(provider, gpo_config, cache),
+            gas_cap,
+            DEFAULT_MAX_SIMULATE_BLOCKS,
+            DEFAULT_PENDING_BLOCK_CACHE_SIZE,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            fee_history_cache,
+            evm_config,
+            None,
+        )
+    }
+}
@@ -24,7 +24,7 @@ use reth_rpc_types::{
         parity::*,
         tracerequest::TraceCallRequest,
     },
-    BlockError, BlockOverrides, Index, TransactionRequest,
+    BlockError, BlockOverrides, Index, TransactionInfo, TransactionRequest,
 };
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{
@@ -85,21 +85,28 @@ where
         let overrides =
             EvmOverrides::new(trace_request.state_overrides, trace_request.block_overrides);
         let mut inspector = TracingInspector::new(config);
+        let gas_limit = self.eth_api().trace_gas_limit();
         let this = self.clone();
         self.eth_api()
-            .spawn_with_call_at(trace_request.call, at, overrides, move |db, env| {
-                // wrapper is hack to get around 'higher-ranked lifetime error', see
-                // <https://github.com/rust-lang/rust/issues/100013>
-                let db = db.0;
-
-                let (res, _) = this.eth_api().inspect(&mut *db, env, &mut inspector)?;
-                let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
-                    &res,
-                    &trace_request.trace_types,
-                    &db,
-                )?;
-                Ok(trace_res)
-            })
+            .spawn_with_call_at_capped(
+                trace_request.call,
+                at,
+                overrides,
+                gas_limit,
+                move |db, env| {
+                    // wrapper is hack to get around 'higher-ranked lifetime error', see
+                    // <https://github.com/rust-lang/rust/issues/100013>
+                    let db = db.0;
+
+                    let (res, _) = this.eth_api().inspect(&mut *db, env, &mut inspector)?;
+                    let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
+                        &res,
+                        &trace_request.trace_types,
+                        &db,
+                    )?;
+                    Ok(trace_res)
+                },
+            )
             .await
     }
 
@@ -142,7 +149,7 @@ where
         let at = block_id.unwrap_or(BlockId::pending());
         let (cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
 
-        let gas_limit = self.inner.eth_api.call_gas_limit();
+        let gas_limit = self.inner.eth_api.trace_gas_limit();
         let this = self.clone();
         // execute all transactions on top of each other and record the traces
         self.eth_api()
@@ -351,11 +358,7 @@ where
                 hash,
                 TracingInspectorConfig::default_parity(),
                 move |tx_info, inspector, res, _| {
-                    let traces = inspector
-                        .with_transaction_gas_used(res.result.gas_used())
-                        .into_parity_builder()
-                        .into_localized_transaction_traces(tx_info);
-                    Ok(traces)
+                    Ok(localized_transaction_traces(inspector, res.result.gas_used(), tx_info))
                 },
             )
             .await
@@ -370,11 +373,7 @@ where
             block_id,
             TracingInspectorConfig::default_parity(),
             |tx_info, inspector, res, _, _| {
-                let traces = inspector
-                    .with_transaction_gas_used(res.gas_used())
-                    .into_parity_builder()
-                    .into_localized_transaction_traces(tx_info);
-                Ok(traces)
+                Ok(localized_transaction_traces(inspector, res.gas_used(), tx_info))
             },
         );
 
@@ -689,6 +688,19 @@ struct TraceApiInner<Provider, Eth> {
     blocking_task_guard: BlockingTaskGuard,
 }
 
+/// Returns the flattened parity traces of a transaction, with their trace addresses and number of
+/// subtraces, from the inspector that traced its execution.
+fn localized_transaction_traces(
+    inspector: TracingInspector,
+    gas_used: u64,
+    tx_info: TransactionInfo,
+) -> Vec<LocalizedTransactionTrace> {
+    inspector
+        .with_transaction_gas_used(gas_used)
+        .into_parity_builder()
+        .into_localized_transaction_traces(tx_info)
+}
+
 /// Helper to construct a [`LocalizedTransactionTrace`] that describes a reward to the block
 /// beneficiary.
 fn reward_trace(header: &Header, reward: RewardAction) -> LocalizedTransactionTrace {
@@ -706,3 +718,146 @@ fn reward_trace(header: &Header, reward: RewardAction) -> LocalizedTransactionTr
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use reth_chainspec::MAINNET;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        constants::{ETHEREUM_BLOCK_GAS_LIMIT, ETH_TO_WEI},
+        Address, TxKind,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_eth_types::{
+        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    };
+    use reth_rpc_server_types::constants::{
+        DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PENDING_BLOCK_CACHE_SIZE,
+    };
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_transaction_pool::test_utils::testing_pool;
+    use revm::{
+        db::EmptyDB,
+        inspector_handle_register,
+        primitives::{AccountInfo, Bytecode},
+    };
+
+    use super::*;
+    use crate::EthApi;
+
+    /// The block at which the merge activated on mainnet.
+    const PARIS_BLOCK: u64 = 15_537_394;
+
+    #[tokio::test]
+    async fn reward_traces_only_before_merge() {
+        let provider = MockEthProvider { chain_spec: MAINNET.clone(), ..Default::default() };
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider.clone(), Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            DEFAULT_MAX_SIMULATE_BLOCKS,
+            DEFAULT_PENDING_BLOCK_CACHE_SIZE,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+        let trace_api = TraceApi::new(provider, eth_api, BlockingTaskGuard::new(1));
+
+        let ommer = Header { number: 99, beneficiary: Address::random(), ..Default::default() };
+        let header = Header { number: 100, beneficiary: Address::random(), ..Default::default() };
+        let base_block_reward = trace_api.calculate_base_block_reward(&header).unwrap().unwrap();
+        assert_eq!(base_block_reward, 5 * ETH_TO_WEI);
+
+        let traces = trace_api.extract_reward_traces(&header, &[ommer.clone()], base_block_reward);
+        let rewards = traces
+            .iter()
+            .map(|trace| match &trace.trace.action {
+                Action::Reward(reward) => (reward.author, reward.reward_type, reward.value),
+                action => panic!("expected a reward, got {action:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rewards,
+            vec![
+                (header.beneficiary, RewardType::Block, U256::from(5 * ETH_TO_WEI * 33 / 32)),
+                (ommer.beneficiary, RewardType::Uncle, U256::from(5 * ETH_TO_WEI * 7 / 8)),
+            ]
+        );
+        assert!(traces.iter().all(|trace| trace.trace.trace_address.is_empty()));
+
+        // post-merge blocks, whose total difficulty reached the terminal total difficulty, have no
+        // block reward
+        let terminal_total_difficulty = U256::from(58_750_000_000_000_000_000_000_u128);
+        let last_pow_block = Header {
+            number: PARIS_BLOCK - 1,
+            difficulty: terminal_total_difficulty,
+            ..Default::default()
+        };
+        trace_api.provider().add_header(last_pow_block.hash_slow(), last_pow_block);
+        let header = Header { number: PARIS_BLOCK, ..Default::default() };
+        assert_eq!(trace_api.calculate_base_block_reward(&header).unwrap(), None);
+    }
+
+    #[test]
+    fn nested_call_trace_addresses() {
+        let (caller, outer, inner) = (Address::random(), Address::random(), Address::random());
+
+        // the outer contract calls the inner contract, which stops
+        let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+        code.extend_from_slice(inner.as_slice());
+        code.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            outer,
+            AccountInfo { code: Some(Bytecode::new_raw(code.into())), ..Default::default() },
+        );
+        db.insert_account_info(
+            inner,
+            AccountInfo {
+                code: Some(Bytecode::new_raw(Bytes::from_static(&[0x00]))),
+                ..Default::default()
+            },
+        );
+
+        let mut inspector = TracingInspector::new(TracingInspectorConfig::default_parity());
+        let mut evm = revm::Evm::builder()
+            .with_db(db)
+            .with_external_context(&mut inspector)
+            .append_handler_register(inspector_handle_register)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(outer);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+        let gas_used = evm.transact().unwrap().result.gas_used();
+        drop(evm);
+
+        let tx_info = TransactionInfo {
+            hash: Some(B256::random()),
+            index: Some(0),
+            block_hash: Some(B256::random()),
+            block_number: Some(1),
+            base_fee: None,
+        };
+        let traces = localized_transaction_traces(inspector, gas_used, tx_info);
+        assert_eq!(traces.len(), 2);
+        assert!(traces.iter().all(|trace| trace.transaction_hash == tx_info.hash));
+
+        let Action::Call(call) = &traces[0].trace.action else { panic!("expected a call") };
+        assert_eq!((call.from, call.to), (caller, outer));
+        assert!(traces[0].trace.trace_address.is_empty());
+        assert_eq!(traces[0].trace.subtraces, 1);
+
+        let Action::Call(call) = &traces[1].trace.action else { panic!("expected a call") };
+        assert_eq!((call.from, call.to), (outer, inner));
+        assert_eq!(traces[1].trace.trace_address, vec![0]);
+        assert_eq!(traces[1].trace.subtraces, 0);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     AccountReader, BlockHashReader, ExecutionDataProvider, StateProvider, StateRootProvider,
 };
-use reth_primitives::{Account, Address, BlockNumber, Bytecode, B256};
+use reth_primitives::{keccak256, Account, Address, BlockNumber, Bytecode, StorageValue, B256};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{updates::TrieUpdates, AccountProof};
 use revm::db::BundleState;
+use std::collections::BTreeMap;
 
 /// A state provider that resolves to data from either a wrapped [`crate::ExecutionOutcome`]
 /// or an underlying state provider.
@@ -111,4 +112,38 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProvider for BundleStat
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
     }
+
+    fn storage_range(
+        &self,
+        account: Address,
+        start_key: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, StorageValue)>> {
+        let Some(bundle_account) =
+            self.block_execution_data_provider.execution_outcome().bundle.account(&account)
+        else {
+            return self.state_provider.storage_range(account, start_key, limit)
+        };
+
+        let changed = bundle_account
+            .storage
+            .iter()
+            .map(|(slot, value)| (keccak256(B256::new(slot.to_be_bytes())), value.present_value))
+            .filter(|(hashed_slot, _)| *hashed_slot >= start_key)
+            .collect::<BTreeMap<_, _>>();
+
+        // every changed slot can shadow at most one slot of the inner state, so reading that many
+        // more slots is enough to fill the range
+        let mut slots = if bundle_account.status.was_destroyed() {
+            BTreeMap::new()
+        } else {
+            self.state_provider
+                .storage_range(account, start_key, limit.saturating_add(changed.len()))?
+                .into_iter()
+                .collect()
+        };
+        slots.extend(changed);
+
+        Ok(slots.into_iter().filter(|(_, value)| !value.is_zero()).take(limit).collect())
+    }
 }
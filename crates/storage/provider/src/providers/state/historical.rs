@@ -1,5 +1,8 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{latest::hashed_storage_range, macros::delegate_provider_impls},
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
 use reth_db::{tables, BlockNumberList};
@@ -14,7 +17,10 @@ use reth_primitives::{
     StorageValue, B256,
 };
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{
+    hashed_cursor::HashedPostStateCursorFactory, updates::TrieUpdates, AccountProof,
+    HashedPostState,
+};
 use revm::db::BundleState;
 use std::fmt::Debug;
 
@@ -307,9 +313,28 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         self.tx.get::<tables::Bytecodes>(code_hash).map_err(Into::into)
     }
 
-    /// Get account and storage proofs.
-    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    /// Get account and storage proofs by reverting the latest state to this block.
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        let revert_state = self.revert_state()?;
+        revert_state
+            .account_proof(self.tx, address, keys)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    /// Get the storage slots by reverting the latest hashed storage to this block.
+    fn storage_range(
+        &self,
+        account: Address,
+        start_key: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, StorageValue)>> {
+        let revert_state = self.revert_state()?.into_sorted();
+        hashed_storage_range(
+            HashedPostStateCursorFactory::new(self.tx, &revert_state),
+            account,
+            start_key,
+            limit,
+        )
     }
 }
 
@@ -411,8 +436,9 @@ mod tests {
         models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
         transaction::{DbTx, DbTxMut},
     };
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{address, b256, keccak256, Account, Address, StorageEntry, B256, U256};
     use reth_storage_errors::provider::ProviderError;
+    use reth_trie::test_utils::state_root;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -680,6 +706,10 @@ mod tests {
             provider.storage_history_lookup(ADDRESS, STORAGE),
             Err(ProviderError::StateAtBlockPruned(provider.block_number))
         );
+        assert_eq!(
+            provider.proof(ADDRESS, &[STORAGE]),
+            Err(ProviderError::StateAtBlockPruned(provider.block_number))
+        );
 
         // provider block_number == lowest available block number,
         // i.e. state at provider block is available
@@ -715,4 +745,59 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_proof() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let other_slot = B256::with_last_byte(2);
+        let acc_at2 = Account { nonce: 1, balance: U256::from(10), bytecode_hash: None };
+        let acc_plain = Account { nonce: 2, balance: U256::from(20), bytecode_hash: None };
+        let higher_acc = Account { nonce: 1, balance: U256::from(5), bytecode_hash: None };
+        let entry_at2 = StorageEntry { key: STORAGE, value: U256::from(5) };
+        let entry_plain = StorageEntry { key: STORAGE, value: U256::from(7) };
+        let other_entry = StorageEntry { key: other_slot, value: U256::from(3) };
+
+        // block 2 changed the account and one of its storage slots
+        tx.put::<tables::CanonicalHeaders>(2, B256::ZERO).unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            2,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at2) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>((2, ADDRESS).into(), entry_at2).unwrap();
+
+        // setup hashed state
+        tx.put::<tables::HashedAccounts>(keccak256(ADDRESS), acc_plain).unwrap();
+        tx.put::<tables::HashedAccounts>(keccak256(HIGHER_ADDRESS), higher_acc).unwrap();
+        for entry in [entry_plain, other_entry] {
+            let hashed_entry = StorageEntry { key: keccak256(entry.key), value: entry.value };
+            tx.put::<tables::HashedStorages>(keccak256(ADDRESS), hashed_entry).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let root_at2 = state_root([
+            (ADDRESS, (acc_at2, vec![(STORAGE, entry_at2.value), (other_slot, other_entry.value)])),
+            (HIGHER_ADDRESS, (higher_acc, vec![])),
+        ]);
+        let latest_root = state_root([
+            (
+                ADDRESS,
+                (acc_plain, vec![(STORAGE, entry_plain.value), (other_slot, other_entry.value)]),
+            ),
+            (HIGHER_ADDRESS, (higher_acc, vec![])),
+        ]);
+
+        // run
+        let proof = HistoricalStateProviderRef::new(&tx, 2, static_file_provider)
+            .proof(ADDRESS, &[STORAGE])
+            .unwrap();
+        assert_eq!(proof.info, Some(acc_at2));
+        assert_eq!(proof.storage_proofs[0].value, entry_at2.value);
+        assert_eq!(proof.verify(root_at2), Ok(()));
+        assert!(proof.verify(latest_root).is_err());
+    }
 }
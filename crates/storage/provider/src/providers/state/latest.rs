@@ -8,10 +8,16 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{
-    Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey, StorageValue, B256,
+    keccak256, Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey,
+    StorageValue, B256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::{proof::Proof, updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory},
+    proof::Proof,
+    updates::TrieUpdates,
+    AccountProof, HashedPostState,
+};
 use revm::db::BundleState;
 
 /// State provider over latest state that takes tx reference.
@@ -116,6 +122,40 @@ impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
             .account_proof(address, slots)
             .map_err(Into::<reth_db::DatabaseError>::into)?)
     }
+
+    fn storage_range(
+        &self,
+        account: Address,
+        start_key: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, StorageValue)>> {
+        hashed_storage_range(self.tx, account, start_key, limit)
+    }
+}
+
+/// Walks the hashed storage of the account from the given hashed key, returning at most `limit`
+/// slots.
+pub(crate) fn hashed_storage_range<CF: HashedCursorFactory>(
+    cursor_factory: CF,
+    account: Address,
+    start_key: B256,
+    limit: usize,
+) -> ProviderResult<Vec<(B256, StorageValue)>> {
+    let mut slots = Vec::new();
+    if limit == 0 {
+        return Ok(slots)
+    }
+
+    let mut cursor = cursor_factory.hashed_storage_cursor(keccak256(account))?;
+    let mut entry = cursor.seek(start_key)?;
+    while let Some(slot) = entry {
+        slots.push(slot);
+        if slots.len() == limit {
+            break
+        }
+        entry = cursor.next()?;
+    }
+    Ok(slots)
 }
 
 /// State provider for the latest state.
@@ -146,10 +186,44 @@ delegate_provider_impls!(LatestStateProvider<TX> where [TX: DbTx]);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{test_utils::create_test_provider_factory, StaticFileProviderFactory};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives::{StorageEntry, U256};
 
     const fn assert_state_provider<T: StateProvider>() {}
     #[allow(dead_code)]
     const fn assert_latest_state_provider<T: DbTx>() {
         assert_state_provider::<LatestStateProvider<T>>();
     }
+
+    #[test]
+    fn latest_storage_range() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+
+        let address = Address::with_last_byte(1);
+        let mut slots = (1..=3u8)
+            .map(|slot| StorageEntry {
+                key: keccak256(B256::with_last_byte(slot)),
+                value: U256::from(slot),
+            })
+            .collect::<Vec<_>>();
+        slots.sort_unstable_by_key(|entry| entry.key);
+        for entry in &slots {
+            tx.put::<tables::HashedStorages>(keccak256(address), *entry).unwrap();
+        }
+        // the storage of other accounts isn't part of the range
+        let other = StorageEntry { key: B256::ZERO, value: U256::from(1) };
+        tx.put::<tables::HashedStorages>(keccak256(Address::with_last_byte(2)), other).unwrap();
+
+        let provider = LatestStateProviderRef::new(&tx, factory.static_file_provider());
+        let expected = slots.iter().map(|entry| (entry.key, entry.value)).collect::<Vec<_>>();
+        assert_eq!(provider.storage_range(address, B256::ZERO, 10).unwrap(), expected);
+        assert_eq!(provider.storage_range(address, slots[1].key, 1).unwrap(), expected[1..2]);
+        assert!(provider.storage_range(address, B256::ZERO, 0).unwrap().is_empty());
+        assert!(provider
+            .storage_range(Address::with_last_byte(3), B256::ZERO, 10)
+            .unwrap()
+            .is_empty());
+    }
 }
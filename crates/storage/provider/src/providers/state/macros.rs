@@ -44,6 +44,7 @@ macro_rules! delegate_provider_impls {
             StateProvider $(where [$($generics)*])?{
                 fn storage(&self, account: reth_primitives::Address, storage_key: reth_primitives::StorageKey) -> reth_storage_errors::provider::ProviderResult<Option<reth_primitives::StorageValue>>;
                 fn proof(&self, address: reth_primitives::Address, keys: &[reth_primitives::B256]) -> reth_storage_errors::provider::ProviderResult<reth_trie::AccountProof>;
+                fn storage_range(&self, account: reth_primitives::Address, start_key: reth_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<Vec<(reth_primitives::B256, reth_primitives::StorageValue)>>;
                 fn bytecode_by_hash(&self, code_hash: reth_primitives::B256) -> reth_storage_errors::provider::ProviderResult<Option<reth_primitives::Bytecode>>;
             }
         );
@@ -16,7 +16,7 @@ use reth_primitives::{
     TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::{updates::TrieUpdates, AccountProof};
+use reth_trie::{updates::TrieUpdates, AccountProof, StorageProof};
 use revm::{
     db::BundleState,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
@@ -36,6 +36,10 @@ pub struct MockEthProvider {
     pub headers: Arc<Mutex<HashMap<B256, Header>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
+    /// Local receipt store, keyed by block hash
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<Receipt>>>>,
+    /// Local pending block
+    pub pending_block: Arc<Mutex<Option<SealedBlockWithSenders>>>,
     /// Local chain spec
     pub chain_spec: Arc<ChainSpec>,
 }
@@ -46,6 +50,8 @@ impl Default for MockEthProvider {
             blocks: Default::default(),
             headers: Default::default(),
             accounts: Default::default(),
+            receipts: Default::default(),
+            pending_block: Default::default(),
             chain_spec: Arc::new(reth_chainspec::ChainSpecBuilder::mainnet().build()),
         }
     }
@@ -103,6 +109,19 @@ impl MockEthProvider {
         }
     }
 
+    /// Returns the blocks of local block store ordered by number, which is the order of their
+    /// transaction numbers.
+    fn blocks_by_number(&self) -> Vec<Block> {
+        let mut blocks = self.blocks.lock().values().cloned().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.number);
+        blocks
+    }
+
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, block_hash: B256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(block_hash, receipts);
+    }
+
     /// Add header to local header store
     pub fn add_header(&self, hash: B256, header: Header) {
         self.headers.lock().insert(hash, header);
@@ -126,6 +145,11 @@ impl MockEthProvider {
             self.add_account(address, account)
         }
     }
+
+    /// Set the pending block, or clear it if `None`
+    pub fn set_pending_block(&self, block: Option<SealedBlockWithSenders>) {
+        *self.pending_block.lock() = block;
+    }
 }
 
 impl HeaderProvider for MockEthProvider {
@@ -193,9 +217,9 @@ impl ChainSpecProvider for MockEthProvider {
 
 impl TransactionsProvider for MockEthProvider {
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
-        let lock = self.blocks.lock();
-        let tx_number = lock
-            .values()
+        let tx_number = self
+            .blocks_by_number()
+            .iter()
             .flat_map(|block| &block.body)
             .position(|tx| tx.hash() == tx_hash)
             .map(|pos| pos as TxNumber);
@@ -204,8 +228,8 @@ impl TransactionsProvider for MockEthProvider {
     }
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
-        let lock = self.blocks.lock();
-        let transaction = lock.values().flat_map(|block| &block.body).nth(id as usize).cloned();
+        let transaction =
+            self.blocks_by_number().iter().flat_map(|block| &block.body).nth(id as usize).cloned();
 
         Ok(transaction)
     }
@@ -214,9 +238,9 @@ impl TransactionsProvider for MockEthProvider {
         &self,
         id: TxNumber,
     ) -> ProviderResult<Option<TransactionSignedNoHash>> {
-        let lock = self.blocks.lock();
-        let transaction = lock
-            .values()
+        let transaction = self
+            .blocks_by_number()
+            .iter()
             .flat_map(|block| &block.body)
             .nth(id as usize)
             .map(|tx| Into::<TransactionSignedNoHash>::into(tx.clone()));
@@ -346,8 +370,17 @@ impl ReceiptProvider for MockEthProvider {
         Ok(None)
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
+        let block_hash = match block {
+            BlockHashOrNumber::Hash(hash) => Some(hash),
+            BlockHashOrNumber::Number(number) => self.block_hash(number)?,
+        };
+        if let Some(receipts) = block_hash.and_then(|hash| self.receipts.lock().get(&hash).cloned())
+        {
+            return Ok(Some(receipts))
+        }
+        // blocks without transactions have no receipts, so they don't need to be stored
+        Ok(self.block(block)?.filter(|block| block.body.is_empty()).map(|_| Vec::new()))
     }
 
     fn receipts_by_tx_range(
@@ -417,7 +450,7 @@ impl BlockNumReader for MockEthProvider {
 
 impl BlockIdReader for MockEthProvider {
     fn pending_block_num_hash(&self) -> ProviderResult<Option<reth_primitives::BlockNumHash>> {
-        Ok(None)
+        Ok(self.pending_block.lock().as_ref().map(|block| block.num_hash()))
     }
 
     fn safe_block_num_hash(&self) -> ProviderResult<Option<reth_primitives::BlockNumHash>> {
@@ -447,31 +480,46 @@ impl BlockReader for MockEthProvider {
     }
 
     fn pending_block(&self) -> ProviderResult<Option<SealedBlock>> {
-        Ok(None)
+        Ok(self.pending_block.lock().as_ref().map(|block| block.block.clone()))
     }
 
     fn pending_block_with_senders(&self) -> ProviderResult<Option<SealedBlockWithSenders>> {
-        Ok(None)
+        Ok(self.pending_block.lock().clone())
     }
 
     fn pending_block_and_receipts(&self) -> ProviderResult<Option<(SealedBlock, Vec<Receipt>)>> {
         Ok(None)
     }
 
-    fn ommers(&self, _id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Header>>> {
-        Ok(None)
+    fn ommers(&self, id: BlockHashOrNumber) -> ProviderResult<Option<Vec<Header>>> {
+        Ok(self.block(id)?.map(|block| {
+            // blocks after the merge don't have ommers
+            if self.chain_spec.final_paris_total_difficulty(block.number).is_some() {
+                Vec::new()
+            } else {
+                block.ommers
+            }
+        }))
     }
 
-    fn block_body_indices(&self, _num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+    fn block_body_indices(&self, num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+        let mut first_tx_num = 0;
+        for block in self.blocks_by_number() {
+            let tx_count = block.body.len() as u64;
+            if block.number == num {
+                return Ok(Some(StoredBlockBodyIndices { first_tx_num, tx_count }))
+            }
+            first_tx_num += tx_count;
+        }
         Ok(None)
     }
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -511,6 +559,10 @@ impl BlockReaderIdExt for MockEthProvider {
         self.header_by_id(id)?.map_or_else(|| Ok(None), |h| Ok(Some(h.seal_slow())))
     }
 
+    fn pending_header(&self) -> ProviderResult<Option<SealedHeader>> {
+        Ok(self.pending_block.lock().as_ref().map(|block| block.header.clone()))
+    }
+
     fn header_by_id(&self, id: BlockId) -> ProviderResult<Option<Header>> {
         match self.block_by_id(id)? {
             None => Ok(None),
@@ -567,8 +619,31 @@ impl StateProvider for MockEthProvider {
         }))
     }
 
-    fn proof(&self, address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Ok(AccountProof::new(address))
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        let mut proof = AccountProof::new(address);
+        proof.storage_proofs = keys.iter().copied().map(StorageProof::new).collect();
+        Ok(proof)
+    }
+
+    fn storage_range(
+        &self,
+        account: Address,
+        start_key: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, StorageValue)>> {
+        let lock = self.accounts.lock();
+        let Some(account) = lock.get(&account) else { return Ok(Vec::new()) };
+
+        let mut slots = account
+            .storage
+            .iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(key, value)| (keccak256(key), *value))
+            .filter(|(hashed_key, _)| *hashed_key >= start_key)
+            .collect::<Vec<_>>();
+        slots.sort_unstable_by_key(|(hashed_key, _)| *hashed_key);
+        slots.truncate(limit);
+        Ok(slots)
     }
 }
 
@@ -588,14 +663,24 @@ impl EvmEnvProvider for MockEthProvider {
 
     fn fill_env_with_header<EvmConfig>(
         &self,
-        _cfg: &mut CfgEnvWithHandlerCfg,
-        _block_env: &mut BlockEnv,
-        _header: &Header,
+        cfg: &mut CfgEnvWithHandlerCfg,
+        block_env: &mut BlockEnv,
+        header: &Header,
         _evm_config: EvmConfig,
     ) -> ProviderResult<()>
     where
         EvmConfig: ConfigureEvmEnv,
     {
+        let total_difficulty = self
+            .header_td_by_number(header.number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(header.number.into()))?;
+        EvmConfig::fill_cfg_and_block_env(
+            cfg,
+            block_env,
+            &self.chain_spec,
+            header,
+            total_difficulty,
+        );
         Ok(())
     }
 
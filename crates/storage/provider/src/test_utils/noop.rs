@@ -329,6 +329,15 @@ impl StateProvider for NoopProvider {
     fn proof(&self, address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Ok(AccountProof::new(address))
     }
+
+    fn storage_range(
+        &self,
+        _account: Address,
+        _start_key: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, StorageValue)>> {
+        Ok(Vec::new())
+    }
 }
 
 impl EvmEnvProvider for NoopProvider {
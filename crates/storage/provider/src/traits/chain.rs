@@ -2,8 +2,9 @@
 
 use crate::{BlockReceipts, Chain};
 use auto_impl::auto_impl;
-use reth_primitives::SealedBlockWithSenders;
+use reth_primitives::{SealedBlock, SealedBlockWithSenders};
 use std::{
+    collections::VecDeque,
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
@@ -32,6 +33,13 @@ pub trait CanonStateSubscriptions: Send + Sync {
             st: BroadcastStream::new(self.subscribe_to_canonical_state()),
         }
     }
+
+    /// Convenience method to get a stream of the blocks committed to the canonical chain.
+    ///
+    /// See [`CommittedBlocksStream`].
+    fn committed_blocks_stream(&self) -> CommittedBlocksStream {
+        CommittedBlocksStream { st: self.canonical_state_stream(), pending: VecDeque::new() }
+    }
 }
 
 /// A Stream of [CanonStateNotification].
@@ -59,6 +67,41 @@ impl Stream for CanonStateNotificationStream {
     }
 }
 
+/// A Stream of the blocks committed by [`CanonStateNotification`]s, in the order they are
+/// committed.
+///
+/// The blocks of the new chain of a reorg are yielded as well, reverted blocks are skipped.
+#[derive(Debug)]
+#[pin_project::pin_project]
+pub struct CommittedBlocksStream {
+    #[pin]
+    st: CanonStateNotificationStream,
+    /// Committed blocks of the last notification that weren't yielded yet.
+    pending: VecDeque<Arc<SealedBlock>>,
+}
+
+impl Stream for CommittedBlocksStream {
+    type Item = Arc<SealedBlock>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if let Some(block) = this.pending.pop_front() {
+                return Poll::Ready(Some(block))
+            }
+            match ready!(this.st.as_mut().poll_next(cx)) {
+                Some(notification) => this.pending.extend(
+                    notification
+                        .committed()
+                        .blocks_iter()
+                        .map(|block| Arc::new(block.block.clone())),
+                ),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
 /// Chain action that is triggered when a new block is imported or old block is reverted.
 /// and will return all [`crate::ExecutionOutcome`] and
 /// [`reth_primitives::SealedBlockWithSenders`] of both reverted and committed blocks.
@@ -139,3 +182,45 @@ impl CanonStateNotification {
         receipts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExecutionOutcome;
+    use reth_primitives::{Block, Header};
+    use tokio_stream::StreamExt;
+
+    struct TestSubscriptions(CanonStateNotificationSender);
+
+    impl CanonStateSubscriptions for TestSubscriptions {
+        fn subscribe_to_canonical_state(&self) -> CanonStateNotifications {
+            self.0.subscribe()
+        }
+    }
+
+    fn chain(numbers: impl IntoIterator<Item = u64>) -> Arc<Chain> {
+        let blocks = numbers.into_iter().map(|number| SealedBlockWithSenders {
+            block: Block { header: Header { number, ..Default::default() }, ..Default::default() }
+                .seal_slow(),
+            senders: Vec::new(),
+        });
+        Arc::new(Chain::new(blocks, ExecutionOutcome::default(), None))
+    }
+
+    #[tokio::test]
+    async fn committed_blocks_in_order() {
+        let (tx, _) = broadcast::channel(8);
+        let subscriptions = TestSubscriptions(tx.clone());
+        let blocks = subscriptions.committed_blocks_stream();
+
+        tx.send(CanonStateNotification::Commit { new: chain([1, 2]) }).unwrap();
+        // the reverted block 2 is skipped
+        tx.send(CanonStateNotification::Reorg { old: chain([2]), new: chain([2, 3]) }).unwrap();
+        tx.send(CanonStateNotification::Commit { new: chain([4]) }).unwrap();
+        drop(subscriptions);
+        drop(tx);
+
+        let numbers = blocks.map(|block| block.number).collect::<Vec<_>>().await;
+        assert_eq!(numbers, vec![1, 2, 2, 3, 4]);
+    }
+}
@@ -27,6 +27,22 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
     /// Get account and storage proofs.
     fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof>;
 
+    /// Get up to `limit` storage slots of the given account, starting at the slot with the given
+    /// hashed key.
+    ///
+    /// Slots are ordered by the keccak256 hash of their key, which is the order of the storage
+    /// trie, and returned as `(hashed_key, value)` pairs. Empty slots are skipped.
+    ///
+    /// Returns [`ProviderError::UnsupportedProvider`] by default.
+    fn storage_range(
+        &self,
+        _account: Address,
+        _start_key: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, StorageValue)>> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
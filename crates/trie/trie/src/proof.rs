@@ -1,7 +1,7 @@
 use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{TrieElement, TrieNodeIter},
-    prefix_set::PrefixSetMut,
+    prefix_set::{PrefixSetMut, TriePrefixSets},
     trie_cursor::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     walker::TrieWalker,
     HashBuilder, Nibbles,
@@ -23,12 +23,29 @@ pub struct Proof<'a, TX, H> {
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of prefix sets that have changes on top of the database state.
+    prefix_sets: TriePrefixSets,
 }
 
 impl<'a, TX> Proof<'a, TX, &'a TX> {
     /// Create a new [Proof] instance.
-    pub const fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+    pub fn new(tx: &'a TX) -> Self {
+        Self { tx, hashed_cursor_factory: tx, prefix_sets: TriePrefixSets::default() }
+    }
+}
+
+impl<'a, TX, H> Proof<'a, TX, H> {
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
+        Proof { tx: self.tx, hashed_cursor_factory, prefix_sets: self.prefix_sets }
+    }
+
+    /// Set the prefix sets. They have to contain all keys that were changed by the state the
+    /// hashed cursor factory overlays on top of the database, so that the intermediate nodes of
+    /// the changed paths are recomputed.
+    pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
     }
 }
 
@@ -52,7 +69,8 @@ where
             DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
+        let mut prefix_set =
+            PrefixSetMut::from(self.prefix_sets.account_prefix_set.iter().cloned());
         prefix_set.insert(target_nibbles.clone());
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
@@ -86,6 +104,11 @@ where
             }
         }
 
+        // The account doesn't exist, report the requested slots as empty
+        if account_proof.info.is_none() {
+            account_proof.storage_proofs = slots.iter().copied().map(StorageProof::new).collect();
+        }
+
         let _ = hash_builder.root();
 
         let proofs = hash_builder.take_proofs();
@@ -117,7 +140,14 @@ where
         }
 
         let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let mut prefix_set = PrefixSetMut::from(target_nibbles.clone());
+        if let Some(storage_prefix_set) = self.prefix_sets.storage_prefix_sets.get(&hashed_address)
+        {
+            for key in storage_prefix_set.iter() {
+                prefix_set.insert(key.clone());
+            }
+        }
+        let prefix_set = prefix_set.freeze();
         let trie_cursor = DatabaseStorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
             hashed_address,
@@ -162,7 +192,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{HashedPostState, HashedStorage, StateRoot};
     use once_cell::sync::Lazy;
     use reth_chainspec::{Chain, ChainSpec, HOLESKY, MAINNET};
     use reth_db_api::database::Database;
@@ -446,4 +476,62 @@ mod tests {
         similar_asserts::assert_eq!(account_proof, expected);
         assert_eq!(account_proof.verify(root), Ok(()));
     }
+
+    #[test]
+    fn testspec_nonexistent_account_storage_proofs() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        let root = insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let target = Address::with_last_byte(0x42);
+        let slots = Vec::from([B256::with_last_byte(1), B256::with_last_byte(3)]);
+
+        let provider = factory.provider().unwrap();
+        let account_proof = Proof::new(provider.tx_ref()).account_proof(target, &slots).unwrap();
+        assert_eq!(account_proof.info, None);
+        assert_eq!(account_proof.storage_root, EMPTY_ROOT_HASH);
+
+        assert_eq!(slots.len(), account_proof.storage_proofs.len());
+        for (proof, slot) in account_proof.storage_proofs.iter().zip(slots) {
+            assert_eq!(proof, &StorageProof::new(slot));
+        }
+        assert_eq!(account_proof.verify(root), Ok(()));
+    }
+
+    #[test]
+    fn testspec_overlay_proofs() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let changed = Address::from_str("0x2031f89b3ea8014eb51a78c316e42af3e0d7695f").unwrap();
+        let with_storage = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+        let created = Address::with_last_byte(0x42);
+        let unchanged = Address::from_str("0x62b0dd4aab2b1a0a04e279e2b828791a10755528").unwrap();
+        let slot = B256::with_last_byte(1);
+
+        let account = Account { nonce: 1, balance: U256::from(10), bytecode_hash: None };
+        let state = HashedPostState::default()
+            .with_accounts([
+                (keccak256(changed), Some(account)),
+                (keccak256(with_storage), Some(account)),
+                (keccak256(created), Some(account)),
+            ])
+            .with_storages([(
+                keccak256(with_storage),
+                HashedStorage::from_iter(false, [(keccak256(slot), U256::from(7))]),
+            )]);
+
+        let provider = factory.provider().unwrap();
+        let root = state.state_root(provider.tx_ref()).unwrap();
+
+        for target in [changed, with_storage, created, unchanged] {
+            let account_proof = state.account_proof(provider.tx_ref(), target, &[slot]).unwrap();
+            assert_eq!(account_proof.verify(root), Ok(()), "invalid proof for {target}");
+        }
+
+        let account_proof = state.account_proof(provider.tx_ref(), with_storage, &[slot]).unwrap();
+        assert_eq!(account_proof.info, Some(account));
+        assert_eq!(account_proof.storage_proofs[0].value, U256::from(7));
+    }
 }
@@ -4,7 +4,7 @@ use reth_chainspec::{
 use reth_network_peers::NodeRecord;
 use reth_primitives::{b256, B256};
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 pub const SHANGHAI_TIME: u64 = 1705996800;
 
@@ -22,6 +22,7 @@ pub(crate) fn bsc_chain_spec() -> Arc<ChainSpec> {
         )]),
         deposit_contract: None,
         base_fee_params: reth_chainspec::BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        block_time: Duration::from_secs(3),
         prune_delete_limit: 0,
     }
     .into()
@@ -4,7 +4,7 @@ use reth_chainspec::{
 use reth_discv4::NodeRecord;
 use reth_primitives::{b256, Head, B256};
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 const SHANGAI_BLOCK: u64 = 50523000;
 
@@ -27,6 +27,7 @@ pub(crate) fn polygon_chain_spec() -> Arc<ChainSpec> {
         ]),
         deposit_contract: None,
         base_fee_params: reth_chainspec::BaseFeeParamsKind::Constant(BaseFeeParams::ethereum()),
+        block_time: Duration::from_secs(2),
         prune_delete_limit: 0,
     }
     .into()